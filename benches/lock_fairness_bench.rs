@@ -0,0 +1,79 @@
+//! Tail latency of contended push - worst push time, across all writers.
+//! Unfair locks let the same writer re-acquire lock, while others wait.
+
+use rc_event_queue::mpmc::{EventQueue, EventReader, Settings};
+use rc_event_queue::{CleanupMode, LockBackend};
+use criterion::{Criterion, black_box, criterion_main, criterion_group};
+use std::time::{Duration, Instant};
+use std::thread;
+use std::sync::{Arc, Barrier};
+
+const PUSHES_PER_WRITER: usize = 20000;
+const WRITERS_COUNT: usize = 4;
+
+/// Returns sum of worst push latencies of each iteration.
+fn bench_worst_push<S: Settings + 'static>(iters: u64) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let event = EventQueue::<usize, S>::new();
+        let _reader = EventReader::new(&event);
+
+        let barrier = Arc::new(Barrier::new(WRITERS_COUNT));
+        let mut writer_threads = Vec::new();
+        for _ in 0..WRITERS_COUNT{
+            let event = event.clone();
+            let barrier = barrier.clone();
+            writer_threads.push(thread::spawn(move || {
+                barrier.wait();
+                let mut worst = Duration::ZERO;
+                for i in 0..PUSHES_PER_WRITER{
+                    let start = Instant::now();
+                    event.push(black_box(i));
+                    worst = worst.max(start.elapsed());
+                }
+                worst
+            }));
+        }
+
+        let mut worst = Duration::ZERO;
+        for thread in writer_threads{
+            worst = worst.max(thread.join().unwrap());
+        }
+        total += worst;
+    }
+    total
+}
+
+pub fn lock_fairness_benchmark(c: &mut Criterion) {
+    macro_rules! settings {
+        ($name:ident, $lock:expr) => {
+            struct $name{} impl Settings for $name{
+                const MIN_CHUNK_SIZE: u32 = 512;
+                const MAX_CHUNK_SIZE: u32 = 512;
+                const CLEANUP: CleanupMode = CleanupMode::Never;
+                const LOCK: LockBackend = $lock;
+            }
+        };
+    }
+    settings!(Spin, LockBackend::Spin);
+    settings!(Yield, LockBackend::Yield);
+    settings!(Std, LockBackend::Std);
+    settings!(Ticket, LockBackend::Ticket);
+
+    let mut group = c.benchmark_group("worst push latency");
+    group.bench_function("Spin",   |b|b.iter_custom(bench_worst_push::<Spin>));
+    group.bench_function("Yield",  |b|b.iter_custom(bench_worst_push::<Yield>));
+    group.bench_function("Std",    |b|b.iter_custom(bench_worst_push::<Std>));
+    group.bench_function("Ticket", |b|b.iter_custom(bench_worst_push::<Ticket>));
+
+    #[cfg(feature = "parking_lot")]
+    {
+        settings!(ParkingLot, LockBackend::ParkingLot);
+        settings!(ParkingLotFair, LockBackend::ParkingLotFair);
+        group.bench_function("ParkingLot",     |b|b.iter_custom(bench_worst_push::<ParkingLot>));
+        group.bench_function("ParkingLotFair", |b|b.iter_custom(bench_worst_push::<ParkingLotFair>));
+    }
+}
+
+criterion_group!(benches, lock_fairness_benchmark);
+criterion_main!(benches);
@@ -5,7 +5,6 @@ use std::time::{Duration, Instant};
 use std::thread;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::pin::Pin;
 
 const QUEUE_SIZE: usize = 100000;
 
@@ -15,12 +14,11 @@ struct S{} impl Settings for S{
     const CLEANUP: CleanupMode = CleanupMode::Never;
 }
 type Event = EventQueue<usize, S>;
-type ArcEvent = Pin<Arc<Event>>;
 
 
 /// We test high-contention read-write case.
 fn bench_event_read_write<F>(iters: u64, writer_fn: F) -> Duration
-    where F: Fn(&ArcEvent, usize, usize) -> () + Send + 'static + Clone
+    where F: Fn(&Event, usize, usize) + Send + 'static + Clone
 {
     let mut total = Duration::ZERO;
 
@@ -93,13 +91,13 @@ fn bench_event_read_write<F>(iters: u64, writer_fn: F) -> Duration
 
 pub fn mt_read_write_event_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("mpmc mt read write");
-    for session_size in [4, 8, 16, 32, 128, 512 as usize]{
+    for session_size in [4, 8, 16, 32, 128, 512_usize]{
         group.bench_with_input(
             BenchmarkId::new("mpmc::EventQueue extend", session_size),
             &session_size,
             |b, input| b.iter_custom(|iters| {
                 let session_len = *input;
-                let f = move |event: &ArcEvent, from: usize, to: usize|{
+                let f = move |event: &Event, from: usize, to: usize|{
                     write_extend(session_len, event, from, to);
                 };
                 bench_event_read_write(iters, f)
@@ -107,13 +105,13 @@ pub fn mt_read_write_event_benchmark(c: &mut Criterion) {
     }
 
     #[inline(always)]
-    fn write_push(event: &ArcEvent, from: usize, to: usize){
+    fn write_push(event: &Event, from: usize, to: usize){
         for i  in from..to{
             event.push(black_box(i));
         }
     }
     #[inline(always)]
-    fn write_extend(session_len: usize, event: &ArcEvent, from: usize, to: usize){
+    fn write_extend(session_len: usize, event: &Event, from: usize, to: usize){
         let mut i = from;
         loop{
             let session_from = i;
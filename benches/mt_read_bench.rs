@@ -2,7 +2,7 @@
 //!
 //! Chunk size dependence test.
 
-use criterion::{Criterion, criterion_group, criterion_main, BenchmarkId, black_box, BenchmarkGroup};
+use criterion::{Criterion, criterion_group, criterion_main, black_box, BenchmarkGroup};
 use rc_event_queue::mpmc::{EventQueue, EventReader, Settings};
 use rc_event_queue::prelude::*;
 use std::thread;
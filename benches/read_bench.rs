@@ -107,9 +107,9 @@ pub fn read_event_benchmark(c: &mut Criterion) {
             &read_session_size,
        |b, input| b.iter_custom(|iters| { bench_event_reader(iters, *input) }));
     }
-    group.bench_function("EventReader/Whole", |b|b.iter_custom(|iters| bench_event_reader_whole(iters)));
-    group.bench_function("Vec", |b|b.iter_custom(|iters| bench_vector_whole(iters)));
-    group.bench_function("Deque", |b|b.iter_custom(|iters| bench_deque_whole(iters)));
+    group.bench_function("EventReader/Whole", |b|b.iter_custom(bench_event_reader_whole));
+    group.bench_function("Vec", |b|b.iter_custom(bench_vector_whole));
+    group.bench_function("Deque", |b|b.iter_custom(bench_deque_whole));
 }
 
 criterion_group!(benches, read_event_benchmark);
@@ -3,7 +3,7 @@
 
 use rc_event_queue::mpmc::{EventQueue, EventReader, Settings};
 use rc_event_queue::prelude::*;
-use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use std::time::{Instant, Duration};
 use std::collections::VecDeque;
 
@@ -61,9 +61,9 @@ fn bench_deque(iters: u64) -> Duration{
 
 pub fn read_empty_event_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("Read empty");
-    group.bench_function("EventReader", |b|b.iter_custom(|iters| bench_event_reader(iters)));
-    group.bench_function("Vec", |b|b.iter_custom(|iters| bench_vector(iters)));
-    group.bench_function("Deque", |b|b.iter_custom(|iters| bench_deque(iters)));
+    group.bench_function("EventReader", |b|b.iter_custom(bench_event_reader));
+    group.bench_function("Vec", |b|b.iter_custom(bench_vector));
+    group.bench_function("Deque", |b|b.iter_custom(bench_deque));
 }
 
 criterion_group!(benches, read_empty_event_benchmark);
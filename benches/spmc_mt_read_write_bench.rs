@@ -4,7 +4,6 @@ use std::time::{Duration, Instant};
 use std::thread;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::pin::Pin;
 use rc_event_queue::{CleanupMode, LendingIterator};
 
 const QUEUE_SIZE: usize = 100000;
@@ -19,7 +18,7 @@ type Event = EventQueue<usize, S>;
 
 /// We test high-contention read-write case.
 fn bench_event_read_write<F>(iters: u64, writer_fn: F) -> Duration
-    where F: Fn(&mut Event, usize, usize) -> () + Send + 'static + Clone
+    where F: Fn(&mut Event, usize, usize) + Send + 'static + Clone
 {
     let mut total = Duration::ZERO;
     let readers_thread_count = 4;
@@ -81,7 +80,7 @@ fn bench_event_read_write<F>(iters: u64, writer_fn: F) -> Duration
 
 pub fn mt_read_write_event_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("spmc mt read write");
-    for session_size in [4, 8, 16, 32, 128, 512 as usize]{
+    for session_size in [4, 8, 16, 32, 128, 512_usize]{
         group.bench_with_input(
             BenchmarkId::new("spmc::EventQueue extend", session_size),
             &session_size,
@@ -8,6 +8,7 @@ const QUEUE_SIZE: usize = 100000;
 macro_rules! event_queue_bench {
     ($mod_name:ident, $event_type:ty) => {
         #[allow(unused_mut)]
+        #[allow(dead_code)]
         mod $mod_name{
             use std::time::{Duration, Instant};
             use criterion::black_box;
@@ -78,6 +79,39 @@ impl spmc::Settings for SPMCEventQueueSettings{
 }
 event_queue_bench!(spmc_bench, crate::spmc::EventQueue<usize, crate::SPMCEventQueueSettings>);
 
+struct SPMCBatchedEventQueueSettings{}
+impl spmc::Settings for SPMCBatchedEventQueueSettings{
+    const MIN_CHUNK_SIZE: u32 = 512;
+    const MAX_CHUNK_SIZE: u32 = 512;
+    const CLEANUP: CleanupMode = CleanupMode::Never;
+    const PUBLISH_BATCH: u32 = 64;
+}
+event_queue_bench!(spmc_batched_bench, crate::spmc::EventQueue<usize, crate::SPMCBatchedEventQueueSettings>);
+
+#[derive(Clone, Copy)]
+struct Pod{
+    _data: [u32; 8]
+}
+
+/// extend vs extend_from_slice, for `Copy` values.
+fn bench_event_extend_copy<T: Copy, const FROM_SLICE: bool>(iters: u64, value: T) -> Duration{
+    let values = vec![value; QUEUE_SIZE];
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let event = mpmc::EventQueue::<T, MPMCEventQueueSettings>::new();
+        let start = Instant::now();
+
+        if FROM_SLICE {
+            event.extend_from_slice(black_box(&values));
+        } else {
+            event.extend(black_box(&values).iter().copied());
+        }
+
+        total += start.elapsed();
+    }
+    total
+}
+
 fn bench_vector_push(iters: u64) -> Duration{
     let mut total = Duration::ZERO;
     for _ in 0..iters {
@@ -145,8 +179,12 @@ pub fn write_event_benchmark(c: &mut Criterion) {
             &session_size,
             |b, input| b.iter_custom(|iters| { mpmc_bench::bench_event_extend_session(iters, *input) }));
     }
-    group.bench_function("mpmc::EventQueue::extend", |b|b.iter_custom(|iters| mpmc_bench::bench_event_extend(iters)));
-    group.bench_function("mpmc::EventQueue::push", |b|b.iter_custom(|iters| mpmc_bench::bench_event_push(iters)));
+    group.bench_function("mpmc::EventQueue::extend", |b|b.iter_custom(mpmc_bench::bench_event_extend));
+    group.bench_function("mpmc::EventQueue::push", |b|b.iter_custom(mpmc_bench::bench_event_push));
+    group.bench_function("mpmc::EventQueue::extend u8", |b|b.iter_custom(|iters| bench_event_extend_copy::<u8, false>(iters, 1)));
+    group.bench_function("mpmc::EventQueue::extend_from_slice u8", |b|b.iter_custom(|iters| bench_event_extend_copy::<u8, true>(iters, 1)));
+    group.bench_function("mpmc::EventQueue::extend Pod", |b|b.iter_custom(|iters| bench_event_extend_copy::<Pod, false>(iters, Pod{_data: [1; 8]})));
+    group.bench_function("mpmc::EventQueue::extend_from_slice Pod", |b|b.iter_custom(|iters| bench_event_extend_copy::<Pod, true>(iters, Pod{_data: [1; 8]})));
 
     // -------------------------- spmc ---------------------------------------
     for session_size in [1, 4, 8, 16, 32, 128, 512]{
@@ -155,14 +193,15 @@ pub fn write_event_benchmark(c: &mut Criterion) {
             &session_size,
             |b, input| b.iter_custom(|iters| { spmc_bench::bench_event_extend_session(iters, *input) }));
     }
-    group.bench_function("spmc::EventQueue::extend", |b|b.iter_custom(|iters| spmc_bench::bench_event_extend(iters)));
-    group.bench_function("spmc::EventQueue::push", |b|b.iter_custom(|iters| spmc_bench::bench_event_push(iters)));
+    group.bench_function("spmc::EventQueue::extend", |b|b.iter_custom(spmc_bench::bench_event_extend));
+    group.bench_function("spmc::EventQueue::push", |b|b.iter_custom(spmc_bench::bench_event_push));
+    group.bench_function("spmc::EventQueue::push PUBLISH_BATCH=64", |b|b.iter_custom(spmc_batched_bench::bench_event_push));
 
     // -------------------------- std ---------------------------------------
-    group.bench_function("Vec::push", |b|b.iter_custom(|iters| bench_vector_push(iters)));
-    group.bench_function("Vec::extend", |b|b.iter_custom(|iters| bench_vector_extend(iters)));
-    group.bench_function("Deque::push", |b|b.iter_custom(|iters| bench_deque_push(iters)));
-    group.bench_function("Deque::extend", |b|b.iter_custom(|iters| bench_deque_extend(iters)));
+    group.bench_function("Vec::push", |b|b.iter_custom(bench_vector_push));
+    group.bench_function("Vec::extend", |b|b.iter_custom(bench_vector_extend));
+    group.bench_function("Deque::push", |b|b.iter_custom(bench_deque_push));
+    group.bench_function("Deque::extend", |b|b.iter_custom(bench_deque_extend));
 }
 
 criterion_group!(benches, write_event_benchmark);
@@ -0,0 +1,40 @@
+use core::alloc::Layout;
+
+/// Chunk memory allocator. Plain function pointers - so it can be set in `Settings`.
+///
+/// Allows chunks to come from arena, pool, or tracked allocator. See
+/// [mpmc::Settings::CHUNK_ALLOCATOR](crate::mpmc::Settings::CHUNK_ALLOCATOR).
+///
+/// ```
+/// # use rc_event_queue::ChunkAllocator;
+/// # use core::alloc::Layout;
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+///
+/// const TRACKED: ChunkAllocator = ChunkAllocator{
+///     alloc: |layout|{
+///         ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+///         unsafe{ std::alloc::alloc(layout) }
+///     },
+///     dealloc: |ptr, layout|{
+///         ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+///         unsafe{ std::alloc::dealloc(ptr, layout) }
+///     },
+/// };
+/// ```
+#[derive(Clone, Copy)]
+pub struct ChunkAllocator{
+    /// Same contract as [core::alloc::GlobalAlloc::alloc]. Returns null on failure.
+    pub alloc: fn(Layout) -> *mut u8,
+    /// Same contract as [core::alloc::GlobalAlloc::dealloc]. Called only with pointers,
+    /// returned by `alloc` with the same `layout`.
+    pub dealloc: fn(*mut u8, Layout),
+}
+
+impl ChunkAllocator{
+    /// Global allocator.
+    pub const GLOBAL: ChunkAllocator = ChunkAllocator{
+        alloc: |layout| unsafe{ alloc::alloc::alloc(layout) },
+        dealloc: |ptr, layout| unsafe{ alloc::alloc::dealloc(ptr, layout) },
+    };
+}
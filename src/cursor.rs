@@ -1,8 +1,7 @@
-use std::cmp::Ordering;
+use core::cmp::Ordering;
 use crate::dynamic_chunk::DynamicChunk;
 use crate::event_queue::Settings;
 
-// TODO: Untested comparison!!
 pub(super) struct Cursor<T, S: Settings>
 {
     // TODO: try hide
@@ -15,7 +14,7 @@ pub(super) struct Cursor<T, S: Settings>
 impl<T, S: Settings>Copy for Cursor<T, S> {}
 impl<T, S: Settings>Clone for Cursor<T, S> {
     fn clone(&self) -> Self {
-        Self{ chunk: self.chunk, index: self.index }
+        *self
     }
 }
 
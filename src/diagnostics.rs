@@ -0,0 +1,59 @@
+use alloc::vec::Vec;
+use alloc::string::String;
+use crate::Position;
+
+/// [EventQueue](crate::mpmc::EventQueue) structure snapshot. For logging, and asserting on in tests.
+///
+/// Taken under lock - consistent. But readers move concurrently, so reader counters may lag.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct QueueDiagnostics{
+    /// Chunks in queue, from first to last.
+    pub chunks: Vec<ChunkDiagnostics>,
+    pub readers_count: usize,
+    /// Readers, tracked by queue - named, with TTL, or with `Settings::STALE_READERS`.
+    /// Other readers are anonymous - counted in `readers_count` only.
+    pub readers: Vec<ReaderDiagnostics>,
+    /// Where new readers start, and existing ones jump to, after
+    /// `clear`/`truncate_front`/`cleanup_force`. `None` - queue was never cleared.
+    pub start_position: Option<Position>,
+    /// Pushed, but not yet published values. See `Settings::PUBLISH_BATCH`.
+    pub unpublished_len: usize,
+    /// Freed chunks, kept for reuse (see `Settings::RECYCLE_POOL_SIZE`,
+    /// `Settings::PREALLOCATED_CHUNKS`).
+    pub free_chunks_count: usize,
+    /// Sum of free chunks capacities.
+    pub free_chunks_capacity: usize,
+}
+
+/// Tracked reader in [QueueDiagnostics].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReaderDiagnostics{
+    pub name: Option<String>,
+    /// Expired reader does not pin anything.
+    pub expired: bool,
+    /// Chunk, pinned by reader. `None` - if expired, or in the middle of read session.
+    pub chunk_id: Option<u64>,
+    /// Items from the beginning of pinned chunk to the queue end - upper bound of reader's lag.
+    pub lag: usize,
+    /// Allocation size of pinned chunk and all chunks after it.
+    pub pinned_bytes: usize,
+}
+
+/// Chunk state in [QueueDiagnostics].
+///
+/// Chunk can be freed, only when all chunks before it are freed, and
+/// `read_completely_times` reached `readers_entered`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChunkDiagnostics{
+    /// Sequential chunk number. Starts from 0.
+    pub id: u64,
+    pub capacity: usize,
+    /// Published items in chunk.
+    pub len: usize,
+    /// Start position epoch. Changed in all chunks on each start position change.
+    pub epoch: u32,
+    /// Readers, which ever entered chunk.
+    pub readers_entered: usize,
+    /// Readers, which read chunk till the end, or left it.
+    pub read_completely_times: usize,
+}
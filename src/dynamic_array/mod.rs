@@ -1,14 +1,18 @@
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
+#[allow(clippy::unnecessary_cast)]
 mod test;
 
-use std::mem;
-use std::alloc::Layout;
-use std::borrow::Borrow;
+use core::mem;
+use core::ptr::null_mut;
+use core::alloc::Layout;
+use core::borrow::Borrow;
+use crate::chunk_allocator::ChunkAllocator;
 
 #[repr(C)]
 pub struct DynamicArray<Header, T>{
     header: Header,
     array_len : usize,
+    /// Only for alignment. Items are at [Self::array_ptr].
     array: [T; 0],
 }
 
@@ -21,56 +25,114 @@ impl<Header, T> DynamicArray<Header, T>{
         }
     }
 
+    /// Items start right after `Self`, not at `array` offset - with over-aligned `Header`,
+    /// `Self` have trailing padding, and references to `Self` must not cover items
+    /// (they are written concurrently).
+    #[inline(always)]
+    fn array_ptr(this: *mut Self) -> *mut T{
+        (this as *mut u8).wrapping_add(mem::size_of::<Self>()) as *mut T
+    }
+
+    /// Bytes allocated for array with `len` items.
+    #[inline]
+    pub fn allocation_size(len: usize) -> usize{
+        Self::layout(len).size()
+    }
+
     pub fn construct(header: Header, value: T, len: usize) -> *mut Self {
         unsafe{
             let this = &mut *Self::construct_uninit(header, len);
 
             for item in this.slice_mut(){
-                std::ptr::copy_nonoverlapping(value.borrow(), item, 1);
+                core::ptr::copy_nonoverlapping(value.borrow(), item, 1);
             }
 
-            std::mem::forget(value);
+            core::mem::forget(value);
 
             this
         }
     }
 
     /// array is not initialized
+    #[inline]
     pub unsafe fn construct_uninit(header: Header, len: usize) -> *mut Self {
-        let this = &mut *{
-            let allocation = std::alloc::alloc(Self::layout(len));
-            allocation as *mut Self
-        };
+        Self::construct_uninit_in(header, len, ChunkAllocator::GLOBAL)
+    }
+
+    /// array is not initialized
+    pub unsafe fn construct_uninit_in(header: Header, len: usize, allocator: ChunkAllocator) -> *mut Self {
+        let this = Self::try_construct_uninit_in(header, len, allocator);
+        if this.is_null(){
+            alloc::alloc::handle_alloc_error(Self::layout(len));
+        }
+        this
+    }
+
+    /// array is not initialized. Returns null on allocation failure.
+    pub unsafe fn try_construct_uninit_in(header: Header, len: usize, allocator: ChunkAllocator) -> *mut Self {
+        let allocation = (allocator.alloc)(Self::layout(len));
+        if allocation.is_null(){
+            return null_mut();
+        }
+        let this = &mut *(allocation as *mut Self);
 
-        std::ptr::write(&mut this.header, header);
+        core::ptr::write(&mut this.header, header);
         this.array_len = len;
 
         this
     }
 
     /// No checks at all!
+    /// Takes raw pointer - does not retag the whole array, so other items and header
+    /// may be accessed concurrently.
+    #[inline]
+    pub unsafe fn write_at(this: *mut Self, index: usize, value: T){
+        core::ptr::write(Self::array_ptr(this).add(index), value);
+    }
+
+    /// Same as [write_at](Self::write_at), for many items at once.
+    #[inline]
+    pub unsafe fn write_slice_at(this: *mut Self, index: usize, values: &[T])
+        where T: Copy
+    {
+        core::ptr::copy_nonoverlapping(values.as_ptr(), Self::array_ptr(this).add(index), values.len());
+    }
+
+    /// Does not touch other items - they may be accessed concurrently.
     #[inline]
-    pub unsafe fn write_at(&mut self, index: usize, value: T){
-        std::ptr::write(self.slice_mut().get_unchecked_mut(index), value);
+    pub unsafe fn get_unchecked(&self, index: usize) -> &T{
+        &*Self::array_ptr(self as *const Self as *mut Self).add(index)
+    }
+
+    /// Does not touch other items - they may be accessed concurrently.
+    #[inline]
+    pub unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut T{
+        &mut *Self::array_ptr(self).add(index)
     }
 
     /// Unsafe due to potential double-free, use-after-free
     pub unsafe fn destruct(this: *mut Self) {
-        if std::mem::needs_drop::<T>() {
+        if core::mem::needs_drop::<T>() {
             for item in (*this).slice_mut(){
-                std::ptr::drop_in_place(item);
+                core::ptr::drop_in_place(item);
             }
         }
 
         Self::destruct_uninit(this);
     }
 
+    #[inline]
     pub unsafe fn destruct_uninit(this: *mut Self) {
-        if std::mem::needs_drop::<Header>() {
-            std::ptr::drop_in_place(&mut (*this).header);
+        Self::destruct_uninit_in(this, ChunkAllocator::GLOBAL);
+    }
+
+    /// `allocator` must be the same, as in construct.
+    pub unsafe fn destruct_uninit_in(this: *mut Self, allocator: ChunkAllocator) {
+        if core::mem::needs_drop::<Header>() {
+            core::ptr::drop_in_place(&mut (*this).header);
         }
 
-        std::alloc::dealloc(this as *mut u8,Self::layout((*this).array_len));
+        (allocator.dealloc)(this as *mut u8, Self::layout((*this).array_len));
     }
 
     #[inline]
@@ -86,14 +148,14 @@ impl<Header, T> DynamicArray<Header, T>{
     #[inline]
     pub fn slice(&self) -> &[T] {
         unsafe {
-            std::slice::from_raw_parts(self.array.as_ptr(), self.array_len)
+            core::slice::from_raw_parts(Self::array_ptr(self as *const Self as *mut Self), self.array_len)
         }
     }
 
     #[inline]
     pub fn slice_mut(&mut self) -> &mut [T] {
         unsafe {
-            std::slice::from_raw_parts_mut(self.array.as_mut_ptr(), self.array_len)
+            core::slice::from_raw_parts_mut(Self::array_ptr(self), self.array_len)
         }
     }
 
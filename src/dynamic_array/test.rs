@@ -43,7 +43,7 @@ fn uninit_test(){
         )
     };
 
-    unsafe { fla.write_at(1, Data { i: 800, on_destroy }); }
+    unsafe { DynamicArray::write_at(fla, 1, Data { i: 800, on_destroy }); }
 
     let array = fla.slice_mut();
     assert_eq!(array.len(), 8);
@@ -1,10 +1,15 @@
 use crate::dynamic_array::DynamicArray;
 use crate::sync::{Ordering, AtomicPtr, AtomicUsize, SpinSharedMutex};
-use crate::event_queue::{EventQueue, Settings};
-use std::ptr::{null_mut, NonNull};
-use std::ptr;
+use crate::event_queue::Settings;
+use core::ptr::{null_mut, NonNull};
+use core::ptr;
+use alloc::boxed::Box;
 use crate::chunk_state::{AtomicPackedChunkState, ChunkState, PackedChunkState};
 use crate::StartPositionEpoch;
+use crate::utils::CachePadded;
+#[cfg(feature = "std")]
+use std::time::Instant;
+use core::cell::UnsafeCell;
 
 /// Error, indicating insufficient capacity
 pub struct CapacityError<V>{
@@ -14,29 +19,44 @@ pub struct CapacityError<V>{
 struct Header<T, S: Settings>{
     /// Just to compare chunks by age/sequence fast. Brings order.
     /// Will overflow after years... So just ignore that possibility.
-    pub(super) id      : usize,
-    pub(super) next    : AtomicPtr<DynamicChunk<T, S>>,
+    pub(super) id      : u64,
+    /// Sequence number of the first item. Set before chunk connected to the list.
+    /// Never changes after that.
+    pub(super) first_seq: u64,
+    /// Padded fields - see `cache_padded` feature.
+    pub(super) next    : CachePadded<AtomicPtr<DynamicChunk<T, S>>>,
 
     /// locked in reader next chunk and force_cleanup
     pub(super) chunk_switch_mutex : SpinSharedMutex<()>,
     /// Grow only.
-    pub(super) readers_entered: AtomicUsize,
+    pub(super) readers_entered: CachePadded<AtomicUsize>,
 
     /// When == readers count, it is safe to delete this chunk.
     /// Chunk read completely if reader consumed CHUNK_SIZE'ed element.
     /// Last chunk always exists.
     ///
     /// Grow only.
-    pub(super) read_completely_times : AtomicUsize,
+    pub(super) read_completely_times : CachePadded<AtomicUsize>,
 
-    // This needed to access Event from EventReader.
-    // Never changes.
-    pub(super) event : *const EventQueue<T, S>,
+    /// Items `[0..drained)` moved out (or dropped) by `EventReader::drain`.
+    /// Written by the sole reader only. Destructor skips them.
+    drained: UnsafeCell<usize>,
 
-    /// LenAndEpoch. Epoch same across all chunks. Epoch updated in all chunks at [EventQueue::clear]
+    /// Per-item push time. Empty, if not [Settings::TIMESTAMPS].
+    /// Written by writer before len publish, like items.
+    #[cfg(feature = "std")]
+    timestamps: Box<[UnsafeCell<Instant>]>,
+
+    /// When next chunk was added. No items pushed after that.
+    /// Written by writer only. Never read by readers.
+    /// UnsafeCell - because readers hold shared references to chunk.
+    #[cfg(feature = "std")]
+    closed_at: UnsafeCell<Option<Instant>>,
+
+    /// LenAndEpoch. Epoch same across all chunks. Epoch updated in all chunks at [EventQueue::clear](crate::event_queue::EventQueue::clear)
     /// len fused with epoch for optimization purposes. This allow to get start_position_epoch without
     /// touching EventQueue and without additional atomic load(acquire)
-    chunk_state: AtomicPackedChunkState,
+    chunk_state: CachePadded<AtomicPackedChunkState>,
 }
 
 #[repr(transparent)]
@@ -44,27 +64,81 @@ pub struct DynamicChunk<T, S: Settings>(
     DynamicArray< Header<T, S>, T >
 );
 
+/// Chunk storage, when items [spilled](DynamicChunk::SPILLED) - boxed items pointers.
+/// Same header layout as `DynamicChunk`.
+type SpilledArray<T, S> = DynamicArray< Header<T, S>, NonNull<T> >;
+
 impl<T, S: Settings> DynamicChunk<T, S>{
+    /// Items bigger than [Settings::SPILL_THRESHOLD] stored in `Box`, and slots hold only pointers.
+    ///
+    /// Over-aligned `T` never spilled - `DynamicChunk` and [SpilledArray] must have
+    /// the same size, for items to start at the same offset.
+    pub const SPILLED: bool =
+        core::mem::size_of::<T>() > S::SPILL_THRESHOLD
+        && core::mem::align_of::<T>() <= core::mem::align_of::<Header<T, S>>();
+
+    #[inline(always)]
+    fn spilled_array(this: *mut Self) -> *mut SpilledArray<T, S>{
+        this as *mut SpilledArray<T, S>
+    }
+
     #[inline]
-    pub fn id(&self) -> usize{
+    pub fn id(&self) -> u64{
         self.0.header().id
     }
 
+    /// Sequence number of the first item.
+    #[inline]
+    pub fn first_seq(&self) -> u64{
+        self.0.header().first_seq
+    }
+
+    /// Only before chunk connected to the list.
+    #[inline]
+    pub unsafe fn set_first_seq(this: *mut Self, first_seq: u64){
+        (*this).0.header_mut().first_seq = first_seq;
+    }
+
     #[inline]
     pub fn next(&self, load_ordering: Ordering) -> *mut Self{
         self.0.header().next.load(load_ordering)
     }
 
     #[inline]
-    pub fn set_next(&mut self, ptr: *mut Self, store_ordering: Ordering) {
+    pub fn set_next(&self, ptr: *mut Self, store_ordering: Ordering) {
         self.0.header().next.store(ptr, store_ordering);
 
-        // Relaxed because &mut self
+        // Relaxed because updated only by writer
         let mut chunk_state = self.0.header().chunk_state.load(Ordering::Relaxed);
         chunk_state.set_has_next(!ptr.is_null());
         self.0.header().chunk_state.store(chunk_state, store_ordering);
     }
 
+    /// Should be called only by writer.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn closed_at(&self) -> Option<Instant>{
+        unsafe{ *self.0.header().closed_at.get() }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn set_closed_at(&self, time: Instant){
+        unsafe{ *self.0.header().closed_at.get() = Some(time); }
+    }
+
+    /// Items before - moved out. See [take_unchecked](Self::take_unchecked).
+    #[inline]
+    pub fn drained(&self) -> usize{
+        unsafe{ *self.0.header().drained.get() }
+    }
+
+    /// Should be called only by the sole reader.
+    #[inline]
+    pub unsafe fn set_drained(&self, drained: usize){
+        *self.0.header().drained.get() = drained;
+    }
+
     #[inline]
     pub fn chunk_switch_mutex(&self) -> &SpinSharedMutex<()>{
         &self.0.header().chunk_switch_mutex
@@ -81,34 +155,71 @@ impl<T, S: Settings> DynamicChunk<T, S>{
     }
 
     #[inline]
-    pub fn event(&self) -> &EventQueue<T, S>{
-        unsafe { &*self.0.header().event }
+    pub fn construct(
+        id: u64,
+        epoch: StartPositionEpoch,
+        len: usize
+    ) -> *mut Self{
+        Self::construct_impl::<false>(id, epoch, len)
     }
 
-    pub fn construct(
-        id: usize,
+    /// Returns null on allocation failure.
+    #[inline]
+    pub fn try_construct(
+        id: u64,
+        epoch: StartPositionEpoch,
+        len: usize
+    ) -> *mut Self{
+        Self::construct_impl::<true>(id, epoch, len)
+    }
+
+    fn construct_impl<const FALLIBLE_ALLOC: bool>(
+        id: u64,
         epoch: StartPositionEpoch,
-        event : *const EventQueue<T, S>,
         len: usize
     ) -> *mut Self{
         let header = Header{
             id,
-            next: AtomicPtr::new(null_mut()),
+            first_seq: 0,
+            next: CachePadded::new(AtomicPtr::new(null_mut())),
             chunk_switch_mutex: SpinSharedMutex::new(()),
-            readers_entered: AtomicUsize::new(0),
-            read_completely_times: AtomicUsize::new(0),
-            event,
-            chunk_state: AtomicPackedChunkState::new(
+            readers_entered: CachePadded::new(AtomicUsize::new(0)),
+            read_completely_times: CachePadded::new(AtomicUsize::new(0)),
+            drained: UnsafeCell::new(0),
+            #[cfg(feature = "std")]
+            timestamps:
+                if /*constexpr*/ S::TIMESTAMPS {
+                    let now = Instant::now();
+                    (0..len).map(|_| UnsafeCell::new(now)).collect()
+                } else {
+                    Box::new([])
+                },
+            #[cfg(feature = "std")]
+            closed_at: UnsafeCell::new(None),
+            chunk_state: CachePadded::new(AtomicPackedChunkState::new(
                 PackedChunkState::pack(
                     ChunkState{len: 0, has_next: false, epoch}
                 )
-            )
+            ))
         };
         unsafe{
-            let this = DynamicArray::<Header<T, S>, T>::construct_uninit(
-                header,
-                len
-            );
+            if /*constexpr*/ Self::SPILLED {
+                debug_assert_eq!(core::mem::size_of::<Self>(), core::mem::size_of::<SpilledArray<T, S>>());
+                let this =
+                    if FALLIBLE_ALLOC {
+                        SpilledArray::<T, S>::try_construct_uninit_in(header, len, S::CHUNK_ALLOCATOR)
+                    } else {
+                        SpilledArray::<T, S>::construct_uninit_in(header, len, S::CHUNK_ALLOCATOR)
+                    };
+                return this as *mut Self;
+            }
+
+            let this =
+                if FALLIBLE_ALLOC {
+                    DynamicArray::<Header<T, S>, T>::try_construct_uninit_in(header, len, S::CHUNK_ALLOCATOR)
+                } else {
+                    DynamicArray::<Header<T, S>, T>::construct_uninit_in(header, len, S::CHUNK_ALLOCATOR)
+                };
 
             // This is ok, due to transparent
             this as *mut _ as *mut Self
@@ -120,21 +231,27 @@ impl<T, S: Settings> DynamicChunk<T, S>{
     /// Should be used in deinitialize -> reinitialize cycle.
     pub unsafe fn from_recycled(
         mut recycled: DynamicChunkRecycled<T, S>,
-        id: usize,
+        id: u64,
         epoch: StartPositionEpoch,
     ) -> *mut Self {
         let header = recycled.chunk.as_mut().0.header_mut();
         header.id = id;
-        header.next = AtomicPtr::new(null_mut());
-        header.read_completely_times = AtomicUsize::new(0);
-        header.chunk_state = AtomicPackedChunkState::new(
+        header.next = CachePadded::new(AtomicPtr::new(null_mut()));
+        header.readers_entered = CachePadded::new(AtomicUsize::new(0));
+        header.read_completely_times = CachePadded::new(AtomicUsize::new(0));
+        header.drained = UnsafeCell::new(0);
+        #[cfg(feature = "std")]
+        {
+            header.closed_at = UnsafeCell::new(None);
+        }
+        header.chunk_state = CachePadded::new(AtomicPackedChunkState::new(
             PackedChunkState::pack(
                 ChunkState{len: 0, has_next: false, epoch}
             )
-        );
+        ));
 
         let ptr = recycled.chunk.as_ptr();
-            std::mem::forget(recycled);
+            core::mem::forget(recycled);
         ptr
     }
 
@@ -142,75 +259,158 @@ impl<T, S: Settings> DynamicChunk<T, S>{
 //                      STORAGE
 // ----------------------------------------------------------------
     #[inline]
-    pub fn set_epoch(&mut self, epoch: StartPositionEpoch, load_ordering: Ordering, store_ordering: Ordering){
+    pub fn set_epoch(&self, epoch: StartPositionEpoch, load_ordering: Ordering, store_ordering: Ordering){
         let mut chunk_state = self.chunk_state(load_ordering);
         chunk_state.set_epoch(epoch);
 
         self.0.header().chunk_state.store(chunk_state, store_ordering);
     }
 
+    // Writing functions take `this: *mut Self` instead of `&mut self`:
+    // readers access the same chunk concurrently, so writer must not hold
+    // exclusive reference to it.
+
     /// Needs additional synchronization, because several threads writing simultaneously may finish writes
     /// not in order, but len increases sequentially. This may cause items before len index being not fully written.
     #[inline(always)]
-    pub fn try_push(&mut self, value: T, store_ordering: Ordering) -> Result<(), CapacityError<T>>{
-        // Relaxed because updated only with &mut self
-        let chunk_state = self.chunk_state(Ordering::Relaxed);
+    pub unsafe fn try_push(this: *mut Self, value: T, store_ordering: Ordering) -> Result<(), CapacityError<T>>{
+        // Relaxed because updated only by writer
+        let chunk_state = (*this).chunk_state(Ordering::Relaxed);
         let index = chunk_state.len();
-        if (index as usize) >= self.capacity() {
+        if (index as usize) >= (*this).capacity() {
             return Result::Err(CapacityError{value});
         }
 
-        unsafe{ self.push_at(value, index, chunk_state, store_ordering); }
+        Self::push_at(this, value, index, chunk_state, store_ordering);
 
         return Result::Ok(());
     }
 
     #[inline(always)]
-    pub unsafe fn push_unchecked(&mut self, value: T, store_ordering: Ordering){
-        // Relaxed because updated only with &mut self
-        let chunk_state = self.chunk_state(Ordering::Relaxed);
+    pub unsafe fn push_unchecked(this: *mut Self, value: T, store_ordering: Ordering){
+        // Relaxed because updated only by writer
+        let chunk_state = (*this).chunk_state(Ordering::Relaxed);
         let index = chunk_state.len();
 
-        self.push_at(value, index, chunk_state, store_ordering);
+        Self::push_at(this, value, index, chunk_state, store_ordering);
     }
 
+    /// Write without publishing. Len should be updated with [set_chunk_state](Self::set_chunk_state).
     #[inline(always)]
-    pub unsafe fn push_at(&mut self, value: T, index: u32, mut chunk_state: PackedChunkState, store_ordering: Ordering) {
-        debug_assert!((index as usize) < self.capacity());
+    pub unsafe fn write_at(this: *mut Self, index: u32, value: T){
+        debug_assert!((index as usize) < (*this).capacity());
+        Self::write_item(this, index as usize, value);
+    }
 
-        self.0.write_at(index as usize, value);
+    #[inline(always)]
+    unsafe fn write_item(this: *mut Self, index: usize, value: T){
+        #[cfg(feature = "std")]
+        if /*constexpr*/ S::TIMESTAMPS {
+            (*this).write_timestamp(index, Instant::now());
+        }
+        Self::write_slot(this, index, value);
+    }
 
-        chunk_state.set_len(index+1);
+    #[inline(always)]
+    unsafe fn write_slot(this: *mut Self, index: usize, value: T){
+        if /*constexpr*/ Self::SPILLED {
+            DynamicArray::write_at(Self::spilled_array(this), index, NonNull::from(Box::leak(Box::new(value))));
+        } else {
+            DynamicArray::write_at(ptr::addr_of_mut!((*this).0), index, value);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    unsafe fn write_timestamp(&self, index: usize, time: Instant){
+        *self.0.header().timestamps.get_unchecked(index).get() = time;
+    }
 
+    /// Only with [Settings::TIMESTAMPS].
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub unsafe fn get_timestamp_unchecked(&self, index: usize) -> Instant{
+        debug_assert!(S::TIMESTAMPS);
+        *self.0.header().timestamps.get_unchecked(index).get()
+    }
+
+    #[inline(always)]
+    pub fn set_chunk_state(&self, chunk_state: PackedChunkState, store_ordering: Ordering){
         self.0.header().chunk_state.store(chunk_state, store_ordering);
     }
 
+    #[inline(always)]
+    pub unsafe fn push_at(this: *mut Self, value: T, index: u32, mut chunk_state: PackedChunkState, store_ordering: Ordering) {
+        debug_assert!((index as usize) < (*this).capacity());
+
+        Self::write_item(this, index as usize, value);
+
+        chunk_state.set_len(index+1);
+
+        (*this).set_chunk_state(chunk_state, store_ordering);
+    }
+
+    /// Copy as many `values` as fit. Returns number of copied.
+    #[inline]
+    pub unsafe fn extend_from_slice(this: *mut Self, values: &[T], store_ordering: Ordering) -> usize
+        where T: Copy
+    {
+        let mut chunk_state = (*this).chunk_state(Ordering::Relaxed);
+        let index = chunk_state.len() as usize;
+        let count = values.len().min((*this).capacity() - index);
+
+        #[cfg(feature = "std")]
+        if /*constexpr*/ S::TIMESTAMPS {
+            let now = Instant::now();
+            for i in index..index+count {
+                (*this).write_timestamp(i, now);
+            }
+        }
+        if /*constexpr*/ Self::SPILLED {
+            for (i, value) in values[..count].iter().enumerate() {
+                Self::write_slot(this, index + i, *value);
+            }
+        } else {
+            DynamicArray::write_slice_at(ptr::addr_of_mut!((*this).0), index, &values[..count]);
+        }
+
+        chunk_state.set_len((index + count) as u32);
+        (*this).set_chunk_state(chunk_state, store_ordering);
+        count
+    }
+
     /// Append items from iterator, until have free space
     /// Returns Ok if everything fit, CapacityError() - if not
     #[inline]
-    pub fn extend<I>(&mut self, iter: &mut I, store_ordering: Ordering) -> Result<(), CapacityError<()>>
+    pub unsafe fn extend<I>(this: *mut Self, iter: &mut I, store_ordering: Ordering) -> Result<(), CapacityError<()>>
         where I:Iterator<Item = T>
     {
-        let mut chunk_state = self.chunk_state(Ordering::Relaxed);
+        let capacity = (*this).capacity();
+        let mut chunk_state = (*this).chunk_state(Ordering::Relaxed);
         let mut index = chunk_state.len() as usize;
+        // Items of one extend considered pushed simultaneously.
+        #[cfg(feature = "std")]
+        let now = if /*constexpr*/ S::TIMESTAMPS { Some(Instant::now()) } else { None };
 
         loop {
-            if index == self.capacity(){
-                chunk_state.set_len(self.capacity() as u32);
-                self.0.header().chunk_state.store(chunk_state, store_ordering);
+            if index == capacity{
+                chunk_state.set_len(capacity as u32);
+                (*this).set_chunk_state(chunk_state, store_ordering);
                 return Result::Err(CapacityError{value:()});
             }
 
             match iter.next(){
                 None => {
                     chunk_state.set_len(index as u32);
-                    self.0.header().chunk_state.store(chunk_state, store_ordering);
+                    (*this).set_chunk_state(chunk_state, store_ordering);
                     return Result::Ok(());
                 }
                 Some(value) => {
-                    unsafe{
-                        self.0.write_at(index as usize, value);
+                    #[cfg(feature = "std")]
+                    if let Some(now) = now {
+                        (*this).write_timestamp(index, now);
                     }
+                    Self::write_slot(this, index, value);
                 }
             }
 
@@ -220,12 +420,31 @@ impl<T, S: Settings> DynamicChunk<T, S>{
 
     #[inline(always)]
     pub unsafe fn get_unchecked(&self, index: usize) -> &T{
-        self.0.slice().get_unchecked(index)
+        if /*constexpr*/ Self::SPILLED {
+            let spilled = &*Self::spilled_array(self as *const Self as *mut Self);
+            return spilled.get_unchecked(index).as_ref();
+        }
+        self.0.get_unchecked(index)
+    }
+
+    /// Move item out. Slot becomes logically uninitialized - item should be
+    /// counted in [drained](Self::drained), before chunk destruction.
+    #[inline(always)]
+    pub unsafe fn take_unchecked(&self, index: usize) -> T{
+        if /*constexpr*/ Self::SPILLED {
+            let spilled = &*Self::spilled_array(self as *const Self as *mut Self);
+            return *Box::from_raw(spilled.get_unchecked(index).as_ptr());
+        }
+        ptr::read(self.0.get_unchecked(index))
     }
 
     #[inline(always)]
     pub unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut T{
-        self.0.slice_mut().get_unchecked_mut(index)
+        if /*constexpr*/ Self::SPILLED {
+            let spilled = &mut *Self::spilled_array(self);
+            return spilled.get_unchecked_mut(index).as_mut();
+        }
+        self.0.get_unchecked_mut(index)
     }
 
     #[inline(always)]
@@ -238,19 +457,53 @@ impl<T, S: Settings> DynamicChunk<T, S>{
         self.0.len()
     }
 
+    /// Bytes allocated for chunk with `capacity`.
+    #[inline]
+    pub fn allocation_size(capacity: usize) -> usize {
+        DynamicArray::<Header<T, S>, T>::allocation_size(0)
+        + capacity * Self::slot_size()
+    }
+
+    /// Chunk bytes per item. Without spilled items heap memory.
+    #[inline]
+    pub fn slot_size() -> usize {
+        let item_size =
+            if /*constexpr*/ Self::SPILLED {
+                core::mem::size_of::<NonNull<T>>()
+            } else {
+                core::mem::size_of::<T>()
+            };
+        item_size + Self::timestamp_size()
+    }
+
+    #[inline]
+    pub fn timestamp_size() -> usize {
+        #[cfg(feature = "std")]
+        if /*constexpr*/ S::TIMESTAMPS {
+            return core::mem::size_of::<Instant>();
+        }
+        0
+    }
+
     pub unsafe fn destruct(this: *mut Self){
-        std::mem::drop(Self::recycle(this));
+        core::mem::drop(Self::recycle(this));
     }
 
     /// destruct all items. Can be stored for reuse.
     /// Should be called exactly once before reinitialization.
     #[must_use]
     pub unsafe fn recycle(this: *mut Self) -> DynamicChunkRecycled<T, S>{
-        if std::mem::needs_drop::<T>() {
+        if /*constexpr*/ Self::SPILLED {
             // Relaxed because &mut self
             let len = (*this).chunk_state(Ordering::Relaxed).len() as usize;
-            for i in 0..len {
-                 ptr::drop_in_place((*this).0.slice_mut().get_unchecked_mut(i));
+            for i in (*this).drained()..len {
+                drop(Box::from_raw((*this).get_unchecked_mut(i)));
+            }
+        } else if core::mem::needs_drop::<T>() {
+            // Relaxed because &mut self
+            let len = (*this).chunk_state(Ordering::Relaxed).len() as usize;
+            for i in (*this).drained()..len {
+                 ptr::drop_in_place((*this).0.get_unchecked_mut(i));
             }
         }
         DynamicChunkRecycled {chunk: NonNull::new_unchecked(this)}
@@ -270,8 +523,16 @@ impl<T, S: Settings> DynamicChunkRecycled<T, S>{
 impl<T, S: Settings> Drop for DynamicChunkRecycled<T, S>{
     fn drop(&mut self) {
         unsafe {
-            DynamicArray::<Header<T, S>, T>::destruct_uninit(
-                self.chunk.as_ptr() as *mut DynamicArray<Header<T, S>, T>
+            if /*constexpr*/ DynamicChunk::<T, S>::SPILLED {
+                SpilledArray::<T, S>::destruct_uninit_in(
+                    DynamicChunk::spilled_array(self.chunk.as_ptr()),
+                    S::CHUNK_ALLOCATOR
+                );
+                return;
+            }
+            DynamicArray::<Header<T, S>, T>::destruct_uninit_in(
+                self.chunk.as_ptr() as *mut DynamicArray<Header<T, S>, T>,
+                S::CHUNK_ALLOCATOR
             )
         }
     }
@@ -1,26 +1,43 @@
 #[cfg(not(loom))]
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
+#[allow(unused_imports, clippy::unnecessary_cast)]
 mod test;
 
-use crate::sync::{Ordering};
-use crate::sync::{Mutex, Arc};
-use crate::sync::{SpinMutex};
+#[cfg(feature = "serde")]
+mod snapshot;
 
-use std::ptr::{null_mut, null, NonNull};
-use crate::event_reader::EventReader;
-use std::ops::ControlFlow;
-use std::ops::ControlFlow::{Continue, Break};
-use std::marker::PhantomPinned;
-use std::pin::Pin;
+mod into_iter;
+pub(crate) use into_iter::IntoIter;
+
+use crate::sync::{Ordering, AtomicU64, AtomicUsize, AtomicBool, fence};
+use crate::sync::{Arc, SettingsMutex, SpinMutex};
+
+use core::ptr::{null_mut, null};
+use crate::event_reader::{EventReader, ReaderExpired, ReaderOptions};
+use core::ops::ControlFlow;
+use core::ops::ControlFlow::{Continue, Break};
 use crate::cursor::Cursor;
-use crate::dynamic_chunk::{DynamicChunk};
-#[cfg(feature = "double_buffering")]
-use crate::dynamic_chunk::{DynamicChunkRecycled};
-use crate::{StartPositionEpoch};
+use crate::dynamic_chunk::{DynamicChunk, DynamicChunkRecycled};
+use crate::{StartPositionEpoch, Position};
+use crate::utils::CachePadded;
+#[cfg(feature = "std")]
+use crate::reader_lease::ReaderLease;
+use crate::stats::{Stats, StatsCounters};
+use crate::diagnostics::{ChunkDiagnostics, QueueDiagnostics, ReaderDiagnostics};
+use crate::chunk_allocator::ChunkAllocator;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+use core::fmt;
+use core::ops::Range;
+use core::task::Waker;
+use crate::listener::{Listener, Wakers};
+use alloc::vec::Vec;
+#[cfg(feature = "wal")]
+use crate::wal::{WalSink, WalSegment};
 
 /// This way you can control when chunk's memory deallocation happens.
 /// _In addition, some operations may cause deallocations as well._
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum CleanupMode{
     /// Cleanup will be called when chunk fully read.
     ///
@@ -30,48 +47,316 @@ pub enum CleanupMode{
     OnChunkRead,
     /// Cleanup will be called when new chunk created.
     OnNewChunk,
+    /// Cleanup will be called on every Nth new chunk. `EveryNChunks(1)` is the same as `OnNewChunk`.
+    ///
+    /// Amortize cleanup cost, when chunks are small. N should be >= 1.
+    EveryNChunks(u32),
     /// Cleanup will never be called. You should call `EventQueue::cleanup` manually.
     Never
 }
 
+/// Lock implementation, used for queue's list and readers start position.
+///
+/// Readers take start position lock only after `clear`/`truncate`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LockBackend{
+    /// Busy-wait. Lowest latency, burns CPU while contended.
+    Spin,
+    /// Busy-wait with `thread::yield_now`. Same as `Spin` without `std` feature.
+    Yield,
+    /// Blocking `std::sync::{Mutex, Condvar}`. Waiting threads sleep.
+    #[cfg(feature = "std")]
+    Std,
+    /// FIFO ticket lock - threads acquire lock in arrival order, so no writer starves.
+    /// Waits with `thread::yield_now` (busy-wait without `std` feature).
+    ///
+    /// Whole queue waits, while next-in-line thread is descheduled - prefer `ParkingLotFair`
+    /// with more writers than cores.
+    Ticket,
+    /// Blocking, eventually fair `parking_lot` mutex. Requires `parking_lot` feature.
+    #[cfg(feature = "parking_lot")]
+    ParkingLot,
+    /// Same as `ParkingLot`, but each unlock hands lock to the next waiting thread.
+    /// Fair - at the cost of throughput. Requires `parking_lot` feature.
+    #[cfg(feature = "parking_lot")]
+    ParkingLotFair,
+}
+
+/// How next chunk's capacity chosen, when last chunk is full.
+///
+/// Result is always clamped to `MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE`.
+#[derive(Clone, Copy, Debug)]
+pub enum GrowthStrategy{
+    /// Double capacity every second chunk: 4,4,8,8,16,16...
+    ///
+    /// Each chunk size repeated twice, so reader and writer can work on same-sized chunks.
+    Default,
+    /// Always `MIN_CHUNK_SIZE`.
+    Constant,
+    /// Add this value to previous chunk's capacity.
+    Linear(usize),
+    /// Double capacity every chunk: 4,8,16,32...
+    Doubling,
+    /// `fn(prev_capacity, penult_capacity) -> next_capacity`.
+    /// `penult_capacity` is 0, if there is only one chunk.
+    Custom(fn(usize, usize) -> usize),
+}
+
+impl GrowthStrategy{
+    /// Next chunk capacity, before clamping.
+    #[inline]
+    pub fn next_capacity(&self, prev: usize, penult: usize) -> usize{
+        match *self{
+            GrowthStrategy::Default => {
+                if penult == prev { prev.saturating_mul(2) } else { prev }
+            }
+            GrowthStrategy::Constant  => 0,
+            GrowthStrategy::Linear(step) => prev.saturating_add(step),
+            GrowthStrategy::Doubling  => prev.saturating_mul(2),
+            GrowthStrategy::Custom(f) => f(prev, penult),
+        }
+    }
+}
+
+/// Queue-wide eviction of stale readers. Checked on cleanup.
+///
+/// Evicted reader releases its chunk, and all its read attempts fail with [ReaderExpired].
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StaleReaderPolicy{
+    /// Evict reader, not read for this long.
+    pub idle: Option<Duration>,
+    /// Evict reader, which pins more than this - bytes of item slots from reader's
+    /// chunk to the queue end.
+    pub max_lag_bytes: Option<usize>,
+}
+
+#[cfg(feature = "std")]
+impl StaleReaderPolicy{
+    /// Readers never evicted.
+    pub const NEVER: Self = Self{ idle: None, max_lag_bytes: None };
+}
+
+/// Value, which could not be pushed - because new chunk could not be allocated.
+/// Either allocation failed, or [mpmc::Settings::PREALLOCATED_CHUNKS](crate::mpmc::Settings::PREALLOCATED_CHUNKS)
+/// exhausted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct AllocError<T>(pub T);
+
+impl<T> fmt::Debug for AllocError<T>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AllocError(..)")
+    }
+}
+
+impl<T> fmt::Display for AllocError<T>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventQueue chunk allocation failed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for AllocError<T>{}
+
+/// Value, which could not be pushed by
+/// [mpmc::EventQueue::try_push_nonblocking](crate::mpmc::EventQueue::try_push_nonblocking).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TryPushError<T>{
+    /// Queue is locked by other writer (or by cleanup/clear).
+    WouldBlock(T),
+    /// See [AllocError].
+    Alloc(T),
+}
+
+impl<T> TryPushError<T>{
+    /// Value, which could not be pushed.
+    #[inline]
+    pub fn into_inner(self) -> T{
+        match self{
+            TryPushError::WouldBlock(value) | TryPushError::Alloc(value) => value
+        }
+    }
+}
+
+impl<T> From<AllocError<T>> for TryPushError<T>{
+    #[inline]
+    fn from(error: AllocError<T>) -> Self {
+        TryPushError::Alloc(error.0)
+    }
+}
+
+impl<T> fmt::Debug for TryPushError<T>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            TryPushError::WouldBlock(_) => f.write_str("WouldBlock(..)"),
+            TryPushError::Alloc(_) => f.write_str("Alloc(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for TryPushError<T>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self{
+            TryPushError::WouldBlock(_) => f.write_str("EventQueue is locked"),
+            TryPushError::Alloc(_) => f.write_str("EventQueue chunk allocation failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for TryPushError<T>{}
+
+/// Freed chunk description, passed to `Settings::ON_CHUNK_FREE`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FreedChunkInfo{
+    /// Sequential chunk number. Starts from 0.
+    pub id: u64,
+    pub capacity: usize,
+    /// Items in chunk.
+    pub len: usize,
+}
+
 pub trait Settings{
     const MIN_CHUNK_SIZE : u32;
     const MAX_CHUNK_SIZE : u32;
     const CLEANUP        : CleanupMode;
+    const GROWTH         : GrowthStrategy;
+    /// extend sizes new chunk by iterator's size_hint.
+    const EXTEND_SIZE_HINT: bool;
 
     // for spmc/mpmc
     /// Lock on new chunk cleanup event. Will dead-lock if already locked.
     const LOCK_ON_NEW_CHUNK_CLEANUP: bool;
     /// Call cleanup on unsubscribe?
     const CLEANUP_IN_UNSUBSCRIBE: bool;
+    /// Drop pushed values immediately, if there is no readers.
+    /// Should be used only with list under lock (readers_count is read in push).
+    const DROP_WITHOUT_READERS: bool;
+    /// Publish last chunk's len to readers every N pushes. 1 - publish every push.
+    const PUBLISH_BATCH: u32;
+    /// Use [Config] stored in EventQueue, instead of MIN_CHUNK_SIZE/MAX_CHUNK_SIZE/CLEANUP.
+    const RUNTIME_CONFIG: bool;
+    /// Collect [Stats].
+    const STATS: bool;
+    /// Store push time of each item.
+    #[cfg(feature = "std")]
+    const TIMESTAMPS: bool;
+    /// Call truncate_older_than(RETENTION) on new chunk.
+    #[cfg(feature = "std")]
+    const RETENTION: Option<Duration>;
+    /// Readers skip items older than ITEM_TTL. Call truncate_older_than(ITEM_TTL) on new chunk
+    /// and cleanup. Requires TIMESTAMPS.
+    #[cfg(feature = "std")]
+    const ITEM_TTL: Option<Duration>;
+    /// Every reader gets lease, unless NEVER.
+    #[cfg(feature = "std")]
+    const STALE_READERS: StaleReaderPolicy;
+    /// Allocate all chunks at construction. Never allocate in push/extend.
+    /// Requires MIN_CHUNK_SIZE == MAX_CHUNK_SIZE. 0 - disabled.
+    const PREALLOCATED_CHUNKS: u32;
+    /// Max freed chunks kept for reuse. 0 - freed chunks deallocated.
+    const RECYCLE_POOL_SIZE: usize;
+    /// Called in free_chunk. Under list lock (or by spmc writer).
+    const ON_CHUNK_FREE: Option<fn(FreedChunkInfo)>;
+    /// Used for all chunks allocations/deallocations.
+    const CHUNK_ALLOCATOR: ChunkAllocator;
+    /// Wake registered wakers after publish.
+    const NOTIFY: bool;
+    /// list and start_position lock.
+    const LOCK: LockBackend;
+    /// Items with bigger `size_of` stored boxed.
+    const SPILL_THRESHOLD: usize;
+}
+
+/// Runtime version of [Settings] chunk sizes and cleanup mode.
+/// Used instead of constants, when [Settings::RUNTIME_CONFIG] is true.
+#[derive(Clone, Copy)]
+pub(crate) struct Config{
+    pub min_chunk_size: u32,
+    pub max_chunk_size: u32,
+    pub cleanup: CleanupMode,
+    pub growth: GrowthStrategy,
+}
+
+impl Config{
+    pub fn from_settings<S: Settings>() -> Self{
+        Self{
+            min_chunk_size: S::MIN_CHUNK_SIZE,
+            max_chunk_size: S::MAX_CHUNK_SIZE,
+            cleanup: S::CLEANUP,
+            growth: S::GROWTH,
+        }
+    }
 }
 
 pub struct List<T, S: Settings>{
     first: *mut DynamicChunk<T, S>,
     last : *mut DynamicChunk<T, S>,
-    chunk_id_counter: usize,
+    chunk_id_counter: u64,
     total_capacity: usize,
 
     readers_count: u32,
 
+    /// Leases of readers with TTL.
+    #[cfg(feature = "std")]
+    leases: Vec<Arc<ReaderLease<T, S>>>,
+
+    /// Values dropped in push/extend, due to no readers. See [Settings::DROP_WITHOUT_READERS].
+    dropped_count: usize,
+
+    /// Pushed into last chunk, but not yet visible to readers. See [Settings::PUBLISH_BATCH].
+    unpublished_len: u32,
+
+    /// For [CleanupMode::EveryNChunks].
+    new_chunks_since_cleanup: u32,
+
     /// 0 - means no penult
     penult_chunk_size: u32,
 
-    #[cfg(feature = "double_buffering")]
-    /// Biggest freed chunk
-    free_chunk: Option<DynamicChunkRecycled<T, S>>,
+    /// Allocated in advance, never used chunks. Used before allocating new one.
+    /// Also, freed chunks returns here with [Settings::PREALLOCATED_CHUNKS].
+    reserved_chunks: Vec<DynamicChunkRecycled<T, S>>,
+
+    /// Up to RECYCLE_POOL_SIZE biggest freed chunks
+    free_chunks: Vec<DynamicChunkRecycled<T, S>>,
+
+    /// Gets every pushed value. Detached on first error.
+    #[cfg(feature = "wal")]
+    wal: Option<alloc::boxed::Box<dyn WalSink<T>>>,
+    #[cfg(feature = "wal")]
+    wal_error: Option<std::io::Error>,
 }
 
 pub struct EventQueue<T, S: Settings>{
-    pub(crate) list  : Mutex<List<T, S>>,
+    pub(crate) list  : CachePadded<SettingsMutex<S, List<T, S>>>,
 
     /// Separate lock from list::start_position_epoch, is safe, because start_point_epoch encoded in
     /// chunk's atomic len+epoch.
     // TODO: Make RWLock? Bench.
     // TODO: Optioned
-    pub(crate) start_position: SpinMutex<Option<Cursor<T, S>>>,
+    pub(crate) start_position: SettingsMutex<S, Option<Cursor<T, S>>>,
+
+    /// Never changes. Used only with [Settings::RUNTIME_CONFIG].
+    config: Config,
 
-    _pinned: PhantomPinned,
+    /// Updated only with [Settings::STATS].
+    stats: StatsCounters,
+
+    /// Sequence of the first retained item - at start_position, or the first chunk start.
+    /// Readable without lock. Changed under lock.
+    start_seq: AtomicU64,
+    /// Sequence after the last pushed item (not published too). Readable without lock.
+    /// Changed under lock.
+    end_seq: AtomicU64,
+
+    /// Chunks in list, readable without lock. Changed under lock.
+    chunks_count: AtomicUsize,
+
+    /// Only with [Settings::NOTIFY].
+    wakers: SpinMutex<Wakers>,
+    has_wakers: AtomicBool,
+    #[cfg(feature = "tokio")]
+    tokio_notify: tokio::sync::Notify,
 }
 
 //unsafe impl<T, S: Settings> Send for EventQueue<T, S>{}
@@ -79,89 +364,228 @@ pub struct EventQueue<T, S: Settings>{
 
 impl<T, S: Settings> EventQueue<T, S>
 {
-    pub fn with_capacity(new_capacity: u32) -> Pin<Arc<Self>>{
-        assert!(S::MIN_CHUNK_SIZE <= new_capacity && new_capacity <= S::MAX_CHUNK_SIZE);
+    const ZST_CHUNK_SIZE: u32 = u32::MAX / 2;
+
+    /// ZST items occupy no memory - chunk of any capacity costs the same.
+    /// So ZST queue ignores chunk size settings, and use one huge chunk - it works as
+    /// a bare per-chunk counter, without chunk allocations/cleanups in between.
+    ///
+    /// Not with [Settings::TIMESTAMPS] - timestamps stored per item.
+    #[inline(always)]
+    fn is_zst() -> bool{
+        DynamicChunk::<T, S>::slot_size() == 0
+    }
+
+    /// Returned in `Arc` - readers hold clones of it.
+    pub fn with_capacity(new_capacity: u32) -> Arc<Self>{
+        Self::with_config(Config::from_settings::<S>(), new_capacity)
+    }
+
+    pub(crate) fn with_config(config: Config, new_capacity: u32) -> Arc<Self>{
+        assert!(1 <= config.min_chunk_size && config.min_chunk_size <= config.max_chunk_size);
+        assert!(config.min_chunk_size <= new_capacity && new_capacity <= config.max_chunk_size);
+        assert!(S::PUBLISH_BATCH >= 1);
+        if S::PREALLOCATED_CHUNKS > 0 {
+            assert!(config.min_chunk_size == config.max_chunk_size,
+                "PREALLOCATED_CHUNKS requires MIN_CHUNK_SIZE == MAX_CHUNK_SIZE");
+        }
+        if let CleanupMode::EveryNChunks(n) = config.cleanup{
+            assert!(n >= 1);
+        }
+        #[cfg(feature = "std")]
+        if S::ITEM_TTL.is_some() {
+            assert!(S::TIMESTAMPS, "ITEM_TTL requires TIMESTAMPS");
+        }
+        let new_capacity = if /*constexpr*/ Self::is_zst() { Self::ZST_CHUNK_SIZE } else { new_capacity };
 
         let this = Arc::new(Self{
-            list: Mutex::new(List{
+            list: CachePadded::new(SettingsMutex::new(List{
                 first: null_mut(),
                 last: null_mut(),
                 chunk_id_counter: 0,
                 readers_count:0,
+                #[cfg(feature = "std")]
+                leases: Vec::new(),
+                dropped_count:0,
+                unpublished_len:0,
+                new_chunks_since_cleanup:0,
                 total_capacity:new_capacity as usize,
                 penult_chunk_size : 0,
-
-                #[cfg(feature = "double_buffering")]
-                free_chunk: None,
-            }),
-            start_position: SpinMutex::new(None),
-            _pinned: PhantomPinned,
+                reserved_chunks: Vec::new(),
+                free_chunks: Vec::new(),
+                #[cfg(feature = "wal")]
+                wal: None,
+                #[cfg(feature = "wal")]
+                wal_error: None,
+            })),
+            start_position: SettingsMutex::new(None),
+            config,
+            stats: StatsCounters::new(),
+            start_seq: AtomicU64::new(0),
+            end_seq: AtomicU64::new(0),
+            chunks_count: AtomicUsize::new(1),
+            wakers: SpinMutex::new(Wakers::new()),
+            has_wakers: AtomicBool::new(false),
+            #[cfg(feature = "tokio")]
+            tokio_notify: tokio::sync::Notify::new(),
         });
 
         let node = DynamicChunk::<T, S>::construct(
-            0, StartPositionEpoch::zero(), &*this, new_capacity as usize);
+            0, StartPositionEpoch::zero(), new_capacity as usize);
+        if /*constexpr*/ S::STATS {
+            this.stats.chunks_allocated.fetch_add(1, Ordering::Relaxed);
+        }
 
         {
             let mut list = this.list.lock();
             list.first = node;
             list.last  = node;
+
+            if /*constexpr*/ S::PREALLOCATED_CHUNKS > 0 {
+                // Capacity for all chunks - so freed chunks can be returned without allocation.
+                list.reserved_chunks.reserve_exact(S::PREALLOCATED_CHUNKS as usize);
+                for _ in 1..S::PREALLOCATED_CHUNKS {
+                    this.reserve_chunk(&mut list, new_capacity as usize);
+                }
+            }
         }
 
-        unsafe{ Pin::new_unchecked(this) }
+        this
     }
 
-    #[inline]
-    fn add_chunk_sized(&self, list: &mut List<T, S>, size: usize) -> &mut DynamicChunk<T, S>{
-        let node = unsafe{&mut *list.last};
-        let epoch = node.chunk_state(Ordering::Relaxed).epoch();
+    #[inline(always)]
+    pub(crate) fn min_chunk_size(&self) -> u32{
+        if /*constexpr*/ Self::is_zst() { return Self::ZST_CHUNK_SIZE; }
+        if /*constexpr*/ S::RUNTIME_CONFIG { self.config.min_chunk_size } else { S::MIN_CHUNK_SIZE }
+    }
 
-        // make new node
-        list.chunk_id_counter += 1;
+    #[inline(always)]
+    pub(crate) fn max_chunk_size(&self) -> u32{
+        if /*constexpr*/ Self::is_zst() { return Self::ZST_CHUNK_SIZE; }
+        if /*constexpr*/ S::RUNTIME_CONFIG { self.config.max_chunk_size } else { S::MAX_CHUNK_SIZE }
+    }
+
+    #[inline(always)]
+    pub(crate) fn cleanup_mode(&self) -> CleanupMode{
+        if /*constexpr*/ S::RUNTIME_CONFIG { self.config.cleanup } else { S::CLEANUP }
+    }
+
+    /// Allocate chunk for future use.
+    fn reserve_chunk(&self, list: &mut List<T, S>, size: usize){
+        if /*constexpr*/ S::STATS {
+            self.stats.chunks_allocated.fetch_add(1, Ordering::Relaxed);
+        }
+        let chunk = DynamicChunk::<T, S>::construct(0, StartPositionEpoch::zero(), size);
+        list.reserved_chunks.push(unsafe{ DynamicChunk::recycle(chunk) });
+    }
+
+    #[inline]
+    fn add_chunk_sized(&self, list: &mut List<T, S>, size: usize) -> *mut DynamicChunk<T, S>{
+        // Infallible - never None.
+        self.try_add_chunk_sized::<false>(list, size).unwrap()
+    }
 
-        #[cfg(not(feature = "double_buffering"))]
-        let new_node = DynamicChunk::<T, S>::construct(list.chunk_id_counter, epoch, self, size);
+    /// With `FALLIBLE_ALLOC` returns None on allocation failure. Otherwise - never None.
+    fn try_add_chunk_sized<const FALLIBLE_ALLOC: bool>(&self, list: &mut List<T, S>, size: usize)
+        -> Option<*mut DynamicChunk<T, S>>
+    {
+        self.flush(list);
 
-        #[cfg(feature = "double_buffering")]
-        let new_node = {
+        let node = unsafe{&*list.last};
+        let epoch = node.chunk_state(Ordering::Relaxed).epoch();
+        let new_id = list.chunk_id_counter + 1;
+
+        // Preallocated chunks first.
+        let reserved_index = list.reserved_chunks.iter().position(|chunk| chunk.capacity() == size);
+        let new_node =
+        if let Some(index) = reserved_index {
+            if /*constexpr*/ S::STATS {
+                self.stats.chunks_recycled.fetch_add(1, Ordering::Relaxed);
+            }
+            let reserved_chunk = list.reserved_chunks.swap_remove(index);
+            unsafe { DynamicChunk::from_recycled(reserved_chunk, new_id, epoch) }
+        } else {
             let mut new_node: *mut DynamicChunk<T, S> = null_mut();
 
-            if let Some(recycled_chunk) = &list.free_chunk {
-                // Check if recycled_chunk have exact capacity.
-                if recycled_chunk.capacity() == size {
-                    // unwrap_unchecked()
-                    new_node =
-                    match list.free_chunk.take() {
-                        Some(recycled_chunk) => {
-                            unsafe { DynamicChunk::from_recycled(
-                                recycled_chunk,
-                                list.chunk_id_counter,
-                                epoch) }
-                        }, None => unsafe { std::hint::unreachable_unchecked() },
+            if /*constexpr*/ S::RECYCLE_POOL_SIZE > 0 {
+                // Look for recycled chunk with exact capacity.
+                match list.free_chunks.iter().position(|chunk| chunk.capacity() == size){
+                    Some(index) => {
+                        if /*constexpr*/ S::STATS {
+                            self.stats.chunks_recycled.fetch_add(1, Ordering::Relaxed);
+                        }
+                        let recycled_chunk = list.free_chunks.swap_remove(index);
+                        new_node = unsafe { DynamicChunk::from_recycled(
+                            recycled_chunk,
+                            new_id,
+                            epoch) };
+                    }
+                    None => {
+                        // Chunks grow - smaller ones unlikely will be needed.
+                        // TODO: try free in cleanup somehow
+                        list.free_chunks.retain(|chunk| chunk.capacity() > size);
                     }
-                } else {
-                    // TODO: try free in cleanup somehow
-                    list.free_chunk = None;
                 }
             }
 
             if new_node.is_null(){
-                new_node = DynamicChunk::<T, S>::construct(list.chunk_id_counter, epoch, self, size);
+                new_node =
+                    if FALLIBLE_ALLOC {
+                        DynamicChunk::<T, S>::try_construct(new_id, epoch, size)
+                    } else {
+                        DynamicChunk::<T, S>::construct(new_id, epoch, size)
+                    };
+                if new_node.is_null(){
+                    return None;
+                }
+                if /*constexpr*/ S::STATS {
+                    self.stats.chunks_allocated.fetch_add(1, Ordering::Relaxed);
+                }
             }
             new_node
         };
 
+        list.chunk_id_counter = new_id;
+        self.chunks_count.fetch_add(1, Ordering::Relaxed);
+
+        // Writer is here, so node's len is final.
+        let first_seq = node.first_seq() + node.chunk_state(Ordering::Relaxed).len() as u64;
+        unsafe{ DynamicChunk::set_first_seq(new_node, first_seq); }
+
+        yield_point!(AddChunk);
+
         // connect
+        #[cfg(feature = "std")]
+        if let Some(now) = crate::utils::now(){
+            node.set_closed_at(now);
+        }
         node.set_next(new_node, Ordering::Release);
         list.last = new_node;
         list.penult_chunk_size = node.capacity() as u32;
         list.total_capacity += size;
 
-        unsafe{&mut *new_node}
+        #[cfg(feature = "wal")]
+        self.wal_start_segment(list, WalSegment{chunk_id: new_id, first: Position::from_sequence(first_seq)});
+
+        Some(new_node)
     }
 
     #[inline]
     fn on_new_chunk_cleanup(&self, list: &mut List<T, S>){
-        if S::CLEANUP == CleanupMode::OnNewChunk{
+        let need_cleanup = match self.cleanup_mode(){
+            CleanupMode::OnNewChunk => true,
+            CleanupMode::EveryNChunks(n) => {
+                list.new_chunks_since_cleanup += 1;
+                if list.new_chunks_since_cleanup >= n {
+                    list.new_chunks_since_cleanup = 0;
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => false
+        };
+        if need_cleanup{
             // this should acts as compile-time-if.
             if S::LOCK_ON_NEW_CHUNK_CLEANUP{
                 let _lock = self.list.lock();
@@ -173,49 +597,328 @@ impl<T, S: Settings> EventQueue<T, S>
     }
 
     #[inline]
-    fn add_chunk(&self, list: &mut List<T, S>) -> &mut DynamicChunk<T, S>{
+    /// Returns None with [Settings::PREALLOCATED_CHUNKS], if there is no free chunk.
+    /// Or with `FALLIBLE_ALLOC`, if allocation failed.
+    fn add_chunk<const FALLIBLE_ALLOC: bool>(&self, list: &mut List<T, S>) -> Option<*mut DynamicChunk<T, S>>{
+        self.add_chunk_hinted::<FALLIBLE_ALLOC>(list, 0)
+    }
+
+    /// Same as [add_chunk](Self::add_chunk), but new chunk have at least `min_size` capacity
+    /// (up to `MAX_CHUNK_SIZE`) - if more items are known to come.
+    #[inline]
+    fn add_chunk_hinted<const FALLIBLE_ALLOC: bool>(&self, list: &mut List<T, S>, min_size: usize) -> Option<*mut DynamicChunk<T, S>>{
         let node = unsafe{&*list.last};
 
+        #[cfg(feature = "std")]
+        if let Some(age) = S::RETENTION{
+            self.truncate_older_than(list, age);
+        }
+        #[cfg(feature = "std")]
+        self.truncate_expired(list);
         self.on_new_chunk_cleanup(list);
 
-        // Size pattern 4,4,8,8,16,16
-        let new_size: usize = {
-            if list.penult_chunk_size as usize == node.capacity(){
-                std::cmp::min(node.capacity() * 2, S::MAX_CHUNK_SIZE as usize)
-            } else {
-                node.capacity()
+        if /*constexpr*/ S::PREALLOCATED_CHUNKS > 0 {
+            if list.reserved_chunks.is_empty(){
+                return None;
             }
-        };
+        }
 
-        self.add_chunk_sized(list, new_size)
+        let new_size = self.next_chunk_size(node.capacity(), list.penult_chunk_size as usize)
+            .max(min_size.min(self.max_chunk_size() as usize));
+        self.try_add_chunk_sized::<FALLIBLE_ALLOC>(list, new_size)
     }
 
-    // Have 10% better performance. Observable in spmc.
+    #[inline]
+    fn next_chunk_size(&self, prev_chunk_size: usize, penult_chunk_size: usize) -> usize {
+        let growth = if /*constexpr*/ S::RUNTIME_CONFIG { self.config.growth } else { S::GROWTH };
+        growth
+            .next_capacity(prev_chunk_size, penult_chunk_size)
+            .clamp(self.min_chunk_size() as usize, self.max_chunk_size() as usize)
+    }
+
+    /// With [Settings::PREALLOCATED_CHUNKS], value which does not fit - dropped, and counted in
+    /// dropped_count.
     #[inline]
     pub fn push(&self, list: &mut List<T, S>, value: T){
-        let mut node = unsafe{&mut *list.last};
+        if self.push_impl::<false>(list, value, S::PUBLISH_BATCH).is_err(){
+            list.dropped_count += 1;
+        }
+    }
+
+    /// Fails with [Settings::PREALLOCATED_CHUNKS], if there is no free chunk.
+    /// Or if chunk allocation failed.
+    #[inline]
+    pub fn try_push(&self, list: &mut List<T, S>, value: T) -> Result<(), AllocError<T>>{
+        self.push_impl::<true>(list, value, S::PUBLISH_BATCH)
+    }
+
+    /// Same as [push](Self::push), but value published only when chunk is full,
+    /// or on [publish](Self::publish).
+    #[inline]
+    pub fn push_unpublished(&self, list: &mut List<T, S>, value: T){
+        if self.push_impl::<false>(list, value, u32::MAX).is_err(){
+            list.dropped_count += 1;
+        }
+    }
+
+    /// Same as [try_push](Self::try_push), but value published only when chunk is full,
+    /// or on [publish](Self::publish).
+    #[inline]
+    pub fn try_push_unpublished(&self, list: &mut List<T, S>, value: T) -> Result<(), AllocError<T>>{
+        self.push_impl::<true>(list, value, u32::MAX)
+    }
+
+    // Have 10% better performance. Observable in spmc.
+    // `publish_batch` - constant in all calls.
+    #[inline]
+    fn push_impl<const FALLIBLE_ALLOC: bool>(&self, list: &mut List<T, S>, value: T, publish_batch: u32) -> Result<(), AllocError<T>>{
+        yield_point!(Push);
+
+        if /*constexpr*/ S::DROP_WITHOUT_READERS && list.readers_count == 0 {
+            list.dropped_count += 1;
+            return Ok(());
+        }
+
+        if /*constexpr*/ publish_batch > 1 {
+            return self.push_batched::<FALLIBLE_ALLOC>(list, value, publish_batch);
+        }
+
+        let mut node = list.last;
 
         // Relaxed because we update only under lock
-        let chunk_state = node.chunk_state(Ordering::Relaxed);
+        let chunk_state = unsafe{ (*node).chunk_state(Ordering::Relaxed) };
         let mut storage_len = chunk_state.len();
 
-        if /*unlikely*/ storage_len == node.capacity() as u32{
-            node = self.add_chunk(&mut *list);
+        if /*unlikely*/ storage_len == unsafe{ (*node).capacity() } as u32{
+            node = match self.add_chunk::<FALLIBLE_ALLOC>(&mut *list){
+                Some(node) => node,
+                None => return Err(AllocError(value)),
+            };
+            storage_len = 0;
+        }
+
+        unsafe { DynamicChunk::push_at(node, value, storage_len, chunk_state, Ordering::Release); }
+        #[cfg(feature = "wal")]
+        self.wal_append(list, node, storage_len..storage_len+1);
+        self.update_end_seq(list);
+        self.notify();
+
+        if /*constexpr*/ S::STATS {
+            self.stats.pushed.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Publish len only each `publish_batch` push, or when chunk is full.
+    #[inline]
+    fn push_batched<const FALLIBLE_ALLOC: bool>(&self, list: &mut List<T, S>, value: T, publish_batch: u32) -> Result<(), AllocError<T>>{
+        let mut node = list.last;
+
+        // Relaxed because we update only under lock
+        let mut chunk_state = unsafe{ (*node).chunk_state(Ordering::Relaxed) };
+        let mut storage_len = chunk_state.len() + list.unpublished_len;
+
+        if /*unlikely*/ storage_len == unsafe{ (*node).capacity() } as u32{
+            // Full chunk always published.
+            debug_assert!(list.unpublished_len == 0);
+            node = match self.add_chunk::<FALLIBLE_ALLOC>(&mut *list){
+                Some(node) => node,
+                None => return Err(AllocError(value)),
+            };
+            chunk_state = unsafe{ (*node).chunk_state(Ordering::Relaxed) };
             storage_len = 0;
         }
 
-        unsafe { node.push_at(value, storage_len, chunk_state, Ordering::Release); }
+        unsafe { DynamicChunk::write_at(node, storage_len, value); }
+        list.unpublished_len += 1;
+        self.update_end_seq(list);
+        #[cfg(feature = "wal")]
+        self.wal_append(list, node, storage_len..storage_len+1);
+
+        let node = unsafe{ &*node };
+        if list.unpublished_len == publish_batch || storage_len+1 == node.capacity() as u32 {
+            chunk_state.set_len(storage_len+1);
+            node.set_chunk_state(chunk_state, Ordering::Release);
+            list.unpublished_len = 0;
+            self.notify();
+        }
+
+        if /*constexpr*/ S::STATS {
+            self.stats.pushed.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Make all pushed values visible to readers. See [Settings::PUBLISH_BATCH].
+    #[inline]
+    pub fn flush(&self, list: &mut List<T, S>){
+        if /*constexpr*/ S::PUBLISH_BATCH == 1 {
+            return;
+        }
+        self.publish(list);
+    }
+
+    /// Same as [flush](Self::flush), but for values pushed with [push_unpublished](Self::push_unpublished).
+    #[inline]
+    pub fn publish(&self, list: &mut List<T, S>){
+        if list.unpublished_len == 0 {
+            return;
+        }
+
+        let node = unsafe{&*list.last};
+        let mut chunk_state = node.chunk_state(Ordering::Relaxed);
+        chunk_state.set_len(chunk_state.len() + list.unpublished_len);
+        node.set_chunk_state(chunk_state, Ordering::Release);
+        list.unpublished_len = 0;
+        self.notify();
+    }
+
+    /// Replace write-ahead log sink. Returns previous one.
+    /// New sink starts segment at [end_position](Self::end_position).
+    #[cfg(feature = "wal")]
+    pub fn set_wal_sink(&self, list: &mut List<T, S>, sink: Option<alloc::boxed::Box<dyn WalSink<T>>>)
+        -> Option<alloc::boxed::Box<dyn WalSink<T>>>
+    {
+        let prev = core::mem::replace(&mut list.wal, sink);
+        let segment = WalSegment{
+            chunk_id: unsafe{(*list.last).id()},
+            first: self.end_position(list)
+        };
+        self.wal_start_segment(list, segment);
+        prev
+    }
+
+    /// Error, which detached write-ahead log sink.
+    #[cfg(feature = "wal")]
+    pub fn take_wal_error(&self, list: &mut List<T, S>) -> Option<std::io::Error>{
+        list.wal_error.take()
+    }
+
+    #[cfg(feature = "wal")]
+    pub fn sync_wal(&self, list: &mut List<T, S>) -> std::io::Result<()>{
+        match &mut list.wal{
+            Some(sink) => sink.sync(),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "wal")]
+    fn wal_start_segment(&self, list: &mut List<T, S>, segment: WalSegment){
+        if let Some(sink) = &mut list.wal{
+            if let Err(error) = sink.start_segment(segment){
+                list.wal = None;
+                list.wal_error = Some(error);
+            }
+        }
+    }
+
+    /// Append `node` items in `range` to write-ahead log.
+    #[cfg(feature = "wal")]
+    #[inline]
+    fn wal_append(&self, list: &mut List<T, S>, node: *mut DynamicChunk<T, S>, range: Range<u32>){
+        let sink = match &mut list.wal{
+            Some(sink) => sink,
+            None => return,
+        };
+        for index in range {
+            let value = unsafe{ (*node).get_unchecked(index as usize) };
+            if let Err(error) = sink.append(value){
+                list.wal = None;
+                list.wal_error = Some(error);
+                return;
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn wakers(&self) -> &SpinMutex<Wakers>{
+        &self.wakers
+    }
+
+    /// `waker` will be woken once, on next publish. Only with [Settings::NOTIFY].
+    pub fn register_waker(&self, waker: &Waker){
+        debug_assert!(S::NOTIFY);
+        let mut wakers = self.wakers.lock();
+        wakers.register(waker);
+        self.has_wakers.store(true, Ordering::Relaxed);
+        drop(wakers);
+
+        // Pairs with fence in notify. Either caller will see published items,
+        // or writer will see registered waker.
+        fence(Ordering::SeqCst);
+    }
+
+    /// Future, completed on next publish. Only with [Settings::NOTIFY].
+    ///
+    /// Unlike [register_waker](Self::register_waker), leaves nothing behind, if dropped.
+    #[cfg(feature = "tokio")]
+    pub fn notified(&self) -> tokio::sync::futures::Notified<'_>{
+        debug_assert!(S::NOTIFY);
+        // Notified counts notify_waiters calls since creation. So create it before flag set.
+        let notified = self.tokio_notify.notified();
+        {
+            let _wakers = self.wakers.lock();
+            self.has_wakers.store(true, Ordering::Relaxed);
+        }
+        // Same as in register_waker.
+        fence(Ordering::SeqCst);
+        notified
+    }
+
+    /// Future, completed on next publish. Only with [Settings::NOTIFY].
+    ///
+    /// Same as [notified](Self::notified), but executor agnostic.
+    #[cfg_attr(feature = "tokio", allow(dead_code))]
+    pub(crate) fn listen(&self) -> Listener<'_, T, S>{
+        debug_assert!(S::NOTIFY);
+        let epoch = {
+            let wakers = self.wakers.lock();
+            self.has_wakers.store(true, Ordering::Relaxed);
+            wakers.epoch()
+        };
+        // Same as in register_waker.
+        fence(Ordering::SeqCst);
+        Listener::new(self, epoch)
+    }
+
+    /// Should be called after publish.
+    #[inline]
+    fn notify(&self){
+        if /*constexpr*/ !S::NOTIFY {
+            return;
+        }
+        fence(Ordering::SeqCst);
+        if self.has_wakers.load(Ordering::Relaxed){
+            self.wake_all();
+        }
+    }
+
+    #[cold]
+    fn wake_all(&self){
+        let wakers = {
+            let mut wakers = self.wakers.lock();
+            self.has_wakers.store(false, Ordering::Relaxed);
+            wakers.take()
+        };
+        for (_, waker) in wakers{
+            waker.wake();
+        }
+
+        #[cfg(feature = "tokio")]
+        self.tokio_notify.notify_waiters();
     }
 
 /*
     #[inline]
     pub fn push(&self, list: &mut List<T, S>, value: T){
-        let node = unsafe{&mut *list.last};
+        let node = list.last;
 
-        if let Err(err) = node.try_push(value, Ordering::Release){
+        if let Err(err) = unsafe{ DynamicChunk::try_push(node, value, Ordering::Release) }{
             unsafe {
-                self.add_chunk(&mut *list)
-                    .push_unchecked(err.value, Ordering::Release);
+                DynamicChunk::push_unchecked(
+                    self.add_chunk::<false>(&mut *list).unwrap(),
+                    err.value, Ordering::Release
+                );
             }
         }
     }
@@ -226,66 +929,469 @@ impl<T, S: Settings> EventQueue<T, S>
     pub fn extend<I>(&self, list: &mut List<T, S>, iter: I)
         where I: IntoIterator<Item = T>
     {
-        let mut node = unsafe{&mut *list.last};
+        let _ = self.extend_impl::<false, I>(list, iter);
+        self.update_end_seq(list);
+        self.notify();
+    }
+
+    /// Stops on first value, which does not fit. See [try_push](Self::try_push).
+    #[inline]
+    pub fn try_extend<I>(&self, list: &mut List<T, S>, iter: I) -> Result<(), AllocError<T>>
+        where I: IntoIterator<Item = T>
+    {
+        let result = self.extend_impl::<true, I>(list, iter);
+        self.update_end_seq(list);
+        self.notify();
+        result
+    }
+
+    fn extend_impl<const FALLIBLE_ALLOC: bool, I>(&self, list: &mut List<T, S>, iter: I) -> Result<(), AllocError<T>>
+        where I: IntoIterator<Item = T>
+    {
+        yield_point!(Push);
+
+        if /*constexpr*/ S::DROP_WITHOUT_READERS && list.readers_count == 0 {
+            list.dropped_count += iter.into_iter().count();
+            return Ok(());
+        }
+
+        // extend starts from published len.
+        self.flush(list);
+
+        let mut node = list.last;
+        // For stats and WAL
+        let mut node_start_len = unsafe{ (*node).chunk_state(Ordering::Relaxed).len() };
 
         let mut iter = iter.into_iter();
 
-        while node.extend(&mut iter, Ordering::Release).is_err(){
+        while unsafe{ DynamicChunk::extend(node, &mut iter, Ordering::Release) }.is_err(){
             match iter.next() {
-                None => {return;}
+                None => {break;}
                 Some(value) => {
-                    // add chunk and push value there
-                    node = self.add_chunk(&mut *list);
-                    unsafe{ node.push_unchecked(value, Ordering::Relaxed); }
+                    let node_len = unsafe{ (*node).chunk_state(Ordering::Relaxed).len() };
+                    if /*constexpr*/ S::STATS {
+                        self.stats.pushed.fetch_add((node_len - node_start_len) as usize, Ordering::Relaxed);
+                    }
+                    #[cfg(feature = "wal")]
+                    self.wal_append(list, node, node_start_len..node_len);
+
+                    // add chunk and push value there.
+                    // Big enough for the rest of iter, to not allocate chunk after chunk on bulk load.
+                    let remaining = if /*constexpr*/ S::EXTEND_SIZE_HINT {
+                        iter.size_hint().0.saturating_add(1)
+                    } else {
+                        0
+                    };
+                    node = match self.add_chunk_hinted::<FALLIBLE_ALLOC>(&mut *list, remaining){
+                        Some(node) => node,
+                        None => {
+                            if FALLIBLE_ALLOC {
+                                return Err(AllocError(value));
+                            }
+                            // Out of preallocated chunks. Drop the rest.
+                            list.dropped_count += 1 + iter.count();
+                            return Ok(());
+                        }
+                    };
+                    unsafe{ DynamicChunk::push_unchecked(node, value, Ordering::Relaxed); }
+                    node_start_len = 0;
                 }
             };
         }
+
+        let node_len = unsafe{ (*node).chunk_state(Ordering::Relaxed).len() };
+        if /*constexpr*/ S::STATS {
+            self.stats.pushed.fetch_add((node_len - node_start_len) as usize, Ordering::Relaxed);
+        }
+        #[cfg(feature = "wal")]
+        self.wal_append(list, node, node_start_len..node_len);
+        Ok(())
     }
 
-    /// EventReader will start receive events from NOW.
-    /// It will not see events that was pushed BEFORE subscription.
-    pub fn subscribe(&self, list: &mut List<T, S>) -> EventReader<T, S>{
-        if list.readers_count == 0{
-            // Keep alive. Decrements in unsubscribe
-            unsafe { Arc::increment_strong_count(self); }
+    /// Same as [extend](Self::extend), but with memcpy per chunk.
+    pub fn extend_from_slice(&self, list: &mut List<T, S>, mut values: &[T])
+        where T: Copy
+    {
+        yield_point!(Push);
+
+        if /*constexpr*/ S::DROP_WITHOUT_READERS && list.readers_count == 0 {
+            list.dropped_count += values.len();
+            return;
         }
-        list.readers_count += 1;
+
+        // extend starts from published len.
+        self.flush(list);
+
+        let mut node = list.last;
+        loop{
+            #[cfg(feature = "wal")]
+            let node_start_len = unsafe{ (*node).chunk_state(Ordering::Relaxed).len() };
+            let copied = unsafe{ DynamicChunk::extend_from_slice(node, values, Ordering::Release) };
+            if /*constexpr*/ S::STATS {
+                self.stats.pushed.fetch_add(copied, Ordering::Relaxed);
+            }
+            #[cfg(feature = "wal")]
+            self.wal_append(list, node, node_start_len..node_start_len + copied as u32);
+            values = &values[copied..];
+            if values.is_empty(){
+                break;
+            }
+
+            let remaining = if /*constexpr*/ S::EXTEND_SIZE_HINT { values.len() } else { 0 };
+            node = match self.add_chunk_hinted::<false>(&mut *list, remaining){
+                Some(node) => node,
+                None => {
+                    // Out of preallocated chunks. Drop the rest.
+                    list.dropped_count += values.len();
+                    break;
+                }
+            };
+        }
+        self.update_end_seq(list);
+        self.notify();
+    }
+
+    /// Register `count` readers at the tail of the queue.
+    /// Returns position and epoch, all of them should start from.
+    #[inline]
+    fn enter_readers(&self, list: &mut List<T, S>, count: u32) -> (Cursor<T, S>, StartPositionEpoch){
+        self.flush(list);
+
+        list.readers_count += count;
 
         let last_chunk = unsafe{&*list.last};
         let chunk_state = last_chunk.chunk_state(Ordering::Relaxed);
 
         // Enter chunk
-        last_chunk.readers_entered().fetch_add(1, Ordering::AcqRel);
+        last_chunk.readers_entered().fetch_add(count as usize, Ordering::AcqRel);
 
-        EventReader{
-            position: Cursor{chunk: last_chunk, index: chunk_state.len() as usize},
-            start_position_epoch: chunk_state.epoch()
-        }
+        (
+            Cursor{chunk: last_chunk, index: chunk_state.len() as usize},
+            chunk_state.epoch()
+        )
     }
 
-    // Called from EventReader Drop
-    //
-    // `this_ptr` instead of `&self`, because `&self` as reference should be valid during
-    // function call. And we drop it sometimes.... through `Arc::decrement_strong_count`.
-    pub(crate) fn unsubscribe(this_ptr: NonNull<Self>, event_reader: &EventReader<T, S>){
-        let this = unsafe { this_ptr.as_ref() };
-        let mut list = this.list.lock();
-
-        // Exit chunk
-        unsafe{&*event_reader.position.chunk}.read_completely_times().fetch_add(1, Ordering::AcqRel);
+    /// EventReader will start receive events from NOW.
+    /// It will not see events that was pushed BEFORE subscription.
+    pub fn subscribe(this: &Arc<Self>, list: &mut List<T, S>) -> EventReader<T, S>{
+        let (position, start_position_epoch) = this.enter_readers(list, 1);
+        #[cfg(feature = "std")]
+        let lease = this.reader_lease(list, position.chunk, None);
+        EventReader{event: this.clone(), position, start_position_epoch, missed: 0, cleanup: true, #[cfg(feature = "std")] lease}
+    }
 
-        if S::CLEANUP_IN_UNSUBSCRIBE && S::CLEANUP != CleanupMode::Never{
-            if list.first as *const _ == event_reader.position.chunk {
-                this.cleanup_impl(&mut *list);
+    /// Same as [subscribe](Self::subscribe), with per-reader settings.
+    pub fn subscribe_with_options(this: &Arc<Self>, list: &mut List<T, S>, options: ReaderOptions) -> EventReader<T, S>{
+        let (position, start_position_epoch) = this.enter_readers(list, 1);
+        #[cfg(feature = "std")]
+        let lease = match options.ttl{
+            None => this.reader_lease(list, position.chunk, options.name),
+            Some(ttl) => {
+                let lease = Arc::new(ReaderLease::new(ttl, position.chunk, options.name));
+                list.leases.push(lease.clone());
+                Some(lease)
             }
-        }
+        };
+        EventReader{event: this.clone(), position, start_position_epoch, missed: 0, cleanup: options.cleanup, #[cfg(feature = "std")] lease}
+    }
 
-        list.readers_count -= 1;
-        if list.readers_count == 0{
-            drop(list);
-            // Safe to self-destruct
-            unsafe { Arc::decrement_strong_count(this_ptr.as_ptr()); }
-        }
+    /// Same as [subscribe](Self::subscribe). Reader's `name` shown in diagnostics and stats.
+    #[cfg(feature = "std")]
+    pub fn subscribe_named(this: &Arc<Self>, list: &mut List<T, S>, name: String) -> EventReader<T, S>{
+        Self::subscribe_with_options(this, list, ReaderOptions{ name: Some(name), ..Default::default() })
+    }
+
+    /// Lease for new reader at `chunk`. Only if reader should be tracked by queue -
+    /// named, or with [Settings::STALE_READERS].
+    #[cfg(feature = "std")]
+    #[inline]
+    fn reader_lease(&self, list: &mut List<T, S>, chunk: *const DynamicChunk<T, S>, name: Option<String>)
+        -> Option<Arc<ReaderLease<T, S>>>
+    {
+        if S::STALE_READERS == StaleReaderPolicy::NEVER && name.is_none() {
+            return None;
+        }
+        let ttl = S::STALE_READERS.idle.unwrap_or(Duration::MAX);
+        let lease = Arc::new(ReaderLease::new(ttl, chunk, name));
+        list.leases.push(lease.clone());
+        Some(lease)
+    }
+
+    /// Same as [subscribe](Self::subscribe), but reader will be expired by cleanup,
+    /// if not read for `ttl`. Expired reader release its chunk.
+    #[cfg(feature = "std")]
+    pub fn subscribe_with_ttl(this: &Arc<Self>, list: &mut List<T, S>, ttl: Duration) -> EventReader<T, S>{
+        Self::subscribe_with_options(this, list, ReaderOptions{ ttl: Some(ttl), ..Default::default() })
+    }
+
+    /// Same as [subscribe](Self::subscribe), but for `count` readers at once.
+    /// Chunk counters updated once for the whole batch.
+    pub fn subscribe_batch(this: &Arc<Self>, list: &mut List<T, S>, count: u32) -> Vec<EventReader<T, S>>{
+        if count == 0{
+            return Vec::new();
+        }
+
+        let (position, start_position_epoch) = this.enter_readers(list, count);
+        (0..count)
+            .map(|_| EventReader{
+                event: this.clone(),
+                position,
+                start_position_epoch,
+                missed: 0,
+                cleanup: true,
+                #[cfg(feature = "std")]
+                lease: this.reader_lease(list, position.chunk, None)
+            })
+            .collect()
+    }
+
+    /// Position, [subscribe](Self::subscribe)d reader would start from.
+    pub fn subscribe_position(&self, list: &mut List<T, S>) -> Position{
+        self.flush(list);
+        let last = unsafe{&*list.last};
+        Position::from_sequence(last.first_seq() + last.chunk_state(Ordering::Relaxed).len() as u64)
+    }
+
+    /// Reader at `position`. If `position` was already reclaimed or truncated - at the first
+    /// retained item. Returns count of items skipped that way too.
+    ///
+    /// `position` should not be past [subscribe_position](Self::subscribe_position).
+    /// Reader is not tracked - no lease.
+    pub fn subscribe_at(this: &Arc<Self>, list: &mut List<T, S>, position: Position) -> (EventReader<T, S>, u64){
+        this.flush(list);
+
+        let start = match *this.start_position.lock(){
+            Some(start_position) => start_position,
+            None => Cursor{ chunk: list.first, index: 0 }
+        };
+        let start_seq = Position::from(start).sequence();
+        let (mut cursor, skipped) =
+            if position.sequence() <= start_seq {
+                (start, start_seq - position.sequence())
+            } else {
+                let last = unsafe{&*list.last};
+                (Cursor{ chunk: last, index: last.chunk_state(Ordering::Relaxed).len() as usize }, 0)
+            };
+
+        if skipped == 0 {
+            unsafe {
+                foreach_chunk(
+                    start.chunk,
+                    null(),
+                    Ordering::Relaxed,      // we're under mutex
+                    |chunk| {
+                        let len = chunk.chunk_state(Ordering::Relaxed).len() as u64;
+                        let offset = position.sequence().saturating_sub(chunk.first_seq());
+                        if offset <= len {
+                            cursor = Cursor{ chunk, index: offset as usize };
+                            return Break(());
+                        }
+                        Continue(())
+                    }
+                );
+            }
+        }
+
+        list.readers_count += 1;
+
+        // Enter chunk
+        let chunk = unsafe{&*cursor.chunk};
+        chunk.readers_entered().fetch_add(1, Ordering::AcqRel);
+
+        let reader = EventReader{
+            event: this.clone(),
+            position: cursor,
+            start_position_epoch: chunk.chunk_state(Ordering::Relaxed).epoch(),
+            missed: 0,
+            cleanup: true,
+            #[cfg(feature = "std")]
+            lease: None
+        };
+        (reader, skipped)
+    }
+
+    /// New reader at the same position as `event_reader`. With the same TTL (if any) and options.
+    ///
+    /// Should be called under lock, even for spmc - reader may live in other thread.
+    pub(crate) fn fork_reader(&self, list: &mut List<T, S>, event_reader: &EventReader<T, S>)
+        -> Result<EventReader<T, S>, ReaderExpired>
+    {
+        // Expiration happens under lock - so this is final.
+        #[cfg(feature = "std")]
+        if let Some(lease) = &event_reader.lease{
+            if lease.is_expired(){
+                return Err(ReaderExpired);
+            }
+        }
+
+        // event_reader occupy its chunk, so queue alive and chunk too.
+        debug_assert!(list.readers_count > 0);
+        list.readers_count += 1;
+        let position = event_reader.position;
+        unsafe{&*position.chunk}.readers_entered().fetch_add(1, Ordering::AcqRel);
+
+        #[cfg(feature = "std")]
+        let lease = event_reader.lease.as_ref().map(|lease|{
+            let lease = Arc::new(ReaderLease::new(lease.ttl(), position.chunk, lease.name().map(String::from)));
+            list.leases.push(lease.clone());
+            lease
+        });
+
+        Ok(EventReader{
+            event: event_reader.event.clone(),
+            position,
+            start_position_epoch: event_reader.start_position_epoch,
+            missed: 0,
+            cleanup: event_reader.cleanup,
+            #[cfg(feature = "std")]
+            lease
+        })
+    }
+
+    /// Move `event_reader` up to `n` items back, but not before the first retained item
+    /// and start_position. Returns number of items actually rewound.
+    pub(crate) fn rewind_reader(&self, list: &mut List<T, S>, event_reader: &mut EventReader<T, S>, n: usize)
+        -> Result<usize, ReaderExpired>
+    {
+        // Expiration happens under lock - so this is final.
+        #[cfg(feature = "std")]
+        if let Some(lease) = &event_reader.lease{
+            if lease.is_expired(){
+                return Err(ReaderExpired);
+            }
+        }
+
+        let position = event_reader.position;
+        let mut lower_bound = Cursor{chunk: list.first, index: 0};
+        if let Some(start_position) = *self.start_position.lock(){
+            lower_bound = core::cmp::max(lower_bound, start_position);
+        }
+        if position <= lower_bound{
+            return Ok(0);
+        }
+
+        // Chunks can be freed only under lock. So all chunks from lower_bound are alive.
+        let chunk_end_index = |chunk: &DynamicChunk<T, S>| -> usize {
+            if core::ptr::eq(chunk, position.chunk){
+                position.index
+            } else {
+                chunk.chunk_state(Ordering::Acquire).len() as usize
+            }
+        };
+
+        let end_chunk = unsafe{&*position.chunk}.next(Ordering::Acquire);
+        let mut available = 0;
+        let mut start_index = lower_bound.index;
+        unsafe {
+            foreach_chunk(lower_bound.chunk, end_chunk, Ordering::Acquire, |chunk| {
+                available += chunk_end_index(chunk) - start_index;
+                start_index = 0;
+                Continue(())
+            });
+        }
+
+        let rewound = core::cmp::min(n, available);
+        if rewound == 0{
+            return Ok(0);
+        }
+
+        let mut new_position = lower_bound;
+        let mut skip = available - rewound;
+        unsafe {
+            foreach_chunk(lower_bound.chunk, end_chunk, Ordering::Acquire, |chunk| {
+                let index = if core::ptr::eq(chunk, lower_bound.chunk) { lower_bound.index } else { 0 };
+                let len = chunk_end_index(chunk) - index;
+                if skip < len {
+                    new_position = Cursor{chunk, index: index + skip};
+                    return Break(());
+                }
+                skip -= len;
+                Continue(())
+            });
+        }
+
+        // Re-enter earlier chunk, exit current - as with re-subscription.
+        if new_position.chunk != position.chunk {
+            unsafe{&*new_position.chunk}.readers_entered().fetch_add(1, Ordering::AcqRel);
+            unsafe{&*position.chunk}.read_completely_times().fetch_add(1, Ordering::AcqRel);
+        }
+        event_reader.position = new_position;
+
+        #[cfg(feature = "std")]
+        if let Some(lease) = &event_reader.lease{
+            lease.end_read(new_position.chunk);
+        }
+
+        Ok(rewound)
+    }
+
+    /// Make everything up to the current end unreachable for anyone but `event_reader`,
+    /// which should be the only reader. Returns drain end - start_position now.
+    ///
+    /// `event_reader` should not be behind start_position.
+    pub(crate) fn begin_drain(&self, list: &mut List<T, S>, event_reader: &EventReader<T, S>)
+        -> Option<Cursor<T, S>>
+    {
+        self.flush(list);
+        if list.readers_count != 1 {
+            return None;
+        }
+
+        let last = unsafe{&*list.last};
+        let end = Cursor{chunk: last, index: last.chunk_state(Ordering::Relaxed).len() as usize};
+        let position = event_reader.position;
+        if position == end {
+            return Some(end);
+        }
+        self.set_start_position(list, end);
+
+        // Already read items of the current chunk are unreachable now - drop them, to keep
+        // drained items a prefix.
+        let chunk = unsafe{&*position.chunk};
+        for index in chunk.drained()..position.index {
+            unsafe{ drop(chunk.take_unchecked(index)); }
+        }
+        unsafe{ chunk.set_drained(position.index); }
+
+        Some(end)
+    }
+
+    /// Drain stopped at `position`, before `end`. Make not drained items reachable again.
+    pub(crate) fn end_drain(&self, list: &mut List<T, S>, end: Cursor<T, S>, position: Cursor<T, S>){
+        // Queue may be truncated further meanwhile.
+        if *self.start_position.lock() == Some(end) && position != end {
+            self.set_start_position(list, position);
+        }
+    }
+
+    // Called from EventReader Drop. Reader's `Arc` keeps queue alive till the end.
+    pub(crate) fn unsubscribe(&self, event_reader: &EventReader<T, S>){
+        let mut list = self.list.lock();
+
+        // Expired reader already exited its chunk.
+        #[cfg(feature = "std")]
+        let expired = match &event_reader.lease{
+            None => false,
+            Some(lease) => {
+                list.leases.retain(|l| !core::ptr::eq(&**l, &**lease));
+                lease.is_expired()
+            }
+        };
+        #[cfg(not(feature = "std"))]
+        let expired = false;
+
+        if !expired{
+            // Exit chunk
+            unsafe{&*event_reader.position.chunk}.read_completely_times().fetch_add(1, Ordering::AcqRel);
+
+            if S::CLEANUP_IN_UNSUBSCRIBE && self.cleanup_mode() != CleanupMode::Never{
+                if core::ptr::eq(list.first, event_reader.position.chunk) {
+                    self.cleanup_impl(&mut *list);
+                }
+            }
+        }
+
+        list.readers_count -= 1;
     }
 
     unsafe fn free_chunk<const LOCK_ON_WRITE_START_POSITION: bool>(
@@ -293,39 +1399,89 @@ impl<T, S: Settings> EventQueue<T, S>
         chunk: *mut DynamicChunk<T, S>,
         list: &mut List<T, S>)
     {
-        if let Some(start_position) = *self.start_position.as_mut_ptr(){
+        if let Some(start_position) = *self.start_position.data_ptr(){
             if start_position.chunk == chunk{
                 if LOCK_ON_WRITE_START_POSITION{
                     *self.start_position.lock() = None;
                 } else {
-                    *self.start_position.as_mut_ptr() = None;
+                    *self.start_position.data_ptr() = None;
                 }
             }
         }
 
         list.total_capacity -= (*chunk).capacity();
+        self.chunks_count.fetch_sub(1, Ordering::Relaxed);
+        if let Some(on_chunk_free) = S::ON_CHUNK_FREE{
+            on_chunk_free(FreedChunkInfo{
+                id: (*chunk).id(),
+                capacity: (*chunk).capacity(),
+                len: (*chunk).chunk_state(Ordering::Relaxed).len() as usize,
+            });
+        }
+        if /*constexpr*/ S::STATS {
+            self.stats.chunks_freed.fetch_add(1, Ordering::Relaxed);
+            self.stats.bytes_freed.fetch_add(DynamicChunk::<T, S>::allocation_size((*chunk).capacity()), Ordering::Relaxed);
+        }
 
-        #[cfg(not(feature = "double_buffering"))]
-        {
-            DynamicChunk::destruct(chunk);
-            std::mem::drop(list);   // just for use
+        if /*constexpr*/ S::PREALLOCATED_CHUNKS > 0 {
+            if list.reserved_chunks.len() < S::PREALLOCATED_CHUNKS as usize
+               && (*chunk).capacity() == self.min_chunk_size() as usize
+            {
+                // Return back for reuse, without allocation.
+                list.reserved_chunks.push(DynamicChunk::recycle(chunk));
+                return;
+            }
         }
 
-        #[cfg(feature = "double_buffering")]
-        {
-            if let Some(free_chunk) = &list.free_chunk {
-                if free_chunk.capacity() >= (*chunk).capacity() {
-                    // Discard - recycled chunk bigger then our
-                    DynamicChunk::destruct(chunk);
-                    return;
-                }
+        if list.free_chunks.len() < S::RECYCLE_POOL_SIZE {
+            list.free_chunks.push(DynamicChunk::recycle(chunk));
+            return;
+        }
+
+        let smallest = list.free_chunks.iter().enumerate()
+            .min_by_key(|(_, free_chunk)| free_chunk.capacity());
+        match smallest{
+            Some((index, free_chunk)) if free_chunk.capacity() < (*chunk).capacity() => {
+                // Replace smallest recycled chunk with our.
+                list.free_chunks[index] = DynamicChunk::recycle(chunk);
+            }
+            _ => {
+                // Discard - recycled chunks bigger then our (or RECYCLE_POOL_SIZE == 0)
+                DynamicChunk::destruct(chunk);
+            }
+        }
+    }
+
+    /// Exit chunks of readers, not polled for their TTL.
+    #[cfg(feature = "std")]
+    fn expire_readers(&self, list: &mut List<T, S>){
+        if list.leases.is_empty(){
+            return;
+        }
+
+        let now = Instant::now();
+        let end = self.end_position(list).sequence();
+        let is_lagging = |chunk: &DynamicChunk<T, S>| match S::STALE_READERS.max_lag_bytes{
+            None => false,
+            Some(max_lag_bytes) => {
+                let lag = (end - chunk.first_seq()) as usize;
+                lag.saturating_mul(DynamicChunk::<T, S>::slot_size()) > max_lag_bytes
+            }
+        };
+        for lease in &list.leases{
+            if let Some(chunk) = lease.try_expire(now, is_lagging){
+                unsafe{&*chunk}.read_completely_times().fetch_add(1, Ordering::AcqRel);
             }
-            // Replace free_chunk with our.
-            list.free_chunk = Some(DynamicChunk::recycle(chunk));
         }
     }
 
     fn cleanup_impl(&self, list: &mut List<T, S>){
+        if /*constexpr*/ S::STATS {
+            self.stats.cleanups.fetch_add(1, Ordering::Relaxed);
+        }
+        #[cfg(feature = "std")]
+        self.expire_readers(list);
+
         unsafe {
             // using _ptr version, because with &chunk - reference should be valid during whole
             // lambda function call. (according to miri and some rust borrowing rules).
@@ -336,7 +1492,7 @@ impl<T, S: Settings> EventQueue<T, S>
                 Ordering::Relaxed,      // we're under mutex
                 |chunk_ptr| {
                     // Do not lock prev_chunk.chunk_switch_mutex because we traverse in order.
-                    let chunk = &mut *chunk_ptr;
+                    let chunk = &*chunk_ptr;
                     let chunk_readers = chunk.readers_entered().load(Ordering::Acquire);
                     let chunk_read_times = chunk.read_completely_times().load(Ordering::Acquire);
                     // Cleanup only in order
@@ -347,10 +1503,10 @@ impl<T, S: Settings> EventQueue<T, S>
                     let next_chunk_ptr = chunk.next(Ordering::Relaxed);
                     debug_assert!(!next_chunk_ptr.is_null());
 
-                    debug_assert!(std::ptr::eq(chunk, list.first));
+                    debug_assert!(core::ptr::eq(chunk, list.first));
                     // Do not lock start_position permanently, because reader will
                     // never enter chunk before list.first
-                    self.free_chunk::<true>(chunk, list);
+                    self.free_chunk::<true>(chunk_ptr, list);
                     list.first = next_chunk_ptr;
 
                     Continue(())
@@ -360,6 +1516,21 @@ impl<T, S: Settings> EventQueue<T, S>
         if list.first == list.last{
             list.penult_chunk_size = 0;
         }
+        self.update_start_seq(list);
+    }
+
+    /// Call after start_position or list.first change.
+    #[inline]
+    fn update_start_seq(&self, list: &List<T, S>){
+        let start = unsafe{*self.start_position.data_ptr()}
+            .unwrap_or(Cursor{ chunk: list.first, index: 0 });
+        self.start_seq.store(Position::from(start).sequence(), Ordering::Relaxed);
+    }
+
+    /// Call after push.
+    #[inline]
+    fn update_end_seq(&self, list: &List<T, S>){
+        self.end_seq.store(self.end_position(list).sequence(), Ordering::Relaxed);
     }
 
     /// This will traverse up to the start_point - and will free all unoccupied chunks. (out-of-order cleanup)
@@ -374,7 +1545,7 @@ impl<T, S: Settings> EventQueue<T, S>
             None => { return; }
             Some(cursor) => {cursor.chunk}
         };
-        if list.first as *const _ == terminal_chunk{
+        if core::ptr::eq(list.first, terminal_chunk){
             return;
         }
         unsafe {
@@ -412,7 +1583,19 @@ impl<T, S: Settings> EventQueue<T, S>
     }
 
     pub fn cleanup(&self){
-        self.cleanup_impl(&mut *self.list.lock());
+        let mut list = self.list.lock();
+        #[cfg(feature = "std")]
+        self.truncate_expired(&mut list);
+        self.cleanup_impl(&mut list);
+    }
+
+    /// Free chunks with all items older than [Settings::ITEM_TTL].
+    #[cfg(feature = "std")]
+    #[inline]
+    fn truncate_expired(&self, list: &mut List<T, S>){
+        if let Some(ttl) = S::ITEM_TTL{
+            self.truncate_older_than(list, ttl);
+        }
     }
 
     #[inline]
@@ -421,13 +1604,15 @@ impl<T, S: Settings> EventQueue<T, S>
         list: &mut List<T, S>,
         new_start_position: Cursor<T, S>)
     {
+        yield_point!(SetStartPosition);
         *self.start_position.lock() = Some(new_start_position);
+        self.update_start_seq(list);
 
         // update len_and_start_position_epoch in each chunk
-        let first_chunk = unsafe{&mut *list.first};
+        let first_chunk = unsafe{&*list.first};
         let new_epoch = first_chunk.chunk_state(Ordering::Relaxed).epoch().increment();
         unsafe {
-            foreach_chunk_mut(
+            foreach_chunk(
                 first_chunk,
                 null(),
                 Ordering::Relaxed,      // we're under mutex
@@ -440,6 +1625,8 @@ impl<T, S: Settings> EventQueue<T, S>
     }
 
     pub fn clear(&self, list: &mut List<T, S>){
+        self.flush(list);
+
         let last_chunk = unsafe{ &*list.last };
         let last_chunk_len = last_chunk.chunk_state(Ordering::Relaxed).len() as usize;
 
@@ -451,88 +1638,639 @@ impl<T, S: Settings> EventQueue<T, S>
         self.force_cleanup_impl(list);
     }
 
+    /// Item-granular. Works with any chunks count.
+    /// Same as clear, but also move writer to the new chunk. So the last chunk,
+    /// if not occupied by readers, freed immediately too.
+    pub fn clear_now(&self, list: &mut List<T, S>){
+        self.flush(list);
+
+        let last_chunk = unsafe{ &*list.last };
+        if last_chunk.chunk_state(Ordering::Relaxed).len() != 0 {
+            self.add_chunk_sized(list, last_chunk.capacity());
+        }
+
+        self.clear(list);
+    }
+
     pub fn truncate_front(&self, list: &mut List<T, S>, len: usize) {
-        // make chunks* array
+        self.flush(list);
 
         // TODO: subtract from total_capacity
-        // TODO: use small_vec
-        // TODO: loop if > 128?
-        // there is no way we can have memory enough to hold > 2^64 bytes.
-        let mut chunks : [*const DynamicChunk<T, S>; 128] = [null(); 128];
-        let chunks_count=
-            unsafe {
-                let mut i = 0;
-                foreach_chunk(
-                    list.first,
-                    null(),
-                    Ordering::Relaxed,      // we're under mutex
-                    |chunk| {
-                        chunks[i] = chunk;
-                        i+=1;
-                        Continue(())
+        let mut total_len = 0;
+        unsafe {
+            foreach_chunk(
+                list.first,
+                null(),
+                Ordering::Relaxed,      // we're under mutex
+                |chunk| {
+                    total_len += chunk.chunk_state(Ordering::Relaxed).len() as usize;
+                    Continue(())
+                }
+            );
+        }
+        if len > total_len{
+            // do nothing.
+            return;
+        }
+
+        // Find chunk with (total_len - len)-th item.
+        let mut skip = total_len - len;
+        let mut new_start_position = Cursor{ chunk: list.last, index: 0 };
+        unsafe {
+            foreach_chunk(
+                list.first,
+                null(),
+                Ordering::Relaxed,      // we're under mutex
+                |chunk| {
+                    let chunk_len = chunk.chunk_state(Ordering::Relaxed).len() as usize;
+                    if skip < chunk_len || core::ptr::eq(chunk, list.last){
+                        new_start_position = Cursor{ chunk, index: skip };
+                        return Break(());
                     }
-                );
-                i
-            };
+                    skip -= chunk_len;
+                    Continue(())
+                }
+            );
+        }
 
-        let mut total_len = 0;
-        for i in (0..chunks_count).rev(){
-            let chunk = unsafe{ &*chunks[i] };
-            let chunk_len = chunk.chunk_state(Ordering::Relaxed).len() as usize;
-            total_len += chunk_len;
-            if total_len >= len{
-                let new_start_position = Cursor {
-                    chunk: chunks[i],
-                    index: total_len - len
-                };
-                // Do we actually need to truncate?
-                if let Some(start_position) = unsafe{*self.start_position.as_mut_ptr()}{
-                    if start_position >= new_start_position{
-                        return;
+        // Do we actually need to truncate?
+        if let Some(start_position) = unsafe{*self.start_position.data_ptr()}{
+            if start_position >= new_start_position{
+                return;
+            }
+        }
+
+        self.set_start_position(list, new_start_position);
+        self.force_cleanup_impl(list);
+    }
+
+    /// Move start_position to the beginning of `keep_chunks`-th chunk from the end,
+    /// and free all chunks before it, unoccupied by readers.
+    pub fn cleanup_force(&self, list: &mut List<T, S>, keep_chunks: usize){
+        self.flush(list);
+
+        // Last chunk always kept.
+        let keep_chunks = core::cmp::max(keep_chunks, 1);
+        let chunks_count = unsafe{ (list.chunk_id_counter - (*list.first).id()) as usize + 1 };
+        if chunks_count <= keep_chunks{
+            self.cleanup_impl(list);
+            return;
+        }
+
+        let mut new_start_chunk = list.first as *const DynamicChunk<T, S>;
+        for _ in 0..chunks_count - keep_chunks{
+            new_start_chunk = unsafe{ (*new_start_chunk).next(Ordering::Relaxed) };
+        }
+        let new_start_position = Cursor{ chunk: new_start_chunk, index: 0 };
+
+        let need_move = match unsafe{*self.start_position.data_ptr()}{
+            None => true,
+            Some(start_position) => start_position < new_start_position
+        };
+        if need_move{
+            self.set_start_position(list, new_start_position);
+        }
+        self.force_cleanup_impl(list);
+    }
+
+    /// Chunk-granular. Chunk considered as old as its last item.
+    #[cfg(feature = "std")]
+    pub fn truncate_older_than(&self, list: &mut List<T, S>, age: Duration){
+        self.flush(list);
+
+        let now = match crate::utils::now(){
+            None => return,     // no clock - no closed_at
+            Some(now) => now,
+        };
+        let threshold = match now.checked_sub(age){
+            None => return,     // nothing can be that old
+            Some(threshold) => threshold,
+        };
+
+        // First chunk with items, not older then age. Last chunk always kept.
+        let mut new_start_chunk = list.last as *const DynamicChunk<T, S>;
+        unsafe {
+            foreach_chunk(
+                list.first,
+                list.last,
+                Ordering::Relaxed,      // we're under mutex
+                |chunk| {
+                    match chunk.closed_at(){
+                        Some(closed_at) if closed_at < threshold => Continue(()),
+                        _ => {
+                            new_start_chunk = chunk;
+                            Break(())
+                        }
                     }
                 }
+            );
+        }
+        if core::ptr::eq(new_start_chunk, list.first){
+            return;
+        }
 
-                self.set_start_position(list, new_start_position);
-                self.force_cleanup_impl(list);
+        let new_start_position = Cursor{ chunk: new_start_chunk, index: 0 };
+        if let Some(start_position) = unsafe{*self.start_position.data_ptr()}{
+            if start_position >= new_start_position{
                 return;
             }
         }
 
-        // len is bigger then total_len.
-        // do nothing.
+        self.set_start_position(list, new_start_position);
+        self.force_cleanup_impl(list);
+    }
+
+    /// Keep only the latest retained value per key: clones of them pushed into fresh chunk,
+    /// and start_position moved there. Returns number of values compacted away.
+    pub fn compact_by_key<K, F>(&self, list: &mut List<T, S>, mut key: F) -> usize
+        where T: Clone, K: Ord, F: FnMut(&T) -> K
+    {
+        self.flush(list);
+
+        let start = unsafe{*self.start_position.data_ptr()}
+            .unwrap_or(Cursor{ chunk: list.first, index: 0 });
+
+        // Chunks can not be freed, while we're under lock.
+        let mut retained: Vec<*const T> = Vec::new();
+        unsafe {
+            foreach_chunk(
+                start.chunk,
+                null(),
+                Ordering::Relaxed,      // we're under mutex
+                |chunk| {
+                    let from = if core::ptr::eq(chunk, start.chunk) { start.index } else { 0 };
+                    let len = chunk.chunk_state(Ordering::Relaxed).len() as usize;
+                    for index in from..len {
+                        retained.push(chunk.get_unchecked(index) as *const T);
+                    }
+                    Continue(())
+                }
+            );
+        }
+
+        let mut keys = alloc::collections::BTreeSet::new();
+        // Latest first.
+        let mut compacted: Vec<T> = retained.iter().rev()
+            .map(|&value| unsafe{&*value})
+            .filter(|value| keys.insert(key(value)))
+            .cloned()
+            .collect();
+        let compacted_away = retained.len() - compacted.len();
+        if compacted_away == 0 {
+            return 0;
+        }
+        compacted.reverse();
+
+        let last_chunk = unsafe{ &*list.last };
+        if last_chunk.chunk_state(Ordering::Relaxed).len() != 0 {
+            self.add_chunk_sized(list, last_chunk.capacity());
+        }
+        self.set_start_position(list, Cursor{ chunk: list.last, index: 0 });
+        self.force_cleanup_impl(list);
+        self.extend(list, compacted);
+
+        compacted_away
     }
 
     pub fn change_chunk_capacity(&self, list: &mut List<T, S>, new_capacity: u32){
-        assert!(S::MIN_CHUNK_SIZE <= new_capacity && new_capacity <= S::MAX_CHUNK_SIZE);
+        let new_capacity = if /*constexpr*/ Self::is_zst() { Self::ZST_CHUNK_SIZE } else { new_capacity };
+        assert!(self.min_chunk_size() <= new_capacity && new_capacity <= self.max_chunk_size());
         self.on_new_chunk_cleanup(list);
         self.add_chunk_sized(&mut *list, new_capacity as usize);
     }
 
+    /// Switch to chunk sized to current len. Does nothing, if it is the current chunk size.
+    pub fn resize_to_fit(&self, list: &mut List<T, S>){
+        let new_capacity = self.len(list)
+            .clamp(self.min_chunk_size() as usize, self.max_chunk_size() as usize);
+        if new_capacity == self.chunk_capacity(list){
+            return;
+        }
+        self.change_chunk_capacity(list, new_capacity as u32);
+    }
+
+    /// Allocate chunks for `additional` next items ahead. Already reserved chunks reused.
+    pub fn reserve(&self, list: &mut List<T, S>, additional: usize){
+        let last = unsafe{&*list.last};
+        let last_len = last.chunk_state(Ordering::Relaxed).len() as usize + list.unpublished_len as usize;
+        let mut remaining = additional.saturating_sub(last.capacity() - last_len);
+
+        // Chunk sizes which add_chunk will request.
+        let mut available: Vec<usize> = list.reserved_chunks.iter().map(|chunk| chunk.capacity()).collect();
+        let mut prev_chunk_size   = last.capacity();
+        let mut penult_chunk_size = list.penult_chunk_size as usize;
+        while remaining > 0 {
+            let size = self.next_chunk_size(prev_chunk_size, penult_chunk_size);
+            match available.iter().position(|&capacity| capacity == size){
+                Some(index) => { available.swap_remove(index); }
+                None => self.reserve_chunk(list, size),
+            }
+            remaining = remaining.saturating_sub(size);
+            penult_chunk_size = prev_chunk_size;
+            prev_chunk_size   = size;
+        }
+    }
+
     pub fn total_capacity(&self, list: &List<T, S>) -> usize {
         list.total_capacity
     }
 
+    /// Same as [len](Self::len), without lock. O(1).
+    ///
+    /// Approximate under concurrent push/clear/cleanup - start and end are loaded separately,
+    /// so result may match no actual moment of the queue.
+    #[inline]
+    pub fn len_lock_free(&self) -> usize {
+        // start first - it never passes end.
+        let start = self.start_seq.load(Ordering::Relaxed);
+        let end   = self.end_seq.load(Ordering::Relaxed);
+        end.saturating_sub(start) as usize
+    }
+
+    /// Sequence of the first retained item, without lock.
+    #[inline]
+    pub(crate) fn start_seq(&self) -> u64 {
+        self.start_seq.load(Ordering::Relaxed)
+    }
+
+    /// Sequence after the last pushed item, without lock.
+    #[inline]
+    pub(crate) fn end_seq(&self) -> u64 {
+        self.end_seq.load(Ordering::Relaxed)
+    }
+
+    /// Items from start_position to the end of the queue. O(chunks).
+    pub fn len(&self, list: &List<T, S>) -> usize {
+        let start_position = match unsafe{*self.start_position.data_ptr()}{
+            Some(start_position) => start_position,
+            None => Cursor{ chunk: list.first, index: 0 }
+        };
+
+        let mut len = list.unpublished_len as usize;
+        unsafe{
+            foreach_chunk(
+                start_position.chunk,
+                null(),
+                Ordering::Relaxed,      // we're under mutex
+                |chunk| {
+                    len += chunk.chunk_state(Ordering::Relaxed).len() as usize;
+                    Continue(())
+                }
+            );
+        }
+        len - start_position.index
+    }
+
+    /// Clones of items from start_position to the end of the queue (same as [len](Self::len)).
+    /// Single pass.
+    pub fn snapshot_vec(&self, list: &List<T, S>) -> Vec<T>
+        where T: Clone
+    {
+        let start_position = match unsafe{*self.start_position.data_ptr()}{
+            Some(start_position) => start_position,
+            None => Cursor{ chunk: list.first, index: 0 }
+        };
+        let epoch = unsafe{&*start_position.chunk}.chunk_state(Ordering::Relaxed).epoch();
+
+        let mut values = Vec::new();
+        unsafe{
+            foreach_chunk(
+                start_position.chunk,
+                null(),
+                Ordering::Relaxed,      // we're under mutex - chunks can not be freed
+                |chunk| {
+                    let chunk_state = chunk.chunk_state(Ordering::Relaxed);
+                    // clear/truncate update all chunks' epoch at once, under the same lock.
+                    debug_assert!(chunk_state.epoch() == epoch);
+
+                    let from = if core::ptr::eq(chunk, start_position.chunk) { start_position.index } else { 0 };
+                    let mut len = chunk_state.len() as usize;
+                    if core::ptr::eq(chunk, list.last){
+                        // Written, but not yet published.
+                        len += list.unpublished_len as usize;
+                    }
+                    values.extend((from..len).map(|index| chunk.get_unchecked(index).clone()));
+                    Continue(())
+                }
+            );
+        }
+        values
+    }
+
+    /// Position, next pushed value will get.
+    #[inline]
+    pub fn end_position(&self, list: &List<T, S>) -> Position {
+        let last = unsafe{&*list.last};
+        Position::from_sequence(
+            last.first_seq()
+            + last.chunk_state(Ordering::Relaxed).len() as u64
+            + list.unpublished_len as u64
+        )
+    }
+
+    /// Same as [push](Self::push). Returns position of pushed value. None, if value was dropped.
+    #[inline]
+    pub fn push_with_position(&self, list: &mut List<T, S>, value: T) -> Option<Position> {
+        let position = self.end_position(list);
+        self.push(list, value);
+        if self.end_position(list) == position {
+            None
+        } else {
+            Some(position)
+        }
+    }
+
+    /// Same as [extend](Self::extend). Returns positions of pushed values.
+    #[inline]
+    pub fn extend_with_position<I>(&self, list: &mut List<T, S>, iter: I) -> Range<Position>
+        where I: IntoIterator<Item = T>
+    {
+        let start = self.end_position(list);
+        self.extend(list, iter);
+        start..self.end_position(list)
+    }
+
+    /// O(1). Chunks allocation size linear from capacity.
+    pub fn bytes_allocated(&self, list: &List<T, S>) -> usize {
+        let chunks_count = unsafe{ (list.chunk_id_counter - (*list.first).id()) as usize + 1 };
+        let mut bytes =
+            chunks_count * DynamicChunk::<T, S>::allocation_size(0)
+            + list.total_capacity * DynamicChunk::<T, S>::slot_size();
+
+        for free_chunk in &list.free_chunks{
+            bytes += DynamicChunk::<T, S>::allocation_size(free_chunk.capacity());
+        }
+        for reserved_chunk in &list.reserved_chunks{
+            bytes += DynamicChunk::<T, S>::allocation_size(reserved_chunk.capacity());
+        }
+
+        bytes
+    }
+
+    pub fn dropped_count(&self, list: &List<T, S>) -> usize {
+        list.dropped_count
+    }
+
+    pub fn readers_count(&self, list: &List<T, S>) -> usize {
+        list.readers_count as usize
+    }
+
+    /// Lock-free.
+    pub fn stats(&self) -> Stats {
+        self.stats.snapshot()
+    }
+
     pub fn chunk_capacity(&self, list: &List<T, S>) -> usize {
         unsafe { (*list.last).capacity() }
     }
 
-/*
-    // chunks_count can be atomic. But does that needed?
+    /// Does not allocate.
+    pub fn write_stats(&self, list: &List<T, S>, w: &mut impl fmt::Write) -> fmt::Result {
+        let mut chunks_count = 0;
+        let mut events_count = list.unpublished_len as usize;
+        // Published items, starting from the first chunk, not read by all readers.
+        let mut max_lag = 0;
+        let mut lagging = false;
+        unsafe {
+            foreach_chunk(
+                list.first,
+                null(),
+                Ordering::Acquire,
+                |chunk| {
+                    let len = chunk.chunk_state(Ordering::Acquire).len() as usize;
+                    chunks_count += 1;
+                    events_count += len;
+
+                    if !lagging {
+                        let chunk_readers = chunk.readers_entered().load(Ordering::Acquire);
+                        let chunk_read_times = chunk.read_completely_times().load(Ordering::Acquire);
+                        lagging = chunk_readers != chunk_read_times;
+                    }
+                    if lagging {
+                        max_lag += len;
+                    }
+                    Continue(())
+                }
+            );
+        }
+        if list.readers_count == 0 {
+            max_lag = 0;
+        }
+
+        write!(w, "chunks={} capacity={} events={} readers={} max_lag<={}",
+            chunks_count, list.total_capacity, events_count, list.readers_count, max_lag)?;
+
+        #[cfg(feature = "std")]
+        {
+        let end = self.end_position(list).sequence();
+        for lease in &list.leases{
+            let name = match lease.name(){
+                None => continue,
+                Some(name) => name,
+            };
+            if lease.is_expired() {
+                write!(w, " {}=expired", name)?;
+            } else {
+                let (_, lag, pinned_bytes) = self.lease_footprint(end, lease).unwrap_or_default();
+                write!(w, " {}.lag<={} {}.pinned_bytes={}", name, lag, name, pinned_bytes)?;
+            }
+        }
+        }
+        Ok(())
+    }
+
+    /// Counters from [Stats] - only with [Settings::STATS].
+    #[cfg(feature = "metrics")]
+    pub fn record_metrics(&self, list: &List<T, S>, name: &str){
+        let labels = [("queue", String::from(name))];
+        let stats = self.stats();
+
+        metrics::counter!("rc_event_queue_items_pushed", &labels).absolute(stats.pushed as u64);
+        metrics::counter!("rc_event_queue_cleanup_count", &labels).absolute(stats.cleanups as u64);
+        metrics::counter!("rc_event_queue_reclaimed_bytes", &labels).absolute(stats.bytes_freed as u64);
+        metrics::gauge!("rc_event_queue_chunks_live", &labels).set(self.chunks_count() as f64);
+        metrics::gauge!("rc_event_queue_readers", &labels).set(list.readers_count as f64);
+
+        for reader in self.readers_diagnostics(list){
+            if let Some(reader_name) = reader.name{
+                let labels = [("queue", String::from(name)), ("reader", reader_name)];
+                metrics::gauge!("rc_event_queue_reader_lag", &labels).set(reader.lag as f64);
+                metrics::gauge!("rc_event_queue_reader_pinned_bytes", &labels).set(reader.pinned_bytes as f64);
+            }
+        }
+    }
+
+    pub fn diagnostics(&self, list: &List<T, S>) -> QueueDiagnostics {
+        let mut chunks = Vec::new();
+        unsafe {
+            foreach_chunk(
+                list.first,
+                null(),
+                Ordering::Acquire,
+                |chunk| {
+                    let chunk_state = chunk.chunk_state(Ordering::Acquire);
+                    chunks.push(ChunkDiagnostics{
+                        id: chunk.id(),
+                        capacity: chunk.capacity(),
+                        len: chunk_state.len() as usize,
+                        epoch: chunk_state.epoch().into_inner(),
+                        readers_entered: chunk.readers_entered().load(Ordering::Acquire),
+                        read_completely_times: chunk.read_completely_times().load(Ordering::Acquire),
+                    });
+                    Continue(())
+                }
+            );
+        }
+        let free_chunks = || list.reserved_chunks.iter().chain(&list.free_chunks);
+        QueueDiagnostics{
+            chunks,
+            readers_count: list.readers_count as usize,
+            readers: self.readers_diagnostics(list),
+            start_position: unsafe{*self.start_position.data_ptr()}.map(Position::from),
+            unpublished_len: list.unpublished_len as usize,
+            free_chunks_count: free_chunks().count(),
+            free_chunks_capacity: free_chunks().map(|chunk| chunk.capacity()).sum(),
+        }
+    }
+
+    /// Readers with lease.
+    #[cfg(feature = "std")]
+    fn readers_diagnostics(&self, list: &List<T, S>) -> Vec<ReaderDiagnostics> {
+        let end = self.end_position(list).sequence();
+        list.leases.iter().map(|lease| {
+            let mut reader = ReaderDiagnostics{
+                name: lease.name().map(String::from),
+                expired: lease.is_expired(),
+                chunk_id: None,
+                lag: 0,
+                pinned_bytes: 0,
+            };
+            if let Some((chunk_id, lag, pinned_bytes)) = self.lease_footprint(end, lease){
+                reader.chunk_id = Some(chunk_id);
+                reader.lag = lag;
+                reader.pinned_bytes = pinned_bytes;
+            }
+            reader
+        }).collect()
+    }
+
+    /// Chunk id, lag and pinned bytes of idle reader. `end` - sequence after the last item.
+    /// Does not allocate.
+    #[cfg(feature = "std")]
+    fn lease_footprint(&self, end: u64, lease: &ReaderLease<T, S>) -> Option<(u64, usize, usize)> {
+        let chunk = unsafe{&*lease.idle_chunk()?};
+        let lag = (end - chunk.first_seq()) as usize;
+        let mut pinned_bytes = 0;
+        unsafe {
+            foreach_chunk(
+                chunk,
+                null(),
+                Ordering::Acquire,
+                |chunk| {
+                    pinned_bytes += DynamicChunk::<T, S>::allocation_size(chunk.capacity());
+                    Continue(())
+                }
+            );
+        }
+        Some((chunk.id(), lag, pinned_bytes))
+    }
+
+    /// No leases without `std`.
+    #[cfg(not(feature = "std"))]
+    fn readers_diagnostics(&self, _list: &List<T, S>) -> Vec<ReaderDiagnostics> {
+        Vec::new()
+    }
+
+    /// Debug list of chunks: live ones in order, then recycled (reserved and free) ones.
+    pub fn fmt_chunks(&self, list: &List<T, S>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&ChunksDebug{list}, f)
+    }
+
+    pub fn fmt_debug(&self, list: &List<T, S>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventQueue")
+            .field("readers", &list.readers_count)
+            .field("total_capacity", &list.total_capacity)
+            .field("unpublished", &list.unpublished_len)
+            .field("chunks", &ChunksDebug{list})
+            .finish()
+    }
+
+    /// Chunks in list. Without lock.
+    #[inline]
     pub fn chunks_count(&self) -> usize {
-        let list = self.list.lock();
-        unsafe{
-            list.chunk_id_counter/*(*list.last).id*/ - (*list.first).id() + 1
+        self.chunks_count.load(Ordering::Relaxed)
+    }
+}
+
+struct ChunksDebug<'a, T, S: Settings>{
+    list: &'a List<T, S>,
+}
+
+impl<'a, T, S: Settings> fmt::Debug for ChunksDebug<'a, T, S>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut chunks = f.debug_list();
+        unsafe {
+            foreach_chunk(
+                self.list.first,
+                null(),
+                Ordering::Acquire,
+                |chunk| {
+                    chunks.entry(&ChunkDebug{chunk});
+                    Continue(())
+                }
+            );
         }
-    }*/
+        for chunk in self.list.reserved_chunks.iter().chain(&self.list.free_chunks){
+            chunks.entry(&RecycledChunkDebug{capacity: chunk.capacity()});
+        }
+        chunks.finish()
+    }
+}
+
+struct ChunkDebug<'a, T, S: Settings>{
+    chunk: &'a DynamicChunk<T, S>,
+}
+
+impl<'a, T, S: Settings> fmt::Debug for ChunkDebug<'a, T, S>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chunk_state = self.chunk.chunk_state(Ordering::Acquire);
+        f.debug_struct("Chunk")
+            .field("id", &self.chunk.id())
+            .field("capacity", &self.chunk.capacity())
+            .field("len", &chunk_state.len())
+            .field("epoch", &chunk_state.epoch().into_inner())
+            .field("readers_entered", &self.chunk.readers_entered().load(Ordering::Acquire))
+            .field("read_completely_times", &self.chunk.read_completely_times().load(Ordering::Acquire))
+            .field("recycled", &false)
+            .finish()
+    }
+}
+
+/// Free chunk, kept for reuse. Its header is stale - only capacity meaningful.
+struct RecycledChunkDebug{
+    capacity: usize,
+}
+
+impl fmt::Debug for RecycledChunkDebug{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Chunk")
+            .field("capacity", &self.capacity)
+            .field("recycled", &true)
+            .finish()
+    }
 }
 
 impl<T, S: Settings> Drop for EventQueue<T, S>{
     fn drop(&mut self) {
+        // Unpublished values should be dropped too.
+        let this = &*self;
+        this.flush(unsafe{ &mut *this.list.data_ptr() });
+
         let list = self.list.get_mut();
         debug_assert!(list.readers_count == 0);
         unsafe{
             let mut node_ptr = list.first;
-            while node_ptr != null_mut() {
+            while !node_ptr.is_null() {
                 let node = &mut *node_ptr;
                 node_ptr = node.next(Ordering::Relaxed);
                 DynamicChunk::destruct(node);
@@ -551,30 +2289,12 @@ pub(super) unsafe fn foreach_chunk<T, F, S: Settings>
 )
     where F: FnMut(&DynamicChunk<T, S>) -> ControlFlow<()>
 {
-    foreach_chunk_mut(
-        start_chunk_ptr as *mut _,
-        end_chunk_ptr,
-        load_ordering,
-        |mut_chunk| func(mut_chunk)
-    );
-}
-
-/// end_chunk_ptr may be null
-#[inline(always)]
-pub(super) unsafe fn foreach_chunk_mut<T, F, S: Settings>
-(
-    start_chunk_ptr : *mut DynamicChunk<T, S>,
-    end_chunk_ptr   : *const DynamicChunk<T, S>,
-    load_ordering   : Ordering,
-    mut func : F
-)
-    where F: FnMut(&mut DynamicChunk<T, S>) -> ControlFlow<()>
-{
+    // Do not go through `&mut` - readers keep pointers to chunks.
     foreach_chunk_ptr_mut(
-        start_chunk_ptr,
+        start_chunk_ptr as *mut _,
         end_chunk_ptr,
         load_ordering,
-        |mut_chunk_ptr| func(&mut *mut_chunk_ptr)
+        |chunk_ptr| func(&*chunk_ptr)
     );
 }
 
@@ -590,11 +2310,6 @@ pub(super) unsafe fn foreach_chunk_ptr_mut<T, F, S: Settings>
     where F: FnMut(*mut DynamicChunk<T, S>) -> ControlFlow<()>
 {
     debug_assert!(!start_chunk_ptr.is_null());
-    debug_assert!(
-        end_chunk_ptr.is_null()
-            ||
-        std::ptr::eq((*start_chunk_ptr).event(), (*end_chunk_ptr).event())
-    );
     debug_assert!(
         end_chunk_ptr.is_null()
             ||
@@ -603,7 +2318,7 @@ pub(super) unsafe fn foreach_chunk_ptr_mut<T, F, S: Settings>
 
     let mut chunk_ptr = start_chunk_ptr;
     while !chunk_ptr.is_null(){
-        if chunk_ptr as *const _ == end_chunk_ptr {
+        if core::ptr::eq(chunk_ptr, end_chunk_ptr) {
             break;
         }
 
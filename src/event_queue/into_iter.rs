@@ -0,0 +1,76 @@
+use crate::cursor::Cursor;
+use crate::event_queue::{EventQueue, List, Settings};
+use crate::sync::{Arc, Ordering};
+
+/// Owning iterator over retained items of the queue without readers.
+/// Each chunk freed, as soon as iterator leaves it.
+pub struct IntoIter<T, S: Settings>{
+    /// Unique - no readers, no other handles.
+    event: Arc<EventQueue<T, S>>,
+    position: Cursor<T, S>,
+}
+
+impl<T, S: Settings> IntoIter<T, S>{
+    /// `event` should be unique.
+    pub(crate) fn new(event: Arc<EventQueue<T, S>>) -> Self{
+        debug_assert_eq!(Arc::strong_count(&event), 1);
+        let list = unsafe{&mut *event.list.data_ptr()};
+        debug_assert_eq!(list.readers_count, 0);
+        event.publish(list);
+
+        // Free (or drop) everything before start_position.
+        let position = match unsafe{*event.start_position.data_ptr()}{
+            None => Cursor{ chunk: list.first, index: 0 },
+            Some(start_position) => {
+                while !core::ptr::eq(list.first, start_position.chunk) {
+                    unsafe{ Self::free_first_chunk(&event, list); }
+                }
+                let chunk = unsafe{&*start_position.chunk};
+                for index in chunk.drained()..start_position.index {
+                    unsafe{ drop(chunk.take_unchecked(index)); }
+                }
+                unsafe{ chunk.set_drained(start_position.index); }
+                start_position
+            }
+        };
+        Self{event, position}
+    }
+
+    /// There should be next chunk.
+    unsafe fn free_first_chunk(event: &EventQueue<T, S>, list: &mut List<T, S>){
+        let chunk = list.first;
+        let next = (*chunk).next(Ordering::Relaxed);
+        debug_assert!(!next.is_null());
+        event.free_chunk::<false>(chunk, list);
+        list.first = next;
+    }
+}
+
+impl<T, S: Settings> Iterator for IntoIter<T, S>{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop{
+            let chunk = unsafe{&*self.position.chunk};
+            let index = self.position.index;
+            if index < chunk.chunk_state(Ordering::Relaxed).len() as usize {
+                debug_assert_eq!(chunk.drained(), index);
+                let value = unsafe{ chunk.take_unchecked(index) };
+                unsafe{ chunk.set_drained(index + 1); }
+                self.position.index += 1;
+                return Some(value);
+            }
+
+            let next = chunk.next(Ordering::Relaxed);
+            if next.is_null() {
+                return None;
+            }
+            unsafe{
+                let list = &mut *self.event.list.data_ptr();
+                debug_assert!(core::ptr::eq(list.first, chunk));
+                Self::free_first_chunk(&self.event, list);
+            }
+            self.position = Cursor{ chunk: next, index: 0 };
+        }
+    }
+}
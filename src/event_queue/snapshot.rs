@@ -0,0 +1,77 @@
+// Serialized as `EventQueueSnapshot{start: u64, events: [T]}`.
+// `start` - sequence number of the first event. Restored queue continues it.
+
+use alloc::vec::Vec;
+use serde::ser::{Serialize, Serializer, SerializeSeq, SerializeStruct};
+use serde::de::{Deserialize, Deserializer};
+use crate::cursor::Cursor;
+use crate::dynamic_chunk::DynamicChunk;
+use crate::event_queue::{EventQueue, List, Settings};
+use crate::sync::Ordering;
+use crate::Position;
+
+impl<T, S: Settings> EventQueue<T, S>{
+    /// Items from start_position to the end of the queue (same as [len](Self::len)).
+    pub fn snapshot_serialize<Ser>(&self, list: &List<T, S>, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+        where T: Serialize, Ser: Serializer
+    {
+        let start = match unsafe{*self.start_position.data_ptr()}{
+            Some(start_position) => start_position,
+            None => Cursor{ chunk: list.first, index: 0 }
+        };
+
+        let mut snapshot = serializer.serialize_struct("EventQueueSnapshot", 2)?;
+        snapshot.serialize_field("start", &Position::from(start).sequence())?;
+        snapshot.serialize_field("events", &Events{list, start, len: self.len(list)})?;
+        snapshot.end()
+    }
+
+    /// Should be called on just constructed queue - without items and readers.
+    pub fn snapshot_deserialize<'de, D>(&self, list: &mut List<T, S>, deserializer: D) -> Result<(), D::Error>
+        where T: Deserialize<'de>, D: Deserializer<'de>
+    {
+        let snapshot = Snapshot::<T>::deserialize(deserializer)?;
+        debug_assert!(list.readers_count == 0 && self.len(list) == 0);
+
+        unsafe{ DynamicChunk::set_first_seq(list.first, snapshot.start); }
+        self.update_start_seq(list);
+        self.extend(list, snapshot.events);
+        Ok(())
+    }
+}
+
+struct Events<'a, T, S: Settings>{
+    list: &'a List<T, S>,
+    start: Cursor<T, S>,
+    len: usize,
+}
+
+impl<'a, T: Serialize, S: Settings> Serialize for Events<'a, T, S>{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len))?;
+        let mut chunk_ptr = self.start.chunk;
+        let mut index = self.start.index;
+        // Under lock - chunks can not be freed.
+        while !chunk_ptr.is_null() {
+            let chunk = unsafe{&*chunk_ptr};
+            let mut len = chunk.chunk_state(Ordering::Relaxed).len() as usize;
+            if core::ptr::eq(chunk_ptr, self.list.last){
+                // Written, but not yet published.
+                len += self.list.unpublished_len as usize;
+            }
+            for i in index..len {
+                seq.serialize_element(unsafe{ chunk.get_unchecked(i) })?;
+            }
+            index = 0;
+            chunk_ptr = chunk.next(Ordering::Relaxed);
+        }
+        seq.end()
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename = "EventQueueSnapshot")]
+struct Snapshot<T>{
+    start: u64,
+    events: Vec<T>,
+}
@@ -1,11 +1,13 @@
 use crate::mpmc::{EventQueue, EventReader, Settings, DefaultSettings};
-use crate::{CleanupMode, LendingIterator};
+use crate::{CleanupMode, GrowthStrategy, LendingIterator};
 use std::ptr::null;
 use std::ops::ControlFlow::Continue;
 use std::ops::Deref;
 use itertools::assert_equal;
 use rand::Rng;
 use crate::event_queue::{foreach_chunk, List};
+use crate::dynamic_chunk::DynamicChunk;
+use crate::mpmc::BS;
 use crate::sync::Ordering;
 use crate::tests::utils::{consume_copies, skip};
 
@@ -67,9 +69,152 @@ fn chunks_size_test(){
     assert_equal(get_chunks_capacities(&event), [4,4,8,8,16]);
 }
 
-#[cfg(feature = "double_buffering")]
+#[test]
+fn growth_strategy_test(){
+    fn test<S: Settings>(capacities: &[usize]){
+        let event = EventQueue::<usize, S>::new();
+        event.extend(0..capacities.iter().sum());
+        assert_equal(get_chunks_capacities(&event), capacities.iter().copied());
+    }
+
+    struct Constant{} impl Settings for Constant{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 64;
+        const GROWTH: GrowthStrategy = GrowthStrategy::Constant;
+    }
+    test::<Constant>(&[4,4,4,4]);
+
+    struct Linear{} impl Settings for Linear{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 16;
+        const GROWTH: GrowthStrategy = GrowthStrategy::Linear(4);
+    }
+    test::<Linear>(&[4,8,12,16,16]);
+
+    struct Doubling{} impl Settings for Doubling{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 32;
+        const GROWTH: GrowthStrategy = GrowthStrategy::Doubling;
+    }
+    test::<Doubling>(&[4,8,16,32,32]);
+
+    struct Custom{} impl Settings for Custom{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 64;
+        // fibonacci
+        const GROWTH: GrowthStrategy = GrowthStrategy::Custom(|prev, penult| prev + penult);
+    }
+    test::<Custom>(&[4,4,8,12,20,32]);
+}
+
+#[test]
+fn extend_size_hint_test(){
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 64;
+        const EXTEND_SIZE_HINT: bool = true;
+    }
+    let event = EventQueue::<usize, S>::new();
+    event.extend(0..30);
+    assert_equal(get_chunks_capacities(&event), [4, 26]);
+
+    // Not less than growth pattern.
+    event.extend(0..2);
+    event.extend(0..3);
+    assert_equal(get_chunks_capacities(&event), [4, 26, 26]);
+
+    // Up to MAX_CHUNK_SIZE.
+    event.extend(0..200);
+    assert_equal(get_chunks_capacities(&event), [4, 26, 26, 64, 64, 64]);
+
+    // Without size_hint - growth pattern.
+    let event = EventQueue::<usize, S>::new();
+    event.extend((0..30).filter(|_| true));
+    assert_equal(get_chunks_capacities(&event), [4, 4, 8, 8, 16]);
+}
+
+#[test]
+fn zst_test(){
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 64;
+    }
+    let zst_chunk_size = (u32::MAX / 2) as usize;
+
+    let event = EventQueue::<(), S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend(core::iter::repeat_n((), 1000));
+    event.push(());
+    assert_equal(get_chunks_capacities(&event), [zst_chunk_size]);
+    assert_eq!(consume_copies(&mut reader.iter()).len(), 1001);
+
+    // Capacity ignored.
+    event.change_chunk_capacity(4);
+    event.push(());
+    assert_equal(get_chunks_capacities(&event), [zst_chunk_size, zst_chunk_size]);
+    assert_eq!(consume_copies(&mut reader.iter()).len(), 1);
+
+    // Timestamps stored per item - regular chunks.
+    struct TS{} impl Settings for TS{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const TIMESTAMPS: bool = true;
+    }
+    let event = EventQueue::<(), TS>::new();
+    event.extend(core::iter::repeat_n((), 6));
+    assert_equal(get_chunks_capacities(&event), [4, 4]);
+}
+
+#[test]
+fn epoch_wrap_test(){
+    use crate::StartPositionEpoch;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let chunk_epoch = |event: &EventQueue<usize, S>| {
+        let list = event.0.list.lock();
+        unsafe{ (*list.first).chunk_state(Ordering::Relaxed).epoch() }
+    };
+
+    // Jump right before wrap.
+    {
+        let list = event.0.list.lock();
+        let epoch = StartPositionEpoch::new(i32::MAX as u32 - 1);
+        unsafe{ (*list.first).set_epoch(epoch, Ordering::Relaxed, Ordering::Release); }
+    }
+    let mut reader = EventReader::new(&event);
+    let mut idle_reader = EventReader::new(&event);
+
+    // clear/truncate_front storm across wrap: MAX-1 -> MAX -> 0 -> 1 -> 2
+    for i in 0..4 {
+        event.extend(0..6);
+        if i % 2 == 0 {
+            event.clear();
+            event.push(100 + i);
+            assert_equal(consume_copies(&mut reader.iter()), [100 + i]);
+        } else {
+            event.truncate_front(2);
+            event.push(100 + i);
+            assert_equal(consume_copies(&mut reader.iter()), [4, 5, 100 + i]);
+        }
+    }
+    assert_eq!(chunk_epoch(&event), StartPositionEpoch::new(2));
+
+    // Reader, which did not read during storm - starts from the last start position.
+    assert_equal(consume_copies(&mut idle_reader.iter()), [4, 5, 103]);
+}
+
 #[test]
 fn double_buffering_test(){
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = u32::MAX;
+        const CLEANUP: CleanupMode = DefaultSettings::CLEANUP;
+        const RECYCLE_POOL_SIZE: usize = 1;
+    }
     let event = EventQueue::<usize, S>::new();
     let mut reader = EventReader::new(&event);
 
@@ -77,14 +222,36 @@ fn double_buffering_test(){
     assert_equal(get_chunks_capacities(&event), [4,4,8,8]);
 
     consume_copies(&mut reader.iter());
-    assert_eq!(event.0.list.lock().free_chunk.as_ref().unwrap().capacity(), 8);
+    assert_equal(event.0.list.lock().free_chunks.iter().map(|c|c.capacity()), [8]);
     assert_equal(get_chunks_capacities(&event), [8]);
 
     event.extend(0..32);
-    assert!(event.0.list.lock().free_chunk.is_none());
+    assert!(event.0.list.lock().free_chunks.is_empty());
     assert_equal(get_chunks_capacities(&event), [8, 8, 16, 16]);
 }
 
+#[test]
+fn recycle_pool_test(){
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const RECYCLE_POOL_SIZE: usize = 2;
+        const STATS: bool = true;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..16);
+    consume_copies(&mut reader.iter());
+    assert_equal(get_chunks_capacities(&event), [4]);
+    assert_eq!(event.0.list.lock().free_chunks.len(), 2);
+
+    event.extend(0..12);
+    assert!(event.0.list.lock().free_chunks.is_empty());
+    assert_eq!(event.stats().chunks_recycled, 2);
+    assert_eq!(event.stats().chunks_allocated, 5);
+}
+
 #[test]
 fn resize_test(){
     let event = EventQueue::<usize, S>::new();
@@ -110,6 +277,28 @@ fn resize_test(){
     assert_equal(get_chunks_lens(&event), [6, 1]);
 }
 
+#[test]
+fn resize_to_fit_test(){
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..32);
+    consume_copies(&mut reader.iter());
+    assert_equal(get_chunks_capacities(&event), [16]);
+    assert_equal(get_chunks_lens(&event), [8]);
+
+    event.resize_to_fit();
+    assert_equal(get_chunks_capacities(&event), [16, 8]);
+
+    // already fit
+    event.resize_to_fit();
+    assert_equal(get_chunks_capacities(&event), [16, 8]);
+
+    event.push(32);
+    assert_equal(consume_copies(&mut reader.iter()), [32]);
+    assert_equal(get_chunks_capacities(&event), [8]);
+}
+
 #[test]
 fn truncate_front_test(){
     let event = EventQueue::<usize, S>::new();
@@ -135,6 +324,24 @@ fn truncate_front_test(){
     assert_equal(consume_copies(&mut reader.iter()), []);
 }
 
+#[test]
+fn truncate_front_many_chunks_test(){
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..1000);
+    assert_eq!(get_chunks_capacities(&event).len(), 250);
+
+    // in the middle of chunk
+    event.truncate_front(6);
+    assert_equal(get_chunks_capacities(&event), [4,4,4]);
+    assert_equal(consume_copies(&mut reader.iter()), 994..1000);
+}
+
 #[test]
 fn force_cleanup_test(){
     struct S{} impl Settings for S{
@@ -167,6 +374,63 @@ fn force_cleanup_test(){
     }
 }
 
+#[test]
+fn cleanup_force_test(){
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut hung_reader = EventReader::new(&event);
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..20);
+    skip(&mut reader.iter(), 9);
+    assert_equal(get_chunks_capacities(&event), [4,4,4,4,4]);
+
+    // nothing to free
+    event.cleanup_force(5);
+    assert_equal(get_chunks_capacities(&event), [4,4,4,4,4]);
+
+    event.cleanup_force(2);
+    // 2nd chunk freed. 1st and 3rd occupied by readers.
+    assert_equal(get_chunks_capacities(&event), [4,4,4,4]);
+
+    assert_equal(consume_copies(&mut hung_reader.iter()), 12..20);
+    assert_equal(get_chunks_capacities(&event), [4,4,4]);
+    assert_equal(consume_copies(&mut reader.iter()), 12..20);
+    assert_equal(get_chunks_capacities(&event), [4]);
+
+    // keep at least last chunk. Readers still occupy previous one.
+    event.extend(20..24);
+    event.cleanup_force(0);
+    assert_equal(get_chunks_capacities(&event), [4,4]);
+    assert_equal(consume_copies(&mut reader.iter()), 20..24);
+}
+
+#[test]
+fn bytes_allocated_test(){
+    fn factual_bytes_allocated<T, S: Settings>(event_queue: &EventQueue<T, S>) -> usize {
+        let mut bytes: usize = get_chunks_capacities(event_queue).into_iter()
+            .map(DynamicChunk::<T, BS<S>>::allocation_size)
+            .sum();
+        for free_chunk in &event_queue.0.list.lock().free_chunks{
+            bytes += DynamicChunk::<T, BS<S>>::allocation_size(free_chunk.capacity());
+        }
+        bytes
+    }
+
+    let event = EventQueue::<[u8; 3], S>::new();
+    let mut reader = EventReader::new(&event);
+    assert_eq!(event.bytes_allocated(), factual_bytes_allocated(&event));
+
+    event.extend((0..30).map(|_| [0;3]));
+    assert_eq!(event.bytes_allocated(), factual_bytes_allocated(&event));
+
+    reader.iter().for_each(|_|{});
+    assert_eq!(event.bytes_allocated(), factual_bytes_allocated(&event));
+}
+
 #[test]
 fn capacity_test(){
     let event = EventQueue::<usize, S>::new();
@@ -247,4 +511,130 @@ fn CleanupMode_Never_test(){
 
     event.cleanup();
     assert_equal(get_chunks_capacities(&event), [4]);
-}
\ No newline at end of file
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn CleanupMode_EveryNChunks_test(){
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const CLEANUP: CleanupMode = CleanupMode::EveryNChunks(2);
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..12);
+    assert_equal(get_chunks_capacities(&event), [4,4,4]);
+
+    consume_copies(&mut reader.iter());
+    assert_equal(get_chunks_capacities(&event), [4,4,4]);
+
+    // 3rd new chunk - no cleanup
+    event.push(100);
+    assert_equal(get_chunks_capacities(&event), [4,4,4,4]);
+
+    // 4th new chunk - cleanup. Reader still occupies 3rd chunk.
+    event.extend(0..4);
+    assert_equal(get_chunks_capacities(&event), [4,4,4]);
+
+    // manual cleanup always available
+    consume_copies(&mut reader.iter());
+    event.cleanup();
+    assert_equal(get_chunks_capacities(&event), [4]);
+}
+
+#[test]
+fn subscribe_batch_test(){
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    event.extend(0..6);
+
+    let mut readers = EventReader::new_batch(&event, 3);
+    assert_eq!(readers.len(), 3);
+    assert!(EventReader::new_batch(&event, 0).is_empty());
+
+    event.extend(6..12);
+    assert_equal(get_chunks_capacities(&event), [4,4,4]);
+
+    for reader in &mut readers{
+        assert_equal(consume_copies(&mut reader.iter()), 6..12);
+    }
+    // first chunk have no readers, second - read by all.
+    assert_equal(get_chunks_capacities(&event), [4]);
+
+    drop(readers);
+    event.push(12);
+    let mut reader = EventReader::new(&event);
+    event.push(13);
+    assert_equal(consume_copies(&mut reader.iter()), [13]);
+}
+
+#[test]
+fn runtime_settings_test(){
+    use crate::mpmc::RuntimeSettings;
+
+    let event = EventQueue::<usize, RuntimeSettings>::builder()
+        .min_chunk_size(4)
+        .max_chunk_size(8)
+        .cleanup(CleanupMode::Never)
+        .build();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..40);
+    assert_equal(get_chunks_capacities(&event), [4,4,8,8,8,8]);
+
+    consume_copies(&mut reader.iter());
+    assert_equal(get_chunks_capacities(&event), [4,4,8,8,8,8]);
+
+    event.cleanup();
+    assert_equal(get_chunks_capacities(&event), [8]);
+}
+
+#[test]
+fn cursor_ordering_test(){
+    use crate::cursor::Cursor;
+    use crate::Position;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const CLEANUP: CleanupMode = CleanupMode::Never;
+    }
+    let event = EventQueue::<usize, S>::new();
+    event.extend(0..6);
+
+    let list = &event.0.list.lock();
+    let mut chunks: Vec<*const DynamicChunk<usize, BS<S>>> = Vec::new();
+    unsafe{
+        foreach_chunk(list.first, null(), Ordering::Acquire, |chunk|{
+            chunks.push(chunk);
+            Continue(())
+        });
+    }
+    assert_eq!(chunks.len(), 2);
+
+    // In order.
+    let cursors = [
+        Cursor{chunk: chunks[0], index: 0},
+        Cursor{chunk: chunks[0], index: 3},
+        Cursor{chunk: chunks[0], index: 4},
+        Cursor{chunk: chunks[1], index: 0},
+        Cursor{chunk: chunks[1], index: 2},
+    ];
+    for (i, l) in cursors.iter().enumerate(){
+        for (j, r) in cursors.iter().enumerate(){
+            assert_eq!(l.cmp(r), i.cmp(&j));
+            assert_eq!(l.partial_cmp(r), Some(i.cmp(&j)));
+            assert_eq!(l < r, i < j);
+            assert_eq!(l == r, i == j);
+        }
+    }
+
+    let positions: Vec<u64> = cursors.iter().map(|c| Position::from(*c).sequence()).collect();
+    // End of chunk and start of the next one - is the same position.
+    assert_equal(positions, [0, 3, 4, 4, 6]);
+}
@@ -4,20 +4,101 @@
 //
 
 use crate::sync::Ordering;
-use std::ptr::{NonNull};
-use crate::event_queue::{CleanupMode, EventQueue, foreach_chunk, Settings};
-use std::ops::ControlFlow::{Continue};
+use core::ptr::null;
+use crate::event_queue::{CleanupMode, EventQueue, foreach_chunk, foreach_chunk_ptr_mut, Settings};
+use core::ops::ControlFlow::{Continue, Break};
+use core::ops::Deref;
 use crate::cursor::Cursor;
 use crate::chunk_state::{PackedChunkState};
-use crate::StartPositionEpoch;
+#[cfg(feature = "std")]
+use crate::chunk_state::ChunkState;
+use crate::{Position, StartPositionEpoch};
+#[cfg(feature = "std")]
+use crate::reader_lease::ReaderLease;
+use crate::sync::Arc;
+use core::convert::Infallible;
+use core::task::{Context, Poll, Waker};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Returned on read attempt from [EventReader](crate::mpmc::EventReader) with TTL, which was expired by queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReaderExpired;
+
+impl core::fmt::Display for ReaderExpired{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("EventReader expired")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReaderExpired{}
+
+/// Returned on read attempt from [WeakReader](crate::mpmc::WeakReader), which missed items.
+/// Holds count of missed items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lagged(pub u64);
+
+impl core::fmt::Display for Lagged{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "WeakReader lagged behind by {} items", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Lagged{}
+
+/// Per-reader settings. See [EventReader::with_options](crate::mpmc::EventReader::with_options).
+///
+/// ```
+/// # use rc_event_queue::ReaderOptions;
+/// let options = ReaderOptions{ cleanup: false, ..Default::default() };
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReaderOptions{
+    /// Run cleanup from reader's [Iter](crate::mpmc::Iter) drop, with [CleanupMode::OnChunkRead]. `true` by default.
+    ///
+    /// `false` - for latency-critical reader: it never takes queue lock for cleanup on read.
+    /// Chunks it read are freed by cleanup of other readers, writers or [cleanup](crate::mpmc::EventQueue::cleanup) call.
+    pub cleanup: bool,
+    /// See [EventReader::new_named](crate::mpmc::EventReader::new_named).
+    #[cfg(feature = "std")]
+    pub name: Option<String>,
+    /// See [EventReader::with_ttl](crate::mpmc::EventReader::with_ttl).
+    #[cfg(feature = "std")]
+    pub ttl: Option<Duration>,
+}
+
+impl Default for ReaderOptions{
+    #[inline]
+    fn default() -> Self {
+        Self{
+            cleanup: true,
+            #[cfg(feature = "std")]
+            name: None,
+            #[cfg(feature = "std")]
+            ttl: None,
+        }
+    }
+}
 
 pub struct EventReader<T, S: Settings>
 {
+    /// Keeps queue alive. Chunk of expired reader may be already freed - so not through it.
+    pub(super) event: Arc<EventQueue<T, S>>,
     pub(super) position: Cursor<T, S>,
     pub(super) start_position_epoch: StartPositionEpoch,
+    /// Items skipped by fast forward and by `ITEM_TTL`. See [take_missed_count](Self::take_missed_count).
+    pub(super) missed: u64,
+    /// See [ReaderOptions::cleanup].
+    pub(super) cleanup: bool,
+    /// Only readers with TTL (or name, or under `STALE_READERS`) have it.
+    #[cfg(feature = "std")]
+    pub(super) lease: Option<Arc<ReaderLease<T, S>>>,
 }
 
-unsafe impl<T, S: Settings> Send for EventReader<T, S>{}
+// Reader gives out `&T` and may free chunks on cleanup.
+unsafe impl<T: Send + Sync, S: Settings> Send for EventReader<T, S>{}
 
 impl<T, S: Settings> EventReader<T, S>
 {
@@ -26,13 +107,16 @@ impl<T, S: Settings> EventReader<T, S>
     #[inline(never)]
     #[cold]
     fn do_update_start_position_and_get_chunk_state(&mut self) -> PackedChunkState {
-        let event = unsafe{(*self.position.chunk).event()};
+        let event = unsafe{&*self.event_ptr()};
+
+        yield_point!(ReaderFastForward);
 
         // fast forward
         {
         let start_position_lock = event.start_position.lock();
         if let Some(start_position) = *start_position_lock{
             if self.position < start_position {
+                self.missed += Position::from(start_position).sequence() - Position::from(self.position).sequence();
 
                 // 1. Enter new_position chunk
                 let new_chunk = unsafe{&*start_position.chunk};
@@ -40,8 +124,7 @@ impl<T, S: Settings> EventReader<T, S>
 
                 // 2. Mark current chunk read
                 let chunk = unsafe{&*self.position.chunk};
-                if /*constexpr*/ S::CLEANUP == CleanupMode::OnChunkRead {
-                    let event = chunk.event();
+                if event.cleanup_mode() == CleanupMode::OnChunkRead && self.cleanup {
                     let readers_entered = chunk.readers_entered().load(Ordering::Acquire);
 
                     // MORE or equal, just in case (this MT...). This check is somewhat opportunistic.
@@ -80,25 +163,338 @@ impl<T, S: Settings> EventReader<T, S>
     // Do we actually need this as separate fn? Benchmark.
     #[inline]
     pub fn update_position(&mut self) {
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            if !lease.begin_read(){
+                return;
+            }
+        }
+
         self.update_start_position_and_get_chunk_state();
+
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            lease.end_read(self.position.chunk);
+        }
     }
 
     // TODO: copy_iter() ?
 
+    /// Expired reader's iterator is empty.
     #[inline]
-    pub fn iter(&mut self) -> Iter<T, S>{
+    pub fn iter(&mut self) -> Iter<'_, T, S>{
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            if !lease.begin_read(){
+                return Iter::expired(self);
+            }
+        }
         Iter::new(self)
     }
+
+    #[inline]
+    pub fn try_iter(&mut self) -> Result<Iter<'_, T, S>, ReaderExpired>{
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            if !lease.begin_read(){
+                return Err(ReaderExpired);
+            }
+        }
+        Ok(Iter::new(self))
+    }
+
+    /// Expired reader's iterator is empty.
+    #[inline]
+    pub fn peek_iter(&mut self) -> PeekIter<'_, T, S>{
+        PeekIter{iter: self.iter()}
+    }
+
+    #[inline]
+    pub fn try_peek_iter(&mut self) -> Result<PeekIter<'_, T, S>, ReaderExpired>{
+        Ok(PeekIter{iter: self.try_iter()?})
+    }
+
+    /// Panics, if reader expired.
+    pub fn position(&self) -> Position{
+        // Do not let queue expire reader and free its chunk, while we're here.
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            if !lease.begin_read(){
+                panic!("EventReader expired.");
+            }
+        }
+
+        let position = Position::from(self.position);
+
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            lease.end_read(self.position.chunk);
+        }
+
+        position
+    }
+
+    /// Panics, if reader expired.
+    #[inline]
+    pub fn fork(&self) -> Self{
+        match self.try_fork(){
+            Ok(reader) => reader,
+            Err(_) => panic!("EventReader expired. Use try_fork() for readers with TTL."),
+        }
+    }
+
+    pub fn try_fork(&self) -> Result<Self, ReaderExpired>{
+        let event = unsafe{&*self.event_ptr()};
+        event.fork_reader(&mut event.list.lock(), self)
+    }
+
+    /// Expired reader can not rewind - returns 0.
+    ///
+    /// Only for queues, where chunks freed under lock (mpmc).
+    pub fn rewind(&mut self, n: usize) -> usize{
+        let event = unsafe{&*self.event_ptr()};
+        event.rewind_reader(&mut event.list.lock(), self, n).unwrap_or(0)
+    }
+
+    /// Move out all items, available at call moment. None - if queue has other readers,
+    /// or reader expired.
+    ///
+    /// Only for queues, where chunks freed under lock (mpmc).
+    pub fn drain(&mut self) -> Option<Drain<'_, T, S>>{
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            if !lease.begin_read(){
+                return None;
+            }
+        }
+
+        let event = unsafe{&*self.event_ptr()};
+        let end = loop{
+            self.update_start_position_and_get_chunk_state();
+            let mut list = event.list.lock();
+            if matches!(*event.start_position.lock(), Some(start_position) if self.position < start_position){
+                // Truncated just now - fast forward again.
+                continue;
+            }
+            break event.begin_drain(&mut list, self);
+        };
+        let end = match end{
+            Some(end) => end,
+            None => {
+                #[cfg(feature = "std")]
+                if let Some(lease) = &self.lease{
+                    lease.end_read(self.position.chunk);
+                }
+                return None;
+            }
+        };
+
+        // Not with Iter::new - it would fast forward to new start_position.
+        let chunk_state = unsafe{&*self.position.chunk}.chunk_state(Ordering::Acquire);
+        Some(Drain{
+            iter: Iter{
+                position: self.position,
+                start: self.position,
+                chunk_state,
+                event_reader: self,
+                #[cfg(feature = "streaming_iterator")]
+                current: null(),
+            },
+            end
+        })
+    }
+
+    // Valid, while reader alive. Not bound to `&self` borrow - reader can be mutated meanwhile.
+    #[inline]
+    fn event_ptr(&self) -> *const EventQueue<T, S>{
+        Arc::as_ptr(&self.event)
+    }
+
+    /// Wake `waker` on next publish. Panics without [Settings::NOTIFY].
+    pub fn register_waker(&self, waker: &Waker){
+        assert!(S::NOTIFY, "Settings::NOTIFY required.");
+        unsafe{&*self.event_ptr()}.register_waker(waker);
+    }
+
+    /// Clone of the next item, if any.
+    pub fn try_recv(&mut self) -> Result<Option<T>, ReaderExpired>
+        where T: Clone
+    {
+        Ok(self.try_iter()?.next().cloned())
+    }
+
+    /// Panics without [Settings::NOTIFY].
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, ReaderExpired>>
+        where T: Clone
+    {
+        if let Some(result) = self.try_recv().transpose(){
+            return Poll::Ready(result);
+        }
+        self.register_waker(cx.waker());
+        // Item could be published before waker registered.
+        match self.try_recv().transpose(){
+            Some(result) => Poll::Ready(result),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Panics without [Settings::NOTIFY].
+    pub async fn recv(&mut self) -> Result<T, ReaderExpired>
+        where T: Clone
+    {
+        assert!(S::NOTIFY, "Settings::NOTIFY required.");
+        // Reader keeps queue alive.
+        let event = unsafe{&*self.event_ptr()};
+        loop{
+            if let Some(result) = self.try_recv().transpose(){
+                return result;
+            }
+            #[cfg(feature = "tokio")]
+            let notified = event.notified();
+            #[cfg(not(feature = "tokio"))]
+            let notified = event.listen();
+            if let Some(result) = self.try_recv().transpose(){
+                return result;
+            }
+            notified.await;
+        }
+    }
+
+    /// Skip to the end of the queue, and return the last item.
+    /// Everything before it considered read.
+    ///
+    /// Returns None, if there is no new items.
+    pub fn latest(&mut self) -> Option<Latest<'_, T, S>>{
+        let mut iter = self.iter();
+        let last = iter.skip_to_last()?;
+        Some(Latest{iter, last})
+    }
+
+    /// Skip all available items, without reading them. Returns number of skipped items.
+    pub fn skip_to_end(&mut self) -> usize{
+        self.iter().skip_to_end()
+    }
+
+    /// Number of unread items, visible to reader. Expired reader have 0.
+    pub fn lag(&self) -> usize{
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            if !lease.begin_read(){
+                return 0;
+            }
+        }
+
+        let event = unsafe{&*self.event_ptr()};
+        let lag = {
+            // Out-of-order chunk destruction happens under start_position lock.
+            // And all chunks after ours can not be freed in-order.
+            let start_position_lock = event.start_position.lock();
+            let position = match *start_position_lock{
+                Some(start_position) if self.position < start_position => start_position,
+                _ => self.position
+            };
+
+            let mut len = 0;
+            unsafe{
+                foreach_chunk(
+                    position.chunk,
+                    null(),
+                    Ordering::Acquire,
+                    |chunk| {
+                        len += chunk.chunk_state(Ordering::Acquire).len() as usize;
+                        Continue(())
+                    }
+                );
+            }
+            len - position.index
+        };
+
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            lease.end_read(self.position.chunk);
+        }
+        lag
+    }
+
+    /// Is there anything to read? Does not mark anything read.
+    pub fn has_new(&self) -> bool{
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            if !lease.begin_read(){
+                return false;
+            }
+        }
+
+        let chunk_state = unsafe{&*self.position.chunk}.chunk_state(Ordering::Acquire);
+        // Fast path - items in current chunk, or nothing at all.
+        let has_new =
+            if chunk_state.epoch() != self.start_position_epoch {
+                None
+            } else if self.position.index < chunk_state.len() as usize {
+                Some(true)
+            } else if !chunk_state.has_next() {
+                Some(false)
+            } else {
+                None
+            };
+
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            lease.end_read(self.position.chunk);
+        }
+        match has_new{
+            Some(has_new) => has_new,
+            None => self.lag() != 0,
+        }
+    }
+
+    /// O(1) [lag](Self::lag) estimation, without lock. Counts not yet published
+    /// items too.
+    pub fn unread_estimate(&self) -> usize{
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            if !lease.begin_read(){
+                return 0;
+            }
+        }
+
+        let event = unsafe{&*self.event_ptr()};
+        // Items before start_position (cleared/truncated) are not counted.
+        let start = core::cmp::max(Position::from(self.position).sequence(), event.start_seq());
+        let estimate = event.end_seq().saturating_sub(start) as usize;
+
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            lease.end_read(self.position.chunk);
+        }
+        estimate
+    }
+
+    #[inline]
+    pub fn is_expired(&self) -> bool{
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.lease{
+            return lease.is_expired();
+        }
+        false
+    }
+
+    #[inline]
+    pub fn take_missed_count(&mut self) -> u64{
+        self.update_position();
+        core::mem::take(&mut self.missed)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn name(&self) -> Option<&str>{
+        self.lease.as_ref().and_then(|lease| lease.name())
+    }
 }
 
 impl<T, S: Settings> Drop for EventReader<T, S>{
     fn drop(&mut self) {
-        unsafe {
-            EventQueue::<T, S>::unsubscribe(
-                NonNull::from((*self.position.chunk).event()),
-                self
-            );
-        }
+        self.event.unsubscribe(self);
     }
 }
 
@@ -106,6 +502,41 @@ impl<T, S: Settings> Drop for EventReader<T, S>{
 pub trait LendingIterator{
     type ItemValue;
     fn next(&mut self) -> Option<&Self::ItemValue>;
+
+    /// Call `f` for each remaining item.
+    #[inline]
+    fn for_each<F>(mut self, mut f: F)
+        where Self: Sized, F: FnMut(&Self::ItemValue)
+    {
+        while let Some(value) = self.next(){
+            f(value);
+        }
+    }
+
+    /// Fold all remaining items into accumulator.
+    #[inline]
+    fn fold<B, F>(mut self, init: B, mut f: F) -> B
+        where Self: Sized, F: FnMut(B, &Self::ItemValue) -> B
+    {
+        let mut acc = init;
+        while let Some(value) = self.next(){
+            acc = f(acc, value);
+        }
+        acc
+    }
+
+    /// Call `f` for each remaining item, until it returns error.
+    ///
+    /// Item, on which `f` failed - considered consumed.
+    #[inline]
+    fn try_for_each<F, E>(&mut self, mut f: F) -> Result<(), E>
+        where F: FnMut(&Self::ItemValue) -> Result<(), E>
+    {
+        while let Some(value) = self.next(){
+            f(value)?;
+        }
+        Ok(())
+    }
 }
 
 // Having separate chunk+index, allow us to postpone marking passed chunks as read, until the Iter destruction.
@@ -113,32 +544,89 @@ pub trait LendingIterator{
 pub struct Iter<'a, T, S: Settings>
 {
     position: Cursor<T, S>,
+    /// Position after fast-forward and expired skip. For [rollback](Self::rollback).
+    start: Cursor<T, S>,
     chunk_state : PackedChunkState,
     // &mut to ensure that only one Iter for Reader can exists
     event_reader : &'a mut EventReader<T, S>,
+    /// Item of the last `StreamingIterator::advance`. Its chunk is not freed while we alive.
+    #[cfg(feature = "streaming_iterator")]
+    current: *const T,
 }
 
 impl<'a, T, S: Settings> Iter<'a, T, S>{
     #[inline]
     fn new(event_reader: &'a mut EventReader<T, S>) -> Self{
         let chunk_state = event_reader.update_start_position_and_get_chunk_state();
-        Self{
+        #[cfg_attr(not(feature = "std"), allow(unused_mut))]
+        let mut this = Self{
             position: event_reader.position,
+            start: event_reader.position,
             chunk_state,
             event_reader,
+            #[cfg(feature = "streaming_iterator")]
+            current: null(),
+        };
+        #[cfg(feature = "std")]
+        {
+            this.skip_expired();
+            this.start = this.position;
         }
+        this
     }
-}
 
-impl<'a, T, S: Settings> LendingIterator for Iter<'a, T, S>{
-    type ItemValue = T;
+    /// Empty iterator of expired reader. Reader's chunk may be already freed -
+    /// so null chunk, which is never dereferenced: there is no items and no next chunk.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn expired(event_reader: &'a mut EventReader<T, S>) -> Self{
+        let position = Cursor{chunk: null(), index: 0};
+        Self{
+            position,
+            start: position,
+            chunk_state: PackedChunkState::pack(ChunkState{
+                len: 0,
+                has_next: false,
+                epoch: StartPositionEpoch::zero()
+            }),
+            event_reader,
+            #[cfg(feature = "streaming_iterator")]
+            current: null(),
+        }
+    }
 
+    /// Skip items older than [Settings::ITEM_TTL].
+    /// Items are in push time order - so only prefix can be expired.
+    #[cfg(feature = "std")]
     #[inline]
-    fn next(&mut self) -> Option<&Self::ItemValue> {
-        if /*unlikely*/ self.position.index as u32 == self.chunk_state.len(){
+    fn skip_expired(&mut self){
+        let ttl = match S::ITEM_TTL{
+            None => return,
+            Some(ttl) => ttl,
+        };
+        let threshold = match crate::utils::now().and_then(|now| now.checked_sub(ttl)){
+            None => return,
+            Some(threshold) => threshold,
+        };
+        while let Some(timestamp) = self.peek_timestamp(){
+            if timestamp >= threshold {
+                break;
+            }
+            self.position.index += 1;
+            self.event_reader.missed += 1;
+        }
+    }
+
+    /// Move to the next chunk.
+    ///
+    /// Returns false, if there is no next chunk, or it is still empty.
+    /// In that case iterator latched at the end, until [refresh](Self::refresh).
+    #[inline]
+    fn switch_chunk(&mut self) -> bool {
+        loop{
             // should try next chunk?
             if !self.chunk_state.has_next(){
-                return None;
+                return false;
             }
 
             // acquire next chunk
@@ -159,9 +647,187 @@ impl<'a, T, S: Settings> LendingIterator for Iter<'a, T, S>{
             self.chunk_state = next_chunk.chunk_state(Ordering::Acquire);
 
             // Maybe 0, when new chunk is created, but item still not pushed.
-            // It is possible rework `push`/`extend` in the way that this situation will not exists.
-            // But for now, just have this check here.
-            if self.chunk_state.len() == 0 {
+            // Or chunk was added by `change_chunk_capacity`, and followed by another one.
+            // In the latter case - just pass through it.
+            if self.chunk_state.len() != 0 {
+                return true;
+            }
+        }
+    }
+
+    /// Return reader to the position, iterator started from - as if nothing was read.
+    #[inline]
+    pub fn rollback(mut self){
+        self.rollback_impl();
+    }
+
+    fn rollback_impl(&mut self){
+        let end_chunk = self.position.chunk;
+        if !core::ptr::eq(self.start.chunk, end_chunk) {
+            // Exit chunks entered by switch_chunk. They are held by start chunk.
+            unsafe {
+                foreach_chunk(
+                    (*self.start.chunk).next(Ordering::Acquire),
+                    null(),
+                    Ordering::Acquire,
+                    |chunk| {
+                        chunk.read_completely_times().fetch_add(1, Ordering::AcqRel);
+                        if core::ptr::eq(chunk, end_chunk) {
+                            Break(())
+                        } else {
+                            Continue(())
+                        }
+                    }
+                );
+            }
+        }
+        // Drop do the rest.
+        self.position = self.start;
+    }
+
+    /// Pick up items, pushed after iterator reached its end.
+    ///
+    /// Iterator is fused - once it returns None, it will return None until refresh.
+    #[inline]
+    pub fn refresh(&mut self){
+        #[cfg(feature = "std")]
+        if self.position.chunk.is_null(){
+            return;
+        }
+        self.chunk_state = unsafe{&*self.position.chunk}.chunk_state(Ordering::Acquire);
+        #[cfg(feature = "std")]
+        self.skip_expired();
+    }
+
+    /// Return next item, without advancing.
+    ///
+    /// May switch to the next chunk, if current one is over. This is the same as
+    /// `next` would do - nothing is marked as read.
+    #[inline]
+    pub fn peek(&mut self) -> Option<&T> {
+        if /*unlikely*/ self.position.index as u32 == self.chunk_state.len(){
+            if !self.switch_chunk(){
+                return None;
+            }
+        }
+
+        let chunk = unsafe{&*self.position.chunk};
+        Some(unsafe { chunk.get_unchecked(self.position.index) })
+    }
+
+    /// Position of the item, [peek](Self::peek) would return.
+    #[inline]
+    pub(crate) fn peek_position(&mut self) -> Option<Position> {
+        self.peek()?;
+        Some(Position::from(self.position))
+    }
+
+    /// Push time of the item, [peek](Self::peek) would return.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn peek_timestamp(&mut self) -> Option<Instant> {
+        assert!(S::TIMESTAMPS, "Settings::TIMESTAMPS is disabled.");
+        self.peek()?;
+        let chunk = unsafe{&*self.position.chunk};
+        Some(unsafe{ chunk.get_timestamp_unchecked(self.position.index) })
+    }
+
+    /// Skip all available items. Returns number of skipped items.
+    fn skip_to_end(&mut self) -> usize {
+        let mut skipped = 0;
+        loop{
+            let len = self.chunk_state.len() as usize;
+            skipped += len - self.position.index;
+            self.position.index = len;
+
+            if !self.switch_chunk(){
+                return skipped;
+            }
+        }
+    }
+
+    /// Skip all available items. Returns position of the last one.
+    ///
+    /// Iter stays in the last item's chunk.
+    fn skip_to_last(&mut self) -> Option<Cursor<T, S>> {
+        let mut last = None;
+        loop{
+            let len = self.chunk_state.len() as usize;
+            if self.position.index < len{
+                last = Some(Cursor{chunk: self.position.chunk, index: len-1});
+                self.position.index = len;
+            }
+
+            let prev_position = self.position;
+            let prev_chunk_state = self.chunk_state;
+            if !self.switch_chunk(){
+                if self.position.chunk != prev_position.chunk && last.is_some(){
+                    // Switched to empty chunk(s). Step back, to keep last item's chunk occupied.
+                    unsafe {
+                        let end_chunk = (*self.position.chunk).next(Ordering::Acquire);
+                        foreach_chunk(
+                            (*prev_position.chunk).next(Ordering::Acquire),
+                            end_chunk,
+                            Ordering::Acquire,
+                            |chunk| {
+                                chunk.read_completely_times().fetch_add(1, Ordering::AcqRel);
+                                Continue(())
+                            }
+                        );
+                    }
+                    self.position = prev_position;
+                    self.chunk_state = prev_chunk_state;
+                    self.chunk_state.set_has_next(false);
+                }
+                return last;
+            }
+        }
+    }
+
+    /// Internal iteration. Chunk and its len loaded once per chunk, not per item.
+    #[inline]
+    fn try_fold_impl<B, E, F>(&mut self, init: B, mut f: F) -> Result<B, E>
+        where F: FnMut(B, &T) -> Result<B, E>
+    {
+        let mut acc = init;
+        loop{
+            let len = self.chunk_state.len() as usize;
+            if self.position.index < len {
+                let chunk = unsafe{&*self.position.chunk};
+                while self.position.index < len {
+                    let value = unsafe { chunk.get_unchecked(self.position.index) };
+                    self.position.index += 1;
+                    acc = f(acc, value)?;
+                }
+            }
+
+            if !self.switch_chunk(){
+                return Ok(acc);
+            }
+        }
+    }
+}
+
+impl<'a, T, S: Settings> Iter<'a, T, S>{
+    /// Same as `next`, with item's push time.
+    #[cfg(feature = "std")]
+    pub fn next_timestamped(&mut self) -> Option<(&T, Instant)> {
+        assert!(S::TIMESTAMPS, "Settings::TIMESTAMPS is disabled.");
+
+        let value = LendingIterator::next(self)? as *const T;
+        let chunk = unsafe{&*self.position.chunk};
+        let time = unsafe{ chunk.get_timestamp_unchecked(self.position.index - 1) };
+        Some((unsafe{&*value}, time))
+    }
+}
+
+impl<'a, T, S: Settings> LendingIterator for Iter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        if /*unlikely*/ self.position.index as u32 == self.chunk_state.len(){
+            if !self.switch_chunk(){
                 return None;
             }
         }
@@ -172,12 +838,144 @@ impl<'a, T, S: Settings> LendingIterator for Iter<'a, T, S>{
 
         Some(value)
     }
+
+    #[inline]
+    fn for_each<F>(mut self, mut f: F)
+        where F: FnMut(&Self::ItemValue)
+    {
+        let result: Result<(), Infallible> = self.try_fold_impl((), |_, value| {
+            f(value);
+            Ok(())
+        });
+        let _ = result;
+    }
+
+    #[inline]
+    fn fold<B, F>(mut self, init: B, mut f: F) -> B
+        where F: FnMut(B, &Self::ItemValue) -> B
+    {
+        let result: Result<B, Infallible> = self.try_fold_impl(init, |acc, value| Ok(f(acc, value)));
+        match result {
+            Ok(acc) => acc,
+            Err(never) => match never {},
+        }
+    }
+
+    #[inline]
+    fn try_for_each<F, E>(&mut self, mut f: F) -> Result<(), E>
+        where F: FnMut(&Self::ItemValue) -> Result<(), E>
+    {
+        self.try_fold_impl((), |_, value| f(value))
+    }
+}
+
+/// [Iter], which rollbacks on drop.
+pub struct PeekIter<'a, T, S: Settings>{
+    iter: Iter<'a, T, S>,
+}
+
+impl<'a, T, S: Settings> LendingIterator for PeekIter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        self.iter.next()
+    }
+}
+
+impl<'a, T, S: Settings> Drop for PeekIter<'a, T, S>{
+    #[inline]
+    fn drop(&mut self) {
+        // Then Iter::drop, as after rollback.
+        self.iter.rollback_impl();
+    }
+}
+
+/// Item of [EventReader::latest]. Read session stays open, while it alive.
+pub struct Latest<'a, T, S: Settings>{
+    // Occupy last item's chunk. And keep lease in read - so reader can not expire meanwhile.
+    iter: Iter<'a, T, S>,
+    last: Cursor<T, S>,
+}
+
+impl<'a, T, S: Settings> Deref for Latest<'a, T, S>{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        debug_assert!(self.last.chunk == self.iter.position.chunk);
+        unsafe{ (*self.last.chunk).get_unchecked(self.last.index) }
+    }
+}
+
+pub struct Drain<'a, T, S: Settings>{
+    iter: Iter<'a, T, S>,
+    /// Queue's start_position, while drain in progress.
+    end: Cursor<T, S>,
+}
+
+impl<'a, T, S: Settings> Iterator for Drain<'a, T, S>{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.iter.position == self.end {
+            return None;
+        }
+        self.iter.peek()?;
+        if self.iter.position == self.end {
+            return None;
+        }
+
+        let chunk = unsafe{&*self.iter.position.chunk};
+        let index = self.iter.position.index;
+        debug_assert_eq!(chunk.drained(), index);
+        let value = unsafe{ chunk.take_unchecked(index) };
+        unsafe{ chunk.set_drained(index + 1); }
+        self.iter.position.index += 1;
+        Some(value)
+    }
+}
+
+impl<'a, T, S: Settings> Drop for Drain<'a, T, S>{
+    fn drop(&mut self) {
+        if self.iter.position != self.end {
+            let event = unsafe{&*self.iter.event_reader.event_ptr()};
+            event.end_drain(&mut event.list.lock(), self.end, self.iter.position);
+        }
+        // Iter marks passed chunks read.
+    }
+}
+
+#[cfg(feature = "streaming_iterator")]
+impl<'a, T, S: Settings> streaming_iterator::StreamingIterator for Iter<'a, T, S>{
+    type Item = T;
+
+    #[inline]
+    fn advance(&mut self) {
+        self.current = match LendingIterator::next(self){
+            Some(value) => value,
+            None => null(),
+        };
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&T> {
+        unsafe{ self.current.as_ref() }
+    }
 }
 
 impl<'a, T, S: Settings> Drop for Iter<'a, T, S>{
     #[inline]
     fn drop(&mut self) {
-        let try_cleanup = S::CLEANUP == CleanupMode::OnChunkRead;   // should be const
+        // Expired reader - nothing was read.
+        #[cfg(feature = "std")]
+        if self.position.chunk.is_null(){
+            return;
+        }
+
+        let event = unsafe{&*self.event_reader.event_ptr()};
+        let try_cleanup = event.cleanup_mode() == CleanupMode::OnChunkRead && self.event_reader.cleanup;
 
         debug_assert!(self.position >= self.event_reader.position);
         let mut need_cleanup = false;
@@ -189,21 +987,31 @@ impl<'a, T, S: Settings> Drop for Iter<'a, T, S>{
         unsafe {
             // It is ok here to switch chunks without chunk_switch_mutex.
             // Chunk already held by in-out counter imbalance.
-            foreach_chunk(
-                first_chunk,
+            // Raw pointer, not `&DynamicChunk` - reference argument would stay protected
+            // till closure end, while chunk may be freed right after increment.
+            foreach_chunk_ptr_mut(
+                first_chunk as *mut _,
                 end_chunk,
                 Ordering::Acquire,
                 |chunk| {
                     debug_assert!(
-                        !chunk.next(Ordering::Acquire).is_null()
+                        !(*chunk).next(Ordering::Acquire).is_null()
                     );
-                    let prev_read = chunk.read_completely_times().fetch_add(1, Ordering::AcqRel);
+                    // Load before increment - after it, chunk may be freed by concurrent cleanup.
+                    // readers_entered can only grow, so stale value may cause only spurious cleanup.
+                    let is_first = core::ptr::eq(chunk, first_chunk);
+                    let chunk_readers =
+                        if try_cleanup && is_first {
+                            (*chunk).readers_entered().load(Ordering::Acquire)
+                        } else {
+                            0
+                        };
+                    let prev_read = (*chunk).read_completely_times().fetch_add(1, Ordering::AcqRel);
 
                     if try_cleanup {
                         // TODO: move out of loop and benchmark.
-                        if chunk as *const _ == first_chunk{
+                        if is_first{
                             let read = prev_read+1;
-                            let chunk_readers = chunk.readers_entered().load(Ordering::Acquire);
                             if read >= chunk_readers {
                                 need_cleanup = true;
                             }
@@ -218,11 +1026,16 @@ impl<'a, T, S: Settings> Drop for Iter<'a, T, S>{
         // Cleanup (optional)
         if try_cleanup {
             if need_cleanup{
-                unsafe{&*end_chunk}.event().cleanup();
+                event.cleanup();
             }
         }
 
         // 2. Update EventReader chunk+index
         self.event_reader.position = self.position;
+
+        #[cfg(feature = "std")]
+        if let Some(lease) = &self.event_reader.lease{
+            lease.end_read(self.position.chunk);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,43 @@
+//! [LendingIterator] with generic associated type - item may be any type, borrowing from iterator.
+//!
+//! Implemented for every [crate::LendingIterator] - with `Item<'a> = &'a ItemValue`.
+//! So all readers' iterators (mpmc/spmc `Iter`, filtered, mapped, merged, etc.) work with it.
+//!
+//! ```
+//! # use rc_event_queue::mpmc::{EventQueue, EventReader};
+//! use rc_event_queue::gat::LendingIterator;
+//!
+//! // Item type is not fixed to `&T`.
+//! fn second<I: LendingIterator>(iter: &mut I) -> Option<I::Item<'_>>{
+//!     iter.next()?;
+//!     iter.next()
+//! }
+//!
+//! let event = EventQueue::<usize>::new();
+//! let mut reader = EventReader::new(&event);
+//! event.extend(0..3);
+//!
+//! assert_eq!(second(&mut reader.iter()), Some(&1));
+//! ```
+//!
+//! # Limitations
+//!
+//! Bounds on item over all lifetimes, like `for<'a> LendingIterator<Item<'a> = &'a T>`, currently
+//! imply `Self: 'static` - and readers' iterators borrow reader. Same for combinators (`for_each`,
+//! `filter`, ...) - so there is none. Use [crate::LendingIterator] for them, until this is lifted
+//! in the language.
+
+/// See [module](self) documentation.
+pub trait LendingIterator{
+    type Item<'a> where Self: 'a;
+    fn next(&mut self) -> Option<Self::Item<'_>>;
+}
+
+impl<I: crate::LendingIterator> LendingIterator for I{
+    type Item<'a> = &'a I::ItemValue where Self: 'a;
+
+    #[inline]
+    fn next(&mut self) -> Option<&I::ItemValue> {
+        crate::LendingIterator::next(self)
+    }
+}
@@ -10,7 +10,45 @@
 //!
 //! # Features
 //!
-//! * `double_buffering` : Reuse biggest freed chunk.
+//! * `std` (default) : Time-based functionality - readers with TTL, timestamps, retention.
+//!   Without it, crate is `no_std` + `alloc`.
+//! * `test_hooks` : `test_hooks` module - yield points for deterministic concurrency testing.
+//! * `async` : `futures_sink::Sink` for [mpmc::Sender].
+//! * `tokio` : `EventReader::recv().await` backed by `tokio::sync::Notify`.
+//! * `parking_lot` : `LockBackend::ParkingLot`, `LockBackend::ParkingLotFair` - `parking_lot` mutex for queue lock.
+//! * `metrics` : `EventQueue::record_metrics` - export queue counters/gauges to `metrics` facade.
+//! * `wal` : `wal` module - write-ahead log of pushed values, for replay after restart.
+//! * `streaming_iterator` : `streaming_iterator::StreamingIterator` for [mpmc::Iter] and [spmc::Iter] -
+//!   use its adapters instead of [LendingIterator].
+//! * `mmap` : `ChunkAllocator::MMAP` - chunks in memory-mapped temporary files. Unix only.
+//! * `double_buffering` : Reuse biggest freed chunk by default. Same as `RECYCLE_POOL_SIZE = 1`,
+//!   see [mpmc::Settings::RECYCLE_POOL_SIZE].
+//! * `cache_padded` : Place hot atomics (chunk state, `next`, reader counters) and queue lock
+//!   on separate cache lines, to avoid false sharing between writer and readers on many cores.
+//!   Costs several hundred bytes per chunk. Experimental - speedup on multi-core machines
+//!   is not measured yet. Benchmark your workload before enabling.
+//!
+//! # wasm
+//!
+//! Works on `wasm32-unknown-unknown`. It have no clock - time-based functionality
+//! (readers with TTL, timestamps) panics, and retention/`truncate_older_than` does nothing.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#![allow(clippy::init_numbered_fields)]
+#![allow(clippy::needless_return)]
+#![allow(clippy::collapsible_if)]
+#![allow(clippy::new_without_default)]
+
+extern crate alloc;
+
+// Call test_hooks yield point. Nothing, without `test_hooks` feature.
+macro_rules! yield_point {
+    ($point:ident) => {
+        #[cfg(feature = "test_hooks")]
+        crate::test_hooks::yield_point(crate::test_hooks::YieldPoint::$point);
+    };
+}
 
 mod sync;
 mod utils;
@@ -18,26 +56,75 @@ mod cursor;
 mod event_queue;
 mod event_reader;
 mod chunk_state;
+#[cfg(feature = "std")]
+mod reader_lease;
+mod stats;
+mod diagnostics;
+mod chunk_allocator;
+mod position;
+mod merge_reader;
+// With `tokio`, recv waits on tokio Notify instead of Listener.
+#[cfg_attr(feature = "tokio", allow(dead_code))]
+mod listener;
 #[allow(dead_code)]
 mod dynamic_array;
 
-// TODO: make double_buffering not a feature.
 #[allow(dead_code)]
 mod dynamic_chunk;
 
-/// Epoch of EventQueue::start_position
+/// Epoch of EventQueue::start_position. Incremented on each `clear`/`truncate_front`/`cleanup_force`.
+/// 31 bit - to fit into chunk state, next to len. Wraps to 0 after max.
+///
+/// Readers only compare epochs for equality, so wrap is transparent for them. Reader misses
+/// start position change only if exactly 2^31 (or multiple of it) changes happened between
+/// its reads - then it just continues from its own position, as if there were no clear.
 type StartPositionEpoch = crate::utils::Epoch<u32, {i32::MAX as u64}>;
 
 pub use crate::event_queue::CleanupMode;
+pub use crate::event_queue::GrowthStrategy;
+pub use crate::event_queue::LockBackend;
+pub use crate::event_queue::FreedChunkInfo;
+pub use crate::event_queue::AllocError;
+pub use crate::event_queue::TryPushError;
+#[cfg(feature = "std")]
+pub use crate::event_queue::StaleReaderPolicy;
 pub use crate::event_reader::LendingIterator;
+pub use crate::event_reader::ReaderExpired;
+pub use crate::event_reader::Lagged;
+pub use crate::event_reader::ReaderOptions;
+pub use crate::stats::Stats;
+pub use crate::diagnostics::{QueueDiagnostics, ChunkDiagnostics, ReaderDiagnostics};
+pub use crate::chunk_allocator::ChunkAllocator;
+pub use crate::position::Position;
+pub use crate::merge_reader::MergeOrder;
 
 pub mod prelude{
     pub use crate::CleanupMode;
+    pub use crate::GrowthStrategy;
+    pub use crate::LockBackend;
     pub use crate::LendingIterator;
 }
 
 pub mod mpmc;
 pub mod spmc;
+pub mod gat;
+
+#[cfg(feature = "std")]
+pub mod select;
+
+#[cfg(all(feature = "std", unix))]
+pub mod readiness;
+
+#[cfg(feature = "wal")]
+pub mod wal;
+
+#[cfg(all(feature = "mmap", unix))]
+pub mod mmap;
+
+#[cfg(feature = "test_hooks")]
+pub mod test_hooks;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
+#[allow(unused_imports)]
+#[allow(clippy::identity_op, clippy::unnecessary_cast, clippy::redundant_pattern_matching)]
 mod tests;
\ No newline at end of file
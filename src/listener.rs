@@ -0,0 +1,103 @@
+// Wakers, waiting for EventQueue publish. Only with Settings::NOTIFY.
+//
+// Publish bumps `epoch` and wakes everything registered. Listener future completes,
+// once epoch differs from one it was created with. Unlike bare waker,
+// listener removes its waker on drop - so cancelled futures leave nothing behind.
+//
+// Executor agnostic, no_std.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use alloc::vec::Vec;
+use crate::event_queue::{EventQueue, Settings};
+
+pub(crate) struct Wakers{
+    /// (listener id, waker). Id 0 - waker without listener.
+    wakers: Vec<(usize, Waker)>,
+    next_id: usize,
+    epoch: usize,
+}
+
+impl Wakers{
+    pub fn new() -> Self{
+        Self{ wakers: Vec::new(), next_id: 1, epoch: 0 }
+    }
+
+    #[inline]
+    pub fn epoch(&self) -> usize{
+        self.epoch
+    }
+
+    /// Stored until wake. Same waker stored once.
+    pub fn register(&mut self, waker: &Waker){
+        if !self.wakers.iter().any(|(_, w)| w.will_wake(waker)){
+            self.wakers.push((0, waker.clone()));
+        }
+    }
+
+    /// Start new epoch. Returns wakers to wake (outside lock).
+    pub fn take(&mut self) -> Vec<(usize, Waker)>{
+        self.epoch = self.epoch.wrapping_add(1);
+        core::mem::take(&mut self.wakers)
+    }
+
+    fn set_listener_waker(&mut self, id: &mut usize, waker: &Waker){
+        if *id != 0{
+            if let Some((_, w)) = self.wakers.iter_mut().find(|(i, _)| *i == *id){
+                if !w.will_wake(waker){
+                    *w = waker.clone();
+                }
+                return;
+            }
+        }
+        *id = self.next_id;
+        self.next_id += 1;
+        self.wakers.push((*id, waker.clone()));
+    }
+
+    fn remove_listener(&mut self, id: usize){
+        if let Some(index) = self.wakers.iter().position(|(i, _)| *i == id){
+            self.wakers.swap_remove(index);
+        }
+    }
+}
+
+/// Completes on the first publish after [EventQueue::listen].
+pub(crate) struct Listener<'a, T, S: Settings>{
+    event: &'a EventQueue<T, S>,
+    epoch: usize,
+    /// 0 - not registered.
+    id: usize,
+}
+
+impl<'a, T, S: Settings> Listener<'a, T, S>{
+    #[inline]
+    pub fn new(event: &'a EventQueue<T, S>, epoch: usize) -> Self{
+        Self{event, epoch, id: 0}
+    }
+}
+
+impl<'a, T, S: Settings> Future for Listener<'a, T, S>{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut wakers = this.event.wakers().lock();
+        if wakers.epoch() != this.epoch{
+            // Our waker already taken by wake.
+            this.id = 0;
+            return Poll::Ready(());
+        }
+        wakers.set_listener_waker(&mut this.id, cx.waker());
+        Poll::Pending
+    }
+}
+
+impl<'a, T, S: Settings> Drop for Listener<'a, T, S>{
+    fn drop(&mut self) {
+        if self.id != 0{
+            self.event.wakers().lock().remove_listener(self.id);
+        }
+    }
+}
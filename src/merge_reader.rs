@@ -0,0 +1,143 @@
+use crate::event_queue::Settings;
+use crate::event_reader::{EventReader, Iter, LendingIterator};
+use alloc::vec::Vec;
+
+/// In which order [MergeReader](crate::mpmc::MergeReader) returns items of different readers.
+/// Items of the same reader are always returned in FIFO order.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MergeOrder{
+    /// Readers drained one after another, in readers order. Cheapest.
+    ///
+    /// Until [refresh](crate::mpmc::MergeIter::refresh), iterator does not return back to
+    /// already drained readers.
+    Unordered,
+    /// Item with the smallest [Position](crate::Position) first.
+    ///
+    /// Positions of different queues are independent counters - this interleaves
+    /// queues with similar push rates, "round-robin"-like.
+    Sequence,
+    /// Item pushed earlier first. All queues must have `Settings::TIMESTAMPS`.
+    #[cfg(feature = "std")]
+    Timestamp,
+}
+
+pub struct MergeReader<T, S: Settings>{
+    readers: Vec<EventReader<T, S>>,
+    order: MergeOrder,
+}
+
+impl<T, S: Settings> MergeReader<T, S>{
+    #[inline]
+    pub fn new(readers: Vec<EventReader<T, S>>, order: MergeOrder) -> Self{
+        #[cfg(feature = "std")]
+        if order == MergeOrder::Timestamp{
+            assert!(S::TIMESTAMPS, "Settings::TIMESTAMPS is disabled.");
+        }
+        Self{readers, order}
+    }
+
+    #[inline]
+    pub fn readers(&self) -> &[EventReader<T, S>]{
+        &self.readers
+    }
+
+    #[inline]
+    pub fn into_readers(self) -> Vec<EventReader<T, S>>{
+        self.readers
+    }
+
+    /// Expired readers are skipped.
+    pub fn iter(&mut self) -> MergeIter<'_, T, S>{
+        MergeIter{
+            iters: self.readers.iter_mut().map(|reader| reader.try_iter().ok()).collect(),
+            order: self.order,
+            current: 0,
+        }
+    }
+}
+
+pub struct MergeIter<'a, T, S: Settings>{
+    // None for expired reader - this keeps indices equal to reader indices.
+    iters: Vec<Option<Iter<'a, T, S>>>,
+    order: MergeOrder,
+    // Unordered only. All before it - drained.
+    current: usize,
+}
+
+impl<'a, T, S: Settings> MergeIter<'a, T, S>{
+    /// Index of reader, which have the next item.
+    fn next_source(&mut self) -> Option<usize>{
+        match self.order{
+            MergeOrder::Unordered => {
+                while self.current < self.iters.len(){
+                    if let Some(iter) = &mut self.iters[self.current]{
+                        if iter.peek().is_some(){
+                            return Some(self.current);
+                        }
+                    }
+                    self.current += 1;
+                }
+                None
+            }
+            MergeOrder::Sequence => {
+                self.min_source(|iter| iter.peek_position())
+            }
+            #[cfg(feature = "std")]
+            MergeOrder::Timestamp => {
+                self.min_source(|iter| iter.peek_timestamp())
+            }
+        }
+    }
+
+    /// On equal keys - the first reader wins.
+    #[inline]
+    fn min_source<K: Ord>(&mut self, mut key: impl FnMut(&mut Iter<'a, T, S>) -> Option<K>) -> Option<usize>{
+        let mut min: Option<(usize, K)> = None;
+        for (index, iter) in self.iters.iter_mut().enumerate(){
+            let iter = match iter{
+                Some(iter) => iter,
+                None => continue,
+            };
+            if let Some(k) = key(iter){
+                match &min{
+                    Some((_, min_k)) if *min_k <= k => {}
+                    _ => min = Some((index, k)),
+                }
+            }
+        }
+        min.map(|(index, _)| index)
+    }
+
+    /// Same as `next`, with index of reader, item came from.
+    #[inline]
+    pub fn next_with_source(&mut self) -> Option<(usize, &T)>{
+        let source = self.next_source()?;
+        let value = self.iters[source].as_mut().unwrap().next()?;
+        Some((source, value))
+    }
+
+    /// Indices of readers, expired when iterator started. They are skipped.
+    pub fn expired_sources(&self) -> impl Iterator<Item = usize> + '_{
+        self.iters.iter().enumerate()
+            .filter(|(_, iter)| iter.is_none())
+            .map(|(index, _)| index)
+    }
+
+    /// Pick up items, pushed after iterator returned None.
+    pub fn refresh(&mut self){
+        for iter in self.iters.iter_mut().flatten(){
+            iter.refresh();
+        }
+        self.current = 0;
+    }
+}
+
+impl<'a, T, S: Settings> LendingIterator for MergeIter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        let source = self.next_source()?;
+        self.iters[source].as_mut().unwrap().next()
+    }
+}
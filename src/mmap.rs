@@ -0,0 +1,108 @@
+//! File-backed chunk memory - see [ChunkAllocator::MMAP].
+//!
+//! Each chunk is `MAP_SHARED` mapping of its own temporary file, unlinked right after creation.
+//! Kernel writes cold pages back to that file, instead of keeping them in RAM (or swap) -
+//! so long history windows can exceed RAM. File space released, when chunk deallocated
+//! (or process exits).
+//!
+//! Files created in [directory] - [std::env::temp_dir] by default. Put it on a disk
+//! with enough space, before first queue construction.
+//!
+//! Chunk content is not usable across restarts - chunk header holds in-process pointers
+//! (next chunk, owning queue) and reader counters. For durable history - see `wal` feature.
+//!
+//! ```
+//! # use rc_event_queue::mpmc::{EventQueue, EventReader, Settings};
+//! # use rc_event_queue::ChunkAllocator;
+//! # use rc_event_queue::LendingIterator;
+//! struct S{} impl Settings for S{
+//!     const MIN_CHUNK_SIZE: u32 = 1 << 16;
+//!     const MAX_CHUNK_SIZE: u32 = 1 << 16;
+//!     const CHUNK_ALLOCATOR: ChunkAllocator = ChunkAllocator::MMAP;
+//! }
+//! let event = EventQueue::<[u8; 64], S>::new();
+//! let mut reader = EventReader::new(&event);
+//! event.extend(core::iter::repeat([1; 64]).take(100_000));
+//! assert_eq!(reader.iter().next(), Some(&[1; 64]));
+//! ```
+
+use crate::ChunkAllocator;
+use core::alloc::Layout;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::fs::{self, OpenOptions};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+static DIRECTORY: RwLock<Option<PathBuf>> = RwLock::new(None);
+static FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Change directory for chunk files. Affects only chunks allocated after call.
+pub fn set_directory(dir: impl AsRef<Path>){
+    *DIRECTORY.write().unwrap() = Some(dir.as_ref().to_path_buf());
+}
+
+/// Directory for chunk files.
+pub fn directory() -> PathBuf{
+    match &*DIRECTORY.read().unwrap(){
+        Some(dir) => dir.clone(),
+        None => std::env::temp_dir(),
+    }
+}
+
+fn page_size() -> usize{
+    unsafe{ libc::sysconf(libc::_SC_PAGESIZE) as usize }
+}
+
+/// Null on any failure.
+fn map_file(size: usize) -> *mut u8{
+    let path = directory().join(format!(
+        "rc_event_queue_chunk_{}_{}",
+        std::process::id(),
+        FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let file = match OpenOptions::new().read(true).write(true).create_new(true).open(&path){
+        Ok(file) => file,
+        Err(_) => return null_mut(),
+    };
+    // Mapping outlives both name and descriptor.
+    let _ = fs::remove_file(&path);
+    if file.set_len(size as u64).is_err(){
+        return null_mut();
+    }
+
+    let ptr = unsafe{ libc::mmap(
+        null_mut(),
+        size,
+        libc::PROT_READ | libc::PROT_WRITE,
+        libc::MAP_SHARED,
+        file.as_raw_fd(),
+        0
+    ) };
+    if ptr == libc::MAP_FAILED {
+        return null_mut();
+    }
+    ptr as *mut u8
+}
+
+fn alloc(layout: Layout) -> *mut u8{
+    // mmap is page aligned.
+    if layout.size() == 0 || layout.align() > page_size(){
+        return null_mut();
+    }
+    map_file(layout.size())
+}
+
+fn dealloc(ptr: *mut u8, layout: Layout){
+    unsafe{ libc::munmap(ptr as *mut libc::c_void, layout.size()); }
+}
+
+impl ChunkAllocator{
+    /// Chunks in memory-mapped temporary files. See [mmap](crate::mmap) module.
+    ///
+    /// Each chunk takes at least one page, and one file - use big chunks.
+    ///
+    /// Requires `mmap` feature. Unix only.
+    pub const MMAP: ChunkAllocator = ChunkAllocator{ alloc, dealloc };
+}
@@ -0,0 +1,80 @@
+use core::marker::PhantomData;
+use crate::{CleanupMode, GrowthStrategy};
+use crate::event_queue::{EventQueue as BaseEventQueue, Config};
+use crate::mpmc::{BS, EventQueue, RuntimeSettings};
+
+/// [EventQueue] with settings, chosen at runtime (e.g. from config file).
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::EventQueue;
+/// let event = EventQueue::<usize, _>::builder()
+///     .min_chunk_size(16)
+///     .max_chunk_size(1024)
+///     .cleanup(CleanupMode::OnNewChunk)
+///     .build();
+/// ```
+pub struct Builder<T>{
+    config: Config,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Builder<T>{
+    #[inline]
+    pub fn new() -> Self{
+        Self{
+            config: Config::from_settings::<BS<RuntimeSettings>>(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// See [Settings::MIN_CHUNK_SIZE](crate::mpmc::Settings::MIN_CHUNK_SIZE).
+    #[inline]
+    pub fn min_chunk_size(mut self, size: u32) -> Self{
+        self.config.min_chunk_size = size;
+        self
+    }
+
+    /// See [Settings::MAX_CHUNK_SIZE](crate::mpmc::Settings::MAX_CHUNK_SIZE).
+    #[inline]
+    pub fn max_chunk_size(mut self, size: u32) -> Self{
+        self.config.max_chunk_size = size;
+        self
+    }
+
+    /// See [Settings::CLEANUP](crate::mpmc::Settings::CLEANUP).
+    #[inline]
+    pub fn cleanup(mut self, cleanup: CleanupMode) -> Self{
+        self.config.cleanup = cleanup;
+        self
+    }
+
+    /// See [Settings::GROWTH](crate::mpmc::Settings::GROWTH).
+    #[inline]
+    pub fn growth(mut self, growth: GrowthStrategy) -> Self{
+        self.config.growth = growth;
+        self
+    }
+
+    /// Panics, if min_chunk_size > max_chunk_size.
+    pub fn build(self) -> EventQueue<T, RuntimeSettings>{
+        let base = BaseEventQueue::<T, BS<RuntimeSettings>>::with_config(
+            self.config, self.config.min_chunk_size
+        );
+        EventQueue::from_base(base)
+    }
+}
+
+impl<T> EventQueue<T, RuntimeSettings>{
+    #[inline]
+    pub fn builder() -> Builder<T>{
+        Builder::new()
+    }
+}
+
+impl<T> Default for Builder<T>{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
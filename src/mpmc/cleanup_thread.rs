@@ -0,0 +1,101 @@
+use crate::event_queue::EventQueue as BaseEventQueue;
+use crate::mpmc::{BS, EventQueue, Settings};
+use std::sync::{Condvar, Mutex, Weak};
+use std::sync::Arc as StdArc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Background thread of [EventQueue::spawn_cleanup_thread]. Stops (and joins) on drop.
+pub struct CleanupThread{
+    /// `true` - stop.
+    stop: StdArc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CleanupThread{
+    /// Stop and wait for thread to finish.
+    #[inline]
+    pub fn stop(self){}
+
+    /// Thread already finished - queue destructed.
+    #[inline]
+    pub fn is_finished(&self) -> bool{
+        match &self.thread{
+            Some(thread) => thread.is_finished(),
+            None => true,
+        }
+    }
+}
+
+impl Drop for CleanupThread{
+    fn drop(&mut self) {
+        let (stop, condvar) = &*self.stop;
+        *stop.lock().unwrap() = true;
+        condvar.notify_one();
+        if let Some(thread) = self.thread.take(){
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Does not keep queue alive.
+struct WeakEventQueue<T, S: Settings>(Weak<BaseEventQueue<T, BS<S>>>);
+
+// Same as EventQueue.
+unsafe impl<T: Send + Sync, S: Settings> Send for WeakEventQueue<T, S>{}
+
+impl<T, S: Settings> EventQueue<T, S>{
+    /// Spawn thread, which calls [cleanup](Self::cleanup) every `interval`.
+    ///
+    /// With [CleanupMode::Never](crate::CleanupMode::Never) (or readers
+    /// with [ReaderOptions::cleanup](crate::ReaderOptions::cleanup) disabled) - reclamation
+    /// moves off the read/write path completely.
+    ///
+    /// Thread does not keep queue alive - it finishes, when queue destructed.
+    /// Or when returned [CleanupThread] dropped.
+    ///
+    /// Requires `std` feature.
+    ///
+    /// ```
+    /// # use rc_event_queue::prelude::*;
+    /// # use rc_event_queue::mpmc::{EventQueue, EventReader, Settings};
+    /// # use std::time::Duration;
+    /// struct S{} impl Settings for S{
+    ///     const CLEANUP: CleanupMode = CleanupMode::Never;
+    /// }
+    /// let event = EventQueue::<usize, S>::new();
+    /// let cleanup_thread = event.spawn_cleanup_thread(Duration::from_millis(10));
+    /// let mut reader = EventReader::new(&event);
+    /// event.extend(0..100);
+    /// reader.iter().for_each(|_|{});
+    /// // ... read chunks freed in background
+    /// ```
+    pub fn spawn_cleanup_thread(&self, interval: Duration) -> CleanupThread
+        where T: Send + Sync + 'static, S: 'static
+    {
+        let stop = StdArc::new((Mutex::new(false), Condvar::new()));
+        let event = WeakEventQueue::<T, S>(StdArc::downgrade(&self.0));
+        let thread = {
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name("rc_event_queue cleanup".into())
+                .spawn(move || {
+                    let event = event;
+                    let (stopped, condvar) = &*stop;
+                    let mut stopped = stopped.lock().unwrap();
+                    loop{
+                        stopped = condvar.wait_timeout(stopped, interval).unwrap().0;
+                        if *stopped {
+                            return;
+                        }
+                        match event.0.upgrade(){
+                            Some(event) => EventQueue{0: event}.cleanup(),
+                            None => return,
+                        }
+                    }
+                })
+                .expect("failed to spawn cleanup thread")
+        };
+        CleanupThread{stop, thread: Some(thread)}
+    }
+}
@@ -0,0 +1,93 @@
+use crate::event_reader::ReaderExpired;
+use crate::mpmc::{EventQueue, EventReader, Iter, Settings};
+use crate::Position;
+
+/// [EventReader] with explicit commit - for at-least-once processing.
+///
+/// Reading does not advance committed position. Each [iter](Self::iter) starts from committed
+/// position, so items read but not [commit](Self::commit)ted are delivered again.
+/// Queue's memory held from committed position.
+///
+/// Each [iter](Self::iter) forks reader, under queue lock.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::{EventQueue, CommittedReader};
+/// let event = EventQueue::<usize>::new();
+/// let mut reader = CommittedReader::new(&event);
+/// event.extend(0..3);
+///
+/// let mut sum = 0;
+/// reader.iter().for_each(|value| sum += value);
+/// // Processing failed - not committed. Read again.
+/// reader.iter().for_each(|value| sum += value);
+/// assert_eq!(sum, 2 * (0 + 1 + 2));
+///
+/// reader.commit();
+/// assert!(reader.iter().next().is_none());
+/// ```
+pub struct CommittedReader<T, S: Settings>{
+    committed: EventReader<T, S>,
+    /// Read by last iter(). Not committed.
+    pending: Option<EventReader<T, S>>,
+}
+
+impl<T, S: Settings> CommittedReader<T, S>{
+    #[inline]
+    pub fn new(event_queue: &EventQueue<T, S>) -> Self {
+        Self::from_reader(EventReader::new(event_queue))
+    }
+
+    /// Existing reader's position becomes committed position.
+    #[inline]
+    pub fn from_reader(reader: EventReader<T, S>) -> Self {
+        Self{committed: reader, pending: None}
+    }
+
+    /// Take back reader at committed position. Not committed progress lost.
+    #[inline]
+    pub fn into_reader(self) -> EventReader<T, S>{
+        self.committed
+    }
+
+    /// Iterator from committed position. Progress of previous iterators, if not committed - lost.
+    ///
+    /// Expired reader's iterator is empty.
+    #[inline]
+    pub fn iter(&mut self) -> Iter<'_, T, S>{
+        match self.committed.try_fork(){
+            Ok(pending) => self.pending.insert(pending).iter(),
+            Err(_) => {
+                self.pending = None;
+                self.committed.iter()
+            }
+        }
+    }
+
+    /// Same as [iter](Self::iter), but returns error for expired reader.
+    pub fn try_iter(&mut self) -> Result<Iter<'_, T, S>, ReaderExpired>{
+        let pending = self.committed.try_fork()?;
+        self.pending.insert(pending).try_iter()
+    }
+
+    /// Make items, read by the last [iter](Self::iter), committed - they will not be
+    /// delivered again. Releases queue's memory they occupy.
+    #[inline]
+    pub fn commit(&mut self){
+        if let Some(pending) = self.pending.take(){
+            self.committed = pending;
+        }
+    }
+
+    /// Committed position. See [EventReader::position].
+    #[inline]
+    pub fn position(&self) -> Position{
+        self.committed.position()
+    }
+
+    /// See [EventReader::is_expired].
+    #[inline]
+    pub fn is_expired(&self) -> bool{
+        self.committed.is_expired()
+    }
+}
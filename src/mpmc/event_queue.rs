@@ -1,31 +1,46 @@
 // This is canonical variant.
 
-use std::pin::Pin;
 use crate::sync::Arc;
-use crate::event_queue::{EventQueue as BaseEventQueue};
-use crate::mpmc::{BS, DefaultSettings, Settings};
+use crate::event_queue::{EventQueue as BaseEventQueue, IntoIter as BaseIntoIter};
+use crate::mpmc::{BS, DefaultSettings, Settings, WriteGuard};
+use crate::{AllocError, Position, QueueDiagnostics, Stats, TryPushError};
+use crate::utils::DebugFn;
+use core::ops::Range;
+use core::fmt;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::time::Duration;
+#[cfg(feature = "wal")]
+use crate::wal::WalSink;
+#[cfg(feature = "wal")]
+use std::io;
 
-#[repr(transparent)]
+/// Cloneable handle. All clones refer to the same queue.
+///
+/// Queue lives while there is at least one handle or [EventReader](crate::mpmc::EventReader).
+///
+/// Zero-sized `T` (like `EventQueue<()>` used as notification channel) stored in one
+/// single chunk, regardless of chunk size settings - queue is just a counter then.
+/// Except with [Settings::TIMESTAMPS].
 pub struct EventQueue<T, S: Settings = DefaultSettings>(
-    pub(crate) BaseEventQueue<T, BS<S>>
+    pub(crate) Arc<BaseEventQueue<T, BS<S>>>
 );
 
 impl<T, S: Settings> EventQueue<T, S>{
     #[inline]
-    pub fn new() -> Pin<Arc<Self>> {
+    pub fn new() -> Self {
         Self::with_capacity(S::MIN_CHUNK_SIZE)
     }
 
     // Hide for a while.
     #[inline]
-    fn with_capacity(new_capacity: u32) -> Pin<Arc<Self>> {
-        let base = BaseEventQueue::<T, BS<S>>::with_capacity(new_capacity);
-        unsafe {
-            let base_ptr = Arc::into_raw(Pin::into_inner_unchecked(base));
-            Pin::new_unchecked(
-                Arc::from_raw(base_ptr as *const Self)
-            )
-        }
+    fn with_capacity(new_capacity: u32) -> Self {
+        Self::from_base(BaseEventQueue::<T, BS<S>>::with_capacity(new_capacity))
+    }
+
+    #[inline]
+    pub(crate) fn from_base(base: Arc<BaseEventQueue<T, BS<S>>>) -> Self {
+        Self{0: base}
     }
 
     #[inline]
@@ -34,6 +49,31 @@ impl<T, S: Settings> EventQueue<T, S>{
         self.0.push(&mut list, value);
     }
 
+    /// Same as `push`, but returns value back, if new chunk could not be allocated -
+    /// instead of aborting on OOM. Or, with [Settings::PREALLOCATED_CHUNKS] > 0, if there
+    /// is no free chunk.
+    ///
+    /// Value dropped due to [Settings::DROP_WITHOUT_READERS] - is `Ok`.
+    #[inline]
+    pub fn try_push(&self, value: T) -> Result<(), AllocError<T>>{
+        let mut list = self.0.list.lock();
+        self.0.try_push(&mut list, value)
+    }
+
+    /// Same as [try_push](Self::try_push), but never waits for the queue lock.
+    /// Returns value back with [TryPushError::WouldBlock], if queue is locked by other writer.
+    ///
+    /// For soft-real-time producers, which prefer dropping an event over blocking.
+    #[inline]
+    pub fn try_push_nonblocking(&self, value: T) -> Result<(), TryPushError<T>>{
+        let mut list = match self.0.list.try_lock(){
+            Some(list) => list,
+            None => return Err(TryPushError::WouldBlock(value)),
+        };
+        self.0.try_push(&mut list, value)?;
+        Ok(())
+    }
+
     #[inline]
     pub fn extend<I>(&self, iter: I)
         where I: IntoIterator<Item = T>
@@ -42,8 +82,89 @@ impl<T, S: Settings> EventQueue<T, S>{
         self.0.extend(&mut list, iter);
     }
 
+    /// Push `n` clones of `value`. Chunk slots filled in bulk, with one publish per chunk.
+    ///
+    /// For heartbeat/padding events.
+    #[inline]
+    pub fn push_repeated(&self, value: T, n: usize)
+        where T: Clone
+    {
+        let mut list = self.0.list.lock();
+        self.0.extend(&mut list, core::iter::repeat_n(value, n));
+    }
+
+    /// Same as `extend`, but copies values into chunk storage with memcpy, instead of
+    /// one by one. For byte-sized and POD values.
+    #[inline]
+    pub fn extend_from_slice(&self, values: &[T])
+        where T: Copy
+    {
+        let mut list = self.0.list.lock();
+        self.0.extend_from_slice(&mut list, values);
+    }
+
+    /// Same as `extend`, but stops on first value, which could not be pushed. See [try_push](Self::try_push).
+    ///
+    /// Rest of the `iter` is not consumed - pass `iter.by_ref()` to keep it.
+    #[inline]
+    pub fn try_extend<I>(&self, iter: I) -> Result<(), AllocError<T>>
+        where I: IntoIterator<Item = T>
+    {
+        let mut list = self.0.list.lock();
+        self.0.try_extend(&mut list, iter)
+    }
+
+    /// Same as `push`, but returns [Position] of pushed value - for flow control, audit logs,
+    /// etc. Compare with [EventReader::position](crate::mpmc::EventReader::position) to know whether reader got it.
+    ///
+    /// None, if value was dropped - see [Settings::DROP_WITHOUT_READERS],
+    /// [Settings::PREALLOCATED_CHUNKS].
+    #[inline]
+    pub fn push_with_position(&self, value: T) -> Option<Position>{
+        let mut list = self.0.list.lock();
+        self.0.push_with_position(&mut list, value)
+    }
+
+    /// Same as `extend`, but returns [Position]s of pushed values. Empty range - if nothing was pushed.
+    #[inline]
+    pub fn extend_with_position<I>(&self, iter: I) -> Range<Position>
+        where I: IntoIterator<Item = T>
+    {
+        let mut list = self.0.list.lock();
+        self.0.extend_with_position(&mut list, iter)
+    }
+
+    /// [Position], next pushed value will get.
+    #[inline]
+    pub fn end_position(&self) -> Position{
+        let list = self.0.list.lock();
+        self.0.end_position(&list)
+    }
+
+    /// Lock queue for many pushes. Values published once, on guard drop.
+    ///
+    /// Saves lock acquisition and publish per push, when producing many small values at once.
+    #[inline]
+    pub fn write_batch(&self) -> WriteGuard<'_, T, S>{
+        WriteGuard::new(self)
+    }
+
+    /// Make all pushed values visible to readers.
+    ///
+    /// Needed only with [Settings::PUBLISH_BATCH] > 1.
+    #[inline]
+    pub fn flush(&self){
+        let mut list = self.0.list.lock();
+        self.0.flush(&mut list);
+    }
+
     /// Free all completely read chunks.
     ///
+    /// Chunks are freed in order. Chunks after the one occupied by the slowest reader
+    /// are still to be read by it, so they stay alive - even if all other readers are done with
+    /// them. To bound memory held by slow readers, use [cleanup_force](Self::cleanup_force)
+    /// or readers with TTL (`EventReader::with_ttl`).
+    ///
     /// Called automatically with [Settings::CLEANUP] != Never.
     #[inline]
     pub fn cleanup(&self){
@@ -65,8 +186,22 @@ impl<T, S: Settings> EventQueue<T, S>{
         self.0.clear(&mut list);
     }
 
+    /// Same as [clear](Self::clear), but all values, which can not be observed by readers, dropped
+    /// immediately. Including the ones in the last chunk, which `clear` keeps for writing.
+    ///
+    /// Values in chunks **occupied** by readers still dropped lazily - reader may be
+    /// in the middle of the read session.
+    ///
+    /// Use this for values holding large buffers, or RAII handles. Allocates new chunk.
+    #[inline]
+    pub fn clear_now(&self){
+        let mut list = self.0.list.lock();
+        self.0.clear_now(&mut list);
+    }
+
     /// "Lazily move" all readers positions to the `len`-th element from the end of the queue.
     /// From readers perspective, equivalent to conventional `truncate` from the other side.
+    /// `len` counted in items - queue retains last `len` items.
     ///
     /// Immediately free chunks, **unoccupied** by readers.
     ///
@@ -78,6 +213,50 @@ impl<T, S: Settings> EventQueue<T, S>{
         self.0.truncate_front(&mut list, len);
     }
 
+    /// Free all chunks, except last `keep_chunks`, even if they are not read yet.
+    /// (At least one - the last chunk - always kept)
+    ///
+    /// Use this, when slow or hung reader holds too much memory. Readers behind
+    /// skip to the beginning of the kept chunks on the next read (like with [clear](Self::clear)).
+    ///
+    /// Chunks **occupied** by readers can not be freed immediately - each lagging reader keeps
+    /// its current chunk alive, until its next read.
+    #[inline]
+    pub fn cleanup_force(&self, keep_chunks: usize){
+        let mut list = self.0.list.lock();
+        self.0.cleanup_force(&mut list, keep_chunks);
+    }
+
+    /// "Lazily move" all readers positions past items, older than `age`. Same as
+    /// [truncate_front](Self::truncate_front), but bounded by time.
+    ///
+    /// Chunk-granular: chunk considered as old, as its newest item. So some older
+    /// items may be kept. Last chunk always kept.
+    ///
+    /// See also [Settings::RETENTION].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn truncate_older_than(&self, age: Duration){
+        let mut list = self.0.list.lock();
+        self.0.truncate_older_than(&mut list, age);
+    }
+
+    /// Log compaction - keep only the latest value per `key`, among retained values
+    /// (see [len](Self::len)). Like Kafka compacted topic.
+    ///
+    /// Kept values cloned into fresh chunk, in the same order, with new [Position]s.
+    /// Then all readers "lazily moved" to them, as with [clear](Self::clear) - including
+    /// up-to-date ones, so each reader gets compacted state as new values.
+    /// Old chunks freed, as with [truncate_front](Self::truncate_front).
+    ///
+    /// Returns number of values compacted away. Nothing changed, if 0. O(n log n), takes lock.
+    pub fn compact_by_key<K, F>(&self, key: F) -> usize
+        where T: Clone, K: Ord, F: FnMut(&T) -> K
+    {
+        let mut list = self.0.list.lock();
+        self.0.compact_by_key(&mut list, key)
+    }
+
     /// Adds chunk with `new_capacity` capacity. All next writes will be on new chunk.
     ///
     /// If you configured [Settings::MAX_CHUNK_SIZE] to high value, use this, in conjunction
@@ -85,26 +264,327 @@ impl<T, S: Settings> EventQueue<T, S>{
     /// memory pressure ASAP.
     ///
     /// Total capacity will be temporarily increased, until readers get to the new chunk.
+    ///
+    /// `new_capacity` ignored for zero-sized `T`.
     #[inline]
     pub fn change_chunk_capacity(&self, new_capacity: u32){
         let mut list = self.0.list.lock();
         self.0.change_chunk_capacity(&mut list, new_capacity);
     }
 
-    /// Returns total chunks capacity.
+    /// Same as [change_chunk_capacity](Self::change_chunk_capacity) with current [len](Self::len)
+    /// (clamped to [Settings::MIN_CHUNK_SIZE]..=[Settings::MAX_CHUNK_SIZE]).
+    ///
+    /// Use after traffic spike, to shrink queue back. Oversized chunks freed on cleanup,
+    /// as readers pass them.
+    #[inline]
+    pub fn resize_to_fit(&self){
+        let mut list = self.0.list.lock();
+        self.0.resize_to_fit(&mut list);
+    }
+
+    /// Returns number of items retained in queue - from the position after last
+    /// [clear](Self::clear)/[truncate_front](Self::truncate_front) to the end of the queue.
+    ///
+    /// Approximate - items, already read by all readers, counted until cleanup.
+    /// Not yet published items counted too. With concurrent writes or
+    /// [clear](Self::clear) - may be off in either direction.
+    ///
+    /// Lock-free, O(1) - reads queue's atomic counters.
+    #[inline]
+    pub fn len(&self) -> usize{
+        self.0.len_lock_free()
+    }
+
+    /// Same as `len() == 0`. Lock-free, O(1).
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+
+    /// Returns total chunks capacity, in items. Chunk kept for reuse not included.
+    ///
+    /// See also [bytes_allocated](Self::bytes_allocated).
     #[inline]
     pub fn total_capacity(&self) -> usize{
+        let list = self.0.list.lock();
+        self.0.total_capacity(&list)
+    }
+
+    /// Chunks in queue. Chunks kept for reuse not included.
+    ///
+    /// Wait-free - does not take lock, and does not perturb writers. For frequent metrics scraping.
+    /// Chunks freed/added concurrently may be not seen yet.
+    #[inline]
+    pub fn chunks_count(&self) -> usize{
+        self.0.chunks_count()
+    }
+
+    /// Returns bytes allocated by queue's chunks (including chunks kept for reuse,
+    /// see [Settings::RECYCLE_POOL_SIZE]). Queue and readers own size not included.
+    ///
+    /// O(1).
+    #[inline]
+    pub fn bytes_allocated(&self) -> usize{
+        let list = self.0.list.lock();
+        self.0.bytes_allocated(&list)
+    }
+
+    /// Returns number of values dropped by `push`/`extend`, due to absence of readers,
+    /// or due to exhausted [Settings::PREALLOCATED_CHUNKS].
+    ///
+    /// Always 0, if [Settings::DROP_WITHOUT_READERS] is false and `PREALLOCATED_CHUNKS` is 0.
+    #[inline]
+    pub fn dropped_count(&self) -> usize{
+        let list = self.0.list.lock();
+        self.0.dropped_count(&list)
+    }
+
+    /// Number of subscribed readers. Expired readers not counted.
+    #[inline]
+    pub fn readers_count(&self) -> usize{
+        let list = self.0.list.lock();
+        self.0.readers_count(&list)
+    }
+
+    /// Write single-line diagnostic:
+    /// `chunks=_ capacity=_ events=_ readers=_ max_lag<=_`.
+    ///
+    /// `events` - values retained in queue. `max_lag` - upper bound of unread values count,
+    /// of the slowest reader (with chunk granularity).
+    ///
+    /// Does not allocate - can be called from panic handler. But takes lock.
+    #[inline]
+    pub fn write_stats(&self, w: &mut impl core::fmt::Write) -> core::fmt::Result{
+        let list = self.0.list.lock();
+        self.0.write_stats(&list, w)
+    }
+
+    /// Export queue state to [metrics](https://docs.rs/metrics) facade, labeled `queue = name`:
+    ///
+    /// * `rc_event_queue_items_pushed` - counter, [Stats::pushed].
+    /// * `rc_event_queue_cleanup_count` - counter, [Stats::cleanups].
+    /// * `rc_event_queue_reclaimed_bytes` - counter, [Stats::bytes_freed].
+    /// * `rc_event_queue_chunks_live` - gauge.
+    /// * `rc_event_queue_readers` - gauge.
+    /// * `rc_event_queue_reader_lag`, `rc_event_queue_reader_pinned_bytes` - gauges, for each
+    ///   [named](crate::mpmc::EventReader::new_named) reader, labeled `reader = reader's name` too.
+    ///   See [ReaderDiagnostics](crate::ReaderDiagnostics).
+    ///
+    /// Counters are [Stats] values - require [Settings::STATS].
+    ///
+    /// Nothing recorded on push - call it periodically, e.g. from your metrics reporting loop.
+    /// Takes lock.
+    ///
+    /// Requires `metrics` feature.
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn record_metrics(&self, name: &str){
+        let list = self.0.list.lock();
+        self.0.record_metrics(&list, name);
+    }
+
+    /// Clones of all retained events (see [len](Self::len)), oldest first.
+    ///
+    /// Consistent view - taken in one pass, under lock. Queue and its readers unaffected.
+    /// For debugging, and for handing a bounded copy to tooling.
+    #[inline]
+    pub fn snapshot_vec(&self) -> Vec<T>
+        where T: Clone
+    {
+        let list = self.0.list.lock();
+        self.0.snapshot_vec(&list)
+    }
+
+    /// Serialize retained events (see [len](Self::len)), and [Position] of the first one.
+    /// With any serde format.
+    ///
+    /// Restore with [snapshot_deserialize](Self::snapshot_deserialize). For state dumps
+    /// and crash reports. Holds lock while serializing.
+    ///
+    /// Requires `serde` feature.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn snapshot_serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+        where T: serde::Serialize, Ser: serde::Serializer
+    {
+        let list = self.0.list.lock();
+        self.0.snapshot_serialize(&list, serializer)
+    }
+
+    /// Construct queue with events from [snapshot_serialize](Self::snapshot_serialize).
+    ///
+    /// Events keep their [Position]s, and next pushed value continues them.
+    /// New readers start from the end, as usual - use [EventReader::rewind](crate::mpmc::EventReader::rewind)
+    /// to read restored events.
+    ///
+    /// Requires `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn snapshot_deserialize<'de, D>(deserializer: D) -> Result<Self, D::Error>
+        where T: serde::Deserialize<'de>, D: serde::Deserializer<'de>
+    {
+        let this = Self::new();
+        {
+            let mut list = this.0.list.lock();
+            this.0.snapshot_deserialize(&mut list, deserializer)?;
+        }
+        Ok(this)
+    }
+
+    /// Attach write-ahead log sink - all following pushed values will be appended to it.
+    /// `None` - detach. Returns previous sink. See [wal](crate::wal) module.
+    ///
+    /// Sink starts segment at [end_position](Self::end_position) immediately, and then on each
+    /// new chunk. On sink error, it is detached - see [take_wal_error](Self::take_wal_error).
+    ///
+    /// Requires `wal` feature.
+    #[cfg(feature = "wal")]
+    #[inline]
+    pub fn set_wal_sink(&self, sink: Option<Box<dyn WalSink<T>>>) -> Option<Box<dyn WalSink<T>>>{
+        let mut list = self.0.list.lock();
+        self.0.set_wal_sink(&mut list, sink)
+    }
+
+    /// [WalSink::sync] attached sink. Takes lock.
+    ///
+    /// Requires `wal` feature.
+    #[cfg(feature = "wal")]
+    #[inline]
+    pub fn sync_wal(&self) -> io::Result<()>{
+        let mut list = self.0.list.lock();
+        self.0.sync_wal(&mut list)
+    }
+
+    /// Error, which detached write-ahead log sink. None, if there was no error since last call.
+    ///
+    /// Requires `wal` feature.
+    #[cfg(feature = "wal")]
+    #[inline]
+    pub fn take_wal_error(&self) -> Option<io::Error>{
+        let mut list = self.0.list.lock();
+        self.0.take_wal_error(&mut list)
+    }
+
+    /// Queue structure snapshot: chunks, readers count, start position, free chunks.
+    ///
+    /// Allocates. Takes lock, O(chunks count).
+    #[inline]
+    pub fn diagnostics(&self) -> QueueDiagnostics{
+        let list = self.0.list.lock();
+        self.0.diagnostics(&list)
+    }
+
+    /// Chunk list, for `{:?}`. For each chunk: id, capacity, len, epoch, `readers_entered`
+    /// and `read_completely_times`. Then chunks kept for reuse, marked `recycled`.
+    ///
+    /// Chunk can be freed, only when all chunks before it are freed, and
+    /// `read_completely_times` reached `readers_entered` - so the first chunk with
+    /// lower `read_completely_times` shows, what holds memory.
+    ///
+    /// Takes lock, while formatted. [EventQueue] `Debug` output includes it.
+    ///
+    /// ```
+    /// # use rc_event_queue::mpmc::{EventQueue, EventReader};
+    /// let event = EventQueue::<usize>::new();
+    /// let _reader = EventReader::new(&event);
+    /// event.push(1);
+    /// assert_eq!(
+    ///     format!("{:?}", event.fmt_chunks()),
+    ///     "[Chunk { id: 0, capacity: 4, len: 1, epoch: 0, readers_entered: 1, read_completely_times: 0, recycled: false }]"
+    /// );
+    /// ```
+    #[inline]
+    pub fn fmt_chunks(&self) -> impl fmt::Debug + '_{
+        DebugFn(move |f: &mut fmt::Formatter<'_>| {
+            let list = self.0.list.lock();
+            self.0.fmt_chunks(&list, f)
+        })
+    }
+
+    /// Returns queue statistics. All zeros, if [Settings::STATS] is false.
+    ///
+    /// Lock-free.
+    #[inline]
+    pub fn stats(&self) -> Stats{
+        self.0.stats()
+    }
+
+    /// Allocate ahead chunks for at least `additional` next items. So known burst
+    /// will not allocate in the middle of `push`es.
+    ///
+    /// Chunk sizes follows [Settings::GROWTH]. Reserved chunks are not included in
+    /// [total_capacity](Self::total_capacity), but included in [bytes_allocated](Self::bytes_allocated).
+    ///
+    /// Cleanup, and [Settings::RETENTION] still happens on chunk switch.
+    #[inline]
+    pub fn reserve(&self, additional: usize){
         let mut list = self.0.list.lock();
-        self.0.total_capacity(&mut list)
+        self.0.reserve(&mut list, additional);
     }
 
     /// Returns last/active chunk capacity
     #[inline]
     pub fn chunk_capacity(&self) -> usize{
-        let mut list = self.0.list.lock();
-        self.0.chunk_capacity(&mut list)
+        let list = self.0.list.lock();
+        self.0.chunk_capacity(&list)
+    }
+
+    /// Owning iterator over retained items (see [len](Self::len)), including not
+    /// [flush](Self::flush)ed ones. Chunks freed as iterator passes them.
+    ///
+    /// Only for the last handle, without readers and [cleanup thread](Self::spawn_cleanup_thread) -
+    /// otherwise queue returned back. For tests, and flushing queue at shutdown.
+    ///
+    /// ```
+    /// # use rc_event_queue::mpmc::{EventQueue, EventReader};
+    /// let event = EventQueue::<String>::new();
+    /// let reader = EventReader::new(&event);
+    /// event.push("a".to_string());
+    /// event.push("b".to_string());
+    ///
+    /// let event = event.try_into_iter().err().unwrap();
+    /// drop(reader);
+    /// let values: Vec<String> = event.try_into_iter().ok().unwrap().collect();
+    /// assert_eq!(values, ["a", "b"]);
+    /// ```
+    pub fn try_into_iter(mut self) -> Result<IntoIter<T, S>, Self>{
+        // Weak handles (cleanup thread) count too - they can upgrade at any moment.
+        if Arc::get_mut(&mut self.0).is_none() {
+            return Err(self);
+        }
+        Ok(IntoIter{0: BaseIntoIter::new(self.0)})
+    }
+}
+
+/// Owning iterator of [EventQueue::try_into_iter].
+pub struct IntoIter<T, S: Settings>(BaseIntoIter<T, BS<S>>);
+impl<T, S: Settings> Iterator for IntoIter<T, S>{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.0.next()
+    }
+}
+
+unsafe impl<T: Send, S: Settings> Send for IntoIter<T, S>{}
+
+impl<T, S: Settings> Clone for EventQueue<T, S>{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self{0: self.0.clone()}
+    }
+}
+
+/// Takes lock. See [fmt_chunks](EventQueue::fmt_chunks).
+impl<T, S: Settings> fmt::Debug for EventQueue<T, S>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = self.0.list.lock();
+        self.0.fmt_debug(&list, f)
     }
 }
 
-unsafe impl<T, S: Settings> Send for EventQueue<T, S>{}
-unsafe impl<T, S: Settings> Sync for EventQueue<T, S>{}
\ No newline at end of file
+// Values are pushed and dropped from any thread (Send), and readers
+// share `&T` across threads (Sync). With `!Send`/`!Sync` T - queue is thread-local.
+unsafe impl<T: Send + Sync, S: Settings> Send for EventQueue<T, S>{}
+unsafe impl<T: Send + Sync, S: Settings> Sync for EventQueue<T, S>{}
\ No newline at end of file
@@ -1,14 +1,81 @@
 // new-type EventReader, mostly to hide `BS`
 
-use crate::event_reader::{EventReader as BaseEventReader, LendingIterator};
+use crate::event_reader::{EventReader as BaseEventReader, LendingIterator, ReaderExpired, ReaderOptions};
+use crate::event_queue::EventQueue as BaseEventQueue;
 use crate::event_reader::Iter as BaseIter;
+use crate::event_reader::Drain as BaseDrain;
+use crate::event_reader::PeekIter as BasePeekIter;
+use crate::event_reader::Latest as BaseLatest;
+use alloc::vec::Vec;
 use crate::mpmc::{BS, EventQueue, Settings};
+use crate::Position;
+use core::ops::Deref;
+use core::task::{Context, Poll, Waker};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
-pub struct EventReader<T, S: Settings>(BaseEventReader<T, BS<S>>);
+pub struct EventReader<T, S: Settings>(pub(crate) BaseEventReader<T, BS<S>>);
 impl<T, S: Settings> EventReader<T, S>{
     #[inline]
     pub fn new(event_queue: &EventQueue<T, S>) -> Self {
-        Self{0: event_queue.0.subscribe(&mut event_queue.0.list.lock())}
+        Self{0: BaseEventQueue::subscribe(&event_queue.0, &mut event_queue.0.list.lock())}
+    }
+
+    /// Reader, which will be automatically unsubscribed, if not read for `ttl`.
+    ///
+    /// Expiration checked on cleanup. Expired reader does not hold queue's memory anymore,
+    /// and all read attempts fail with [ReaderExpired]. Use [try_iter](Self::try_iter) with it.
+    ///
+    /// This prevents leaked reader (e.g. in abandoned task) from holding the whole queue forever.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn with_ttl(event_queue: &EventQueue<T, S>, ttl: Duration) -> Self {
+        Self{0: BaseEventQueue::subscribe_with_ttl(&event_queue.0, &mut event_queue.0.list.lock(), ttl)}
+    }
+
+    /// Reader with name. Named reader reported individually in
+    /// [diagnostics](crate::mpmc::EventQueue::diagnostics) (see [ReaderDiagnostics](crate::ReaderDiagnostics)),
+    /// [write_stats](crate::mpmc::EventQueue::write_stats) and
+    /// `record_metrics` - to find out which consumer holds queue's memory.
+    ///
+    /// Otherwise - same as [new](Self::new).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new_named(event_queue: &EventQueue<T, S>, name: impl Into<String>) -> Self {
+        Self{0: BaseEventQueue::subscribe_named(&event_queue.0, &mut event_queue.0.list.lock(), name.into())}
+    }
+
+    /// Reader with per-reader settings - cleanup on read, name, TTL. See [ReaderOptions].
+    ///
+    /// ```
+    /// # use rc_event_queue::prelude::*;
+    /// # use rc_event_queue::mpmc::{EventQueue, EventReader};
+    /// # use rc_event_queue::ReaderOptions;
+    /// let event = EventQueue::<usize>::new();
+    /// // Never locks queue for cleanup on read.
+    /// let mut realtime = EventReader::with_options(&event, ReaderOptions{ cleanup: false, ..Default::default() });
+    /// // Frees chunks read by both.
+    /// let mut housekeeper = EventReader::new(&event);
+    /// # event.extend(0..3);
+    /// # realtime.iter().for_each(|_|{});
+    /// # housekeeper.iter().for_each(|_|{});
+    /// ```
+    #[inline]
+    pub fn with_options(event_queue: &EventQueue<T, S>, options: ReaderOptions) -> Self {
+        Self{0: BaseEventQueue::subscribe_with_options(&event_queue.0, &mut event_queue.0.list.lock(), options)}
+    }
+
+    /// Subscribe `count` readers at once, under single lock.
+    ///
+    /// All readers start from the same position. Prefer this over calling
+    /// [new](Self::new) in a loop, when many readers subscribe simultaneously
+    /// (e.g. fan-out at startup).
+    #[inline]
+    pub fn new_batch(event_queue: &EventQueue<T, S>, count: u32) -> Vec<Self> {
+        BaseEventQueue::subscribe_batch(&event_queue.0, &mut event_queue.0.list.lock(), count)
+            .into_iter()
+            .map(|reader| Self{0: reader})
+            .collect()
     }
 
     /// Move cursor to the new position, if necessary.
@@ -29,10 +96,299 @@ impl<T, S: Settings> EventReader<T, S>{
     /// Iterator items references should not outlive iterator.
     ///
     /// Read counters of affected chunks updated in `Iter::drop`.
+    ///
+    /// [Expired](Self::is_expired) reader's iterator is empty - use [try_iter](Self::try_iter)
+    /// to tell it apart.
     #[inline]
-    pub fn iter(&mut self) -> Iter<T, S>{
+    pub fn iter(&mut self) -> Iter<'_, T, S>{
         Iter{ 0: self.0.iter() }
     }
+
+    /// Same as [iter](Self::iter), but returns error for expired reader.
+    #[inline]
+    pub fn try_iter(&mut self) -> Result<Iter<'_, T, S>, ReaderExpired>{
+        Ok(Iter{ 0: self.0.try_iter()? })
+    }
+
+    /// Iterator over available items, which does not advance reader - nothing is marked as read.
+    /// Inspect pending work (e.g. to size a batch), before actually consuming it with [iter](Self::iter).
+    ///
+    /// Same as [iter](Self::iter) with [rollback](Iter::rollback) on drop.
+    ///
+    /// ```
+    /// # use rc_event_queue::prelude::*;
+    /// # use rc_event_queue::mpmc::{EventQueue, EventReader};
+    /// let event = EventQueue::<usize>::new();
+    /// let mut reader = EventReader::new(&event);
+    /// event.extend(0..3);
+    ///
+    /// let mut pending = 0;
+    /// let mut peek_iter = reader.peek_iter();
+    /// while peek_iter.next().is_some() { pending += 1; }
+    /// drop(peek_iter);
+    /// assert_eq!(pending, 3);
+    /// assert_eq!(reader.iter().next(), Some(&0));
+    /// ```
+    ///
+    /// [Expired](Self::is_expired) reader's iterator is empty.
+    #[inline]
+    pub fn peek_iter(&mut self) -> PeekIter<'_, T, S>{
+        PeekIter{ 0: self.0.peek_iter() }
+    }
+
+    /// Same as [peek_iter](Self::peek_iter), but returns error for expired reader.
+    #[inline]
+    pub fn try_peek_iter(&mut self) -> Result<PeekIter<'_, T, S>, ReaderExpired>{
+        Ok(PeekIter{ 0: self.0.try_peek_iter()? })
+    }
+
+    /// Position of the next item to read.
+    ///
+    /// If queue was [cleared](crate::mpmc::EventQueue::clear) - reader will jump forward,
+    /// only on next read.
+    ///
+    /// Panics, if reader [expired](Self::is_expired).
+    #[inline]
+    pub fn position(&self) -> Position{
+        self.0.position()
+    }
+
+    /// Count of items, this reader skipped without reading, since last call. Resets it.
+    ///
+    /// Reader skips items, when queue was [cleared](crate::mpmc::EventQueue::clear) or
+    /// [truncated](crate::mpmc::EventQueue::truncate_front) ahead of it, and items expired by
+    /// `Settings::ITEM_TTL`. For consumers, which must know about any loss (e.g. audit) -
+    /// check after each read.
+    ///
+    /// Expired reader reports nothing new.
+    #[inline]
+    pub fn take_missed_count(&mut self) -> u64{
+        self.0.take_missed_count()
+    }
+
+    /// New reader at the same position. It will read the same unread items, as this one.
+    /// Inherits TTL, if any.
+    ///
+    /// Panics, if reader [expired](Self::is_expired).
+    #[inline]
+    pub fn fork(&self) -> Self{
+        Self{0: self.0.fork()}
+    }
+
+    /// Same as [fork](Self::fork), but returns error for expired reader.
+    #[inline]
+    pub fn try_fork(&self) -> Result<Self, ReaderExpired>{
+        Ok(Self{0: self.0.try_fork()?})
+    }
+
+    /// Jump to the newest item, and return it. All items before it - considered read.
+    /// Next [iter](Self::iter) will start after it.
+    ///
+    /// Returns None, if there is no new items. Useful for consumers, interested only in
+    /// the most recent value (GUI, state sync).
+    ///
+    /// [Latest] keeps read session open - reader can not [expire](Self::is_expired) while it alive.
+    #[inline]
+    pub fn latest(&mut self) -> Option<Latest<'_, T, S>>{
+        Some(Latest{ 0: self.0.latest()? })
+    }
+
+    /// Move to the end of the queue, discarding all unread items. Returns number of skipped items.
+    ///
+    /// Passed chunks marked as read, as after regular read - and cleaned up according to settings.
+    /// Useful for consumers, which decide to drop backlog after a stall.
+    ///
+    /// [Expired](Self::is_expired) reader skips nothing.
+    #[inline]
+    pub fn skip_to_end(&mut self) -> usize{
+        self.0.skip_to_end()
+    }
+
+    /// Move reader back by up to `n` items, to re-read them. Returns number of items actually rewound.
+    ///
+    /// Reader can go back only within retained history: down to the oldest chunk not freed yet,
+    /// and not before [clear](crate::mpmc::EventQueue::clear)/[truncate_front](crate::mpmc::EventQueue::truncate_front) position.
+    /// With [CleanupMode::OnChunkRead](crate::CleanupMode::OnChunkRead) read chunks freed
+    /// as soon as possible - so use [CleanupMode::OnNewChunk](crate::CleanupMode::OnNewChunk) or
+    /// [CleanupMode::Never](crate::CleanupMode::Never), to keep history.
+    ///
+    /// Useful for re-processing last events after downstream failure.
+    ///
+    /// There is no spmc version, since spmc writer frees chunks without lock.
+    ///
+    /// [Expired](Self::is_expired) reader can not rewind - returns 0.
+    #[inline]
+    pub fn rewind(&mut self, n: usize) -> usize{
+        self.0.rewind(n)
+    }
+
+    /// Take ownership of items, available at call moment - for the sole reader of the queue.
+    /// Returns `None`, if queue has other readers (forks, [weak readers](crate::mpmc::WeakReader)
+    /// in the middle of iteration, etc.), or reader [expired](Self::is_expired).
+    ///
+    /// Drained items removed from queue, as with [truncate_front](crate::mpmc::EventQueue::truncate_front):
+    /// they are not counted in [len](crate::mpmc::EventQueue::len), and readers subscribed later
+    /// can not [rewind](Self::rewind) to them. Items, not taken from [Drain] - stay in queue.
+    /// `Settings::ITEM_TTL` is not applied - expired items returned too.
+    ///
+    /// Takes lock at start, and on drop of partially consumed [Drain].
+    ///
+    /// There is no spmc version, since spmc writer frees chunks without lock.
+    ///
+    /// ```
+    /// # use rc_event_queue::mpmc::{EventQueue, EventReader};
+    /// let event = EventQueue::<String>::new();
+    /// let mut reader = EventReader::new(&event);
+    /// event.push("a".to_string());
+    /// event.push("b".to_string());
+    ///
+    /// let values: Vec<String> = reader.drain().unwrap().collect();
+    /// assert_eq!(values, ["a", "b"]);
+    /// assert_eq!(event.len(), 0);
+    ///
+    /// let _other = EventReader::new(&event);
+    /// assert!(reader.drain().is_none());
+    /// ```
+    #[inline]
+    pub fn drain(&mut self) -> Option<Drain<'_, T, S>>{
+        Some(Drain{0: self.0.drain()?})
+    }
+
+    /// Number of unread items, between reader's position and the end of the queue.
+    ///
+    /// Walks chunks after reader's position, under short lock, which does not block writers.
+    /// Useful for detecting slow consumers.
+    #[inline]
+    pub fn lag(&self) -> usize{
+        self.0.lag()
+    }
+
+    /// Returns true, if there are unread items. Does not consume anything.
+    ///
+    /// Cheaper than constructing [Iter], in poll loops.
+    #[inline]
+    pub fn has_new(&self) -> bool{
+        self.0.has_new()
+    }
+
+    /// Cheap, lock-free estimation of [lag](Self::lag). O(1).
+    ///
+    /// Exact for reader on the last chunk. Otherwise - counts chunks after reader's one
+    /// as full, and of the same capacity.
+    #[inline]
+    pub fn unread_estimate(&self) -> usize{
+        self.0.unread_estimate()
+    }
+
+    /// Was reader expired by queue? Only readers [with TTL](Self::with_ttl) can expire.
+    #[inline]
+    pub fn is_expired(&self) -> bool{
+        self.0.is_expired()
+    }
+
+    /// Name, given in [new_named](Self::new_named).
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn name(&self) -> Option<&str>{
+        self.0.name()
+    }
+
+    /// Wake `waker` once, on next publish. For custom executors/wait loops -
+    /// see [select](crate::select) for ready-made ones.
+    ///
+    /// Panics without [Settings::NOTIFY].
+    #[inline]
+    pub fn register_waker(&self, waker: &Waker){
+        self.0.register_waker(waker)
+    }
+
+    /// Read one item, without waiting. Returns its clone.
+    ///
+    /// Returns `Ok(None)`, if there is nothing to read.
+    #[inline]
+    pub fn try_recv(&mut self) -> Result<Option<T>, ReaderExpired>
+        where T: Clone
+    {
+        self.0.try_recv()
+    }
+
+    /// Read one item, or register `cx` waker to be woken on next publish.
+    /// For manually implemented futures.
+    ///
+    /// Panics without [Settings::NOTIFY].
+    #[inline]
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, ReaderExpired>>
+        where T: Clone
+    {
+        self.0.poll_recv(cx)
+    }
+
+    /// Wait for the next item, and return its clone.
+    ///
+    /// Cancel safe - nothing read, if dropped before completion.
+    ///
+    /// Executor agnostic. With `tokio` feature - waits on `tokio::sync::Notify`.
+    ///
+    /// Panics without [Settings::NOTIFY].
+    ///
+    /// ```
+    /// # use rc_event_queue::mpmc::{EventQueue, EventReader, Settings};
+    /// struct S{} impl Settings for S{
+    ///     const NOTIFY: bool = true;
+    /// }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let event = EventQueue::<usize, S>::new();
+    /// let mut reader = EventReader::new(&event);
+    ///
+    /// let event2 = event.clone();
+    /// tokio::spawn(async move {
+    ///     event2.push(1);
+    /// });
+    /// assert_eq!(reader.recv().await, Ok(1));
+    /// # });
+    /// ```
+    #[inline]
+    pub async fn recv(&mut self) -> Result<T, ReaderExpired>
+        where T: Clone
+    {
+        self.0.recv().await
+    }
+}
+
+/// Moving iterator of [EventReader::drain]. Items moved out as returned - not returned ones
+/// stay in queue, and reader continues from them.
+///
+/// On drop `cleanup` may be called, like with [Iter].
+pub struct Drain<'a, T, S: Settings> (BaseDrain<'a, T, BS<S>>);
+impl <'a, T, S: Settings> Iterator for Drain<'a, T, S>{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.0.next()
+    }
+}
+
+/// Item of [EventReader::latest]. Derefs to `T`.
+pub struct Latest<'a, T, S: Settings> (BaseLatest<'a, T, BS<S>>);
+impl<'a, T, S: Settings> Deref for Latest<'a, T, S>{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Non-consuming iterator of [EventReader::peek_iter]. Reader stays where it was.
+pub struct PeekIter<'a, T, S: Settings> (BasePeekIter<'a, T, BS<S>>);
+impl <'a, T, S: Settings> LendingIterator for PeekIter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        self.0.next()
+    }
 }
 
 /// This is consuming iterator.
@@ -41,6 +397,44 @@ impl<T, S: Settings> EventReader<T, S>{
 ///
 /// On [drop] `cleanup` may be called. See [Settings::CLEANUP].
 pub struct Iter<'a, T, S: Settings> (BaseIter<'a, T, BS<S>>);
+impl <'a, T, S: Settings> Iter<'a, T, S>{
+    /// Returns reference to the next item, without advancing iterator.
+    ///
+    /// Nothing is marked as read.
+    #[inline]
+    pub fn peek(&mut self) -> Option<&T> {
+        self.0.peek()
+    }
+
+    /// Same as [next](LendingIterator::next), but also returns item's push time.
+    ///
+    /// Panics, if [Settings::TIMESTAMPS] is disabled.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn next_timestamped(&mut self) -> Option<(&T, Instant)> {
+        self.0.next_timestamped()
+    }
+
+    /// Pick up items, pushed after this iterator returned None.
+    ///
+    /// Iterator is fused - once it returns None, it continue to return None, even if new items
+    /// pushed. This gives consumer predictable batch end. Call `refresh` to continue.
+    #[inline]
+    pub fn refresh(&mut self){
+        self.0.refresh()
+    }
+
+    /// Return reader to the position, iterator started from - as if nothing was read.
+    /// Next [iter](EventReader::iter) will return the same items again. Passed chunks
+    /// are not marked read.
+    ///
+    /// Dropped iterator marks everything it returned as read. Call this instead,
+    /// if batch processing failed. For panic safety - see [CommittedReader](crate::mpmc::CommittedReader).
+    #[inline]
+    pub fn rollback(self){
+        self.0.rollback()
+    }
+}
 impl <'a, T, S: Settings> LendingIterator for Iter<'a, T, S>{
     type ItemValue = T;
 
@@ -48,4 +442,42 @@ impl <'a, T, S: Settings> LendingIterator for Iter<'a, T, S>{
     fn next(&mut self) -> Option<&Self::ItemValue> {
         self.0.next()
     }
-}
\ No newline at end of file
+
+    #[inline]
+    fn for_each<F>(self, f: F)
+        where F: FnMut(&Self::ItemValue)
+    {
+        self.0.for_each(f)
+    }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, f: F) -> B
+        where F: FnMut(B, &Self::ItemValue) -> B
+    {
+        self.0.fold(init, f)
+    }
+
+    #[inline]
+    fn try_for_each<F, E>(&mut self, f: F) -> Result<(), E>
+        where F: FnMut(&Self::ItemValue) -> Result<(), E>
+    {
+        self.0.try_for_each(f)
+    }
+}
+
+/// Requires `streaming_iterator` feature. With both [LendingIterator] and `StreamingIterator`
+/// in scope, `next` is ambiguous - call it as `StreamingIterator::next(&mut iter)`.
+#[cfg(feature = "streaming_iterator")]
+impl <'a, T, S: Settings> streaming_iterator::StreamingIterator for Iter<'a, T, S>{
+    type Item = T;
+
+    #[inline]
+    fn advance(&mut self) {
+        streaming_iterator::StreamingIterator::advance(&mut self.0)
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&T> {
+        streaming_iterator::StreamingIterator::get(&self.0)
+    }
+}
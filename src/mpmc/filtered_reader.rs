@@ -0,0 +1,116 @@
+use crate::event_reader::{LendingIterator, ReaderExpired};
+use crate::mpmc::{EventQueue, EventReader, Iter, Settings};
+
+/// [EventReader], which skips items not matching `filter`.
+///
+/// Skipped items are still marked read - they do not hold queue's memory.
+/// For sparse consumers - only matching items reach your code.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::{EventQueue, FilteredReader};
+/// let event = EventQueue::<usize>::new();
+/// let mut odd = FilteredReader::new(&event, |value: &usize| value % 2 == 1);
+///
+/// event.extend(0..6);
+///
+/// let mut sum = 0;
+/// odd.iter().for_each(|value| sum += value);
+/// assert_eq!(sum, 1 + 3 + 5);
+/// ```
+pub struct FilteredReader<T, S: Settings, F>{
+    reader: EventReader<T, S>,
+    filter: F,
+}
+
+impl<T, S: Settings, F: Fn(&T) -> bool> FilteredReader<T, S, F>{
+    #[inline]
+    pub fn new(event_queue: &EventQueue<T, S>, filter: F) -> Self {
+        Self::from_reader(EventReader::new(event_queue), filter)
+    }
+
+    /// Filter existing reader. It continues from its position.
+    #[inline]
+    pub fn from_reader(reader: EventReader<T, S>, filter: F) -> Self {
+        Self{reader, filter}
+    }
+
+    /// Take back unfiltered reader.
+    #[inline]
+    pub fn into_reader(self) -> EventReader<T, S>{
+        self.reader
+    }
+
+    /// Same as [EventReader::iter], but returns only matching items.
+    ///
+    /// [Expired](Self::is_expired) reader's iterator is empty.
+    #[inline]
+    pub fn iter(&mut self) -> FilteredIter<'_, T, S, F>{
+        FilteredIter{iter: self.reader.iter(), filter: &self.filter}
+    }
+
+    /// Same as [iter](Self::iter), but returns error for expired reader.
+    #[inline]
+    pub fn try_iter(&mut self) -> Result<FilteredIter<'_, T, S, F>, ReaderExpired>{
+        Ok(FilteredIter{iter: self.reader.try_iter()?, filter: &self.filter})
+    }
+
+    /// See [EventReader::is_expired].
+    #[inline]
+    pub fn is_expired(&self) -> bool{
+        self.reader.is_expired()
+    }
+}
+
+/// Consuming iterator of [FilteredReader]. Same as [Iter] - read counters updated on drop.
+pub struct FilteredIter<'a, T, S: Settings, F>{
+    iter: Iter<'a, T, S>,
+    filter: &'a F,
+}
+
+impl<'a, T, S: Settings, F: Fn(&T) -> bool> FilteredIter<'a, T, S, F>{
+    /// See [Iter::refresh].
+    #[inline]
+    pub fn refresh(&mut self){
+        self.iter.refresh()
+    }
+}
+
+impl<'a, T, S: Settings, F: Fn(&T) -> bool> LendingIterator for FilteredIter<'a, T, S, F>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        loop{
+            let is_match = (self.filter)(self.iter.peek()?);
+            if is_match{
+                return self.iter.next();
+            }
+            self.iter.next();
+        }
+    }
+
+    #[inline]
+    fn for_each<G>(self, mut f: G)
+        where G: FnMut(&Self::ItemValue)
+    {
+        let filter = self.filter;
+        self.iter.for_each(|value| if filter(value) { f(value) })
+    }
+
+    #[inline]
+    fn fold<B, G>(self, init: B, mut f: G) -> B
+        where G: FnMut(B, &Self::ItemValue) -> B
+    {
+        let filter = self.filter;
+        self.iter.fold(init, |acc, value| if filter(value) { f(acc, value) } else { acc })
+    }
+
+    #[inline]
+    fn try_for_each<G, E>(&mut self, mut f: G) -> Result<(), E>
+        where G: FnMut(&Self::ItemValue) -> Result<(), E>
+    {
+        let filter = self.filter;
+        self.iter.try_for_each(|value| if filter(value) { f(value) } else { Ok(()) })
+    }
+}
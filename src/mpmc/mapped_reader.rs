@@ -0,0 +1,135 @@
+use crate::event_reader::{LendingIterator, ReaderExpired};
+use crate::mpmc::{EventQueue, EventReader, Iter, Settings};
+use core::marker::PhantomData;
+
+/// [EventReader], which returns `&U` projection of each item.
+///
+/// Projection returns `None` for items, which should be skipped. Skipped items still
+/// marked read. This gives typed view over shared raw queue - e.g. only one variant of enum.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::{EventQueue, MappedReader};
+/// enum Event{ Click{x: i32, y: i32}, Key(char) }
+///
+/// let event = EventQueue::<Event>::new();
+/// let mut keys = MappedReader::new(&event, |event: &Event| match event{
+///     Event::Key(key) => Some(key),
+///     _ => None
+/// });
+///
+/// event.push(Event::Key('a'));
+/// event.push(Event::Click{x: 0, y: 0});
+/// event.push(Event::Key('b'));
+///
+/// let mut iter = keys.iter();
+/// assert_eq!(iter.next(), Some(&'a'));
+/// assert_eq!(iter.next(), Some(&'b'));
+/// assert_eq!(iter.next(), None);
+/// ```
+pub struct MappedReader<T, U, S: Settings, F>{
+    reader: EventReader<T, S>,
+    map: F,
+    phantom: PhantomData<fn(&T) -> &U>,
+}
+
+impl<T, U, S: Settings, F: Fn(&T) -> Option<&U>> MappedReader<T, U, S, F>{
+    #[inline]
+    pub fn new(event_queue: &EventQueue<T, S>, map: F) -> Self {
+        Self::from_reader(EventReader::new(event_queue), map)
+    }
+
+    /// Map existing reader. It continues from its position.
+    #[inline]
+    pub fn from_reader(reader: EventReader<T, S>, map: F) -> Self {
+        Self{reader, map, phantom: PhantomData}
+    }
+
+    /// Take back not mapped reader.
+    #[inline]
+    pub fn into_reader(self) -> EventReader<T, S>{
+        self.reader
+    }
+
+    /// Same as [EventReader::iter], but returns projections.
+    ///
+    /// [Expired](Self::is_expired) reader's iterator is empty.
+    #[inline]
+    pub fn iter(&mut self) -> MappedIter<'_, T, U, S, F>{
+        MappedIter{iter: self.reader.iter(), map: &self.map, phantom: PhantomData}
+    }
+
+    /// Same as [iter](Self::iter), but returns error for expired reader.
+    #[inline]
+    pub fn try_iter(&mut self) -> Result<MappedIter<'_, T, U, S, F>, ReaderExpired>{
+        Ok(MappedIter{iter: self.reader.try_iter()?, map: &self.map, phantom: PhantomData})
+    }
+
+    /// See [EventReader::is_expired].
+    #[inline]
+    pub fn is_expired(&self) -> bool{
+        self.reader.is_expired()
+    }
+}
+
+/// Consuming iterator of [MappedReader]. Same as [Iter] - read counters updated on drop.
+pub struct MappedIter<'a, T, U, S: Settings, F>{
+    iter: Iter<'a, T, S>,
+    map: &'a F,
+    phantom: PhantomData<fn(&T) -> &U>,
+}
+
+impl<'a, T, U, S: Settings, F: Fn(&T) -> Option<&U>> MappedIter<'a, T, U, S, F>{
+    /// See [Iter::refresh].
+    #[inline]
+    pub fn refresh(&mut self){
+        self.iter.refresh()
+    }
+}
+
+impl<'a, T, U, S: Settings, F: Fn(&T) -> Option<&U>> LendingIterator for MappedIter<'a, T, U, S, F>{
+    type ItemValue = U;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        // Projection called twice for returned item - borrow checker does not allow
+        // to return from loop, which continues with the same borrow.
+        loop{
+            let is_some = (self.map)(self.iter.peek()?).is_some();
+            if is_some{
+                return (self.map)(self.iter.next()?);
+            }
+            self.iter.next();
+        }
+    }
+
+    #[inline]
+    fn for_each<G>(self, mut f: G)
+        where G: FnMut(&Self::ItemValue)
+    {
+        let map = self.map;
+        self.iter.for_each(|value| if let Some(value) = map(value) { f(value) })
+    }
+
+    #[inline]
+    fn fold<B, G>(self, init: B, mut f: G) -> B
+        where G: FnMut(B, &Self::ItemValue) -> B
+    {
+        let map = self.map;
+        self.iter.fold(init, |acc, value| match map(value){
+            Some(value) => f(acc, value),
+            None => acc
+        })
+    }
+
+    #[inline]
+    fn try_for_each<G, E>(&mut self, mut f: G) -> Result<(), E>
+        where G: FnMut(&Self::ItemValue) -> Result<(), E>
+    {
+        let map = self.map;
+        self.iter.try_for_each(|value| match map(value){
+            Some(value) => f(value),
+            None => Ok(())
+        })
+    }
+}
@@ -0,0 +1,93 @@
+use crate::merge_reader::{MergeReader as BaseMergeReader, MergeIter as BaseMergeIter};
+use crate::event_reader::LendingIterator;
+use crate::mpmc::{BS, EventReader, Settings};
+use crate::MergeOrder;
+use alloc::vec::Vec;
+
+/// Several readers of the same `T`, read as one. Fan-in for sharded queues,
+/// or queues of different producers.
+///
+/// Items of each reader come in FIFO order. Between readers - see [MergeOrder].
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::MergeOrder;
+/// # use rc_event_queue::mpmc::{EventQueue, EventReader, MergeReader};
+/// let shard0 = EventQueue::<usize>::new();
+/// let shard1 = EventQueue::<usize>::new();
+/// let mut reader = MergeReader::new(
+///     vec![EventReader::new(&shard0), EventReader::new(&shard1)],
+///     MergeOrder::Sequence
+/// );
+///
+/// shard0.extend([0, 2]);
+/// shard1.extend([1, 3]);
+///
+/// let mut sum = 0;
+/// reader.iter().for_each(|value| sum += value);
+/// assert_eq!(sum, 6);
+/// ```
+pub struct MergeReader<T, S: Settings>(BaseMergeReader<T, BS<S>>);
+impl<T, S: Settings> MergeReader<T, S>{
+    /// Panics on [MergeOrder::Timestamp], if [Settings::TIMESTAMPS] is disabled.
+    #[inline]
+    pub fn new(readers: Vec<EventReader<T, S>>, order: MergeOrder) -> Self{
+        Self{0: BaseMergeReader::new(readers.into_iter().map(|reader| reader.0).collect(), order)}
+    }
+
+    /// Number of merged readers.
+    #[inline]
+    pub fn len(&self) -> usize{
+        self.0.readers().len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.0.readers().is_empty()
+    }
+
+    /// Take readers back.
+    #[inline]
+    pub fn into_readers(self) -> Vec<EventReader<T, S>>{
+        self.0.into_readers().into_iter().map(|reader| EventReader{0: reader}).collect()
+    }
+
+    /// Iterate all readers at once. Each item read from its reader.
+    ///
+    /// Expired readers are skipped - see [MergeIter::expired_sources]. Allocates.
+    #[inline]
+    pub fn iter(&mut self) -> MergeIter<'_, T, S>{
+        MergeIter{0: self.0.iter()}
+    }
+}
+
+/// Consuming iterator of [MergeReader]. Same as [Iter](crate::mpmc::Iter) - read counters updated on drop.
+pub struct MergeIter<'a, T, S: Settings>(BaseMergeIter<'a, T, BS<S>>);
+impl<'a, T, S: Settings> MergeIter<'a, T, S>{
+    /// Same as [next](LendingIterator::next), but also returns index of reader, item came from.
+    #[inline]
+    pub fn next_with_source(&mut self) -> Option<(usize, &T)>{
+        self.0.next_with_source()
+    }
+
+    /// Indices of [expired](crate::mpmc::EventReader::is_expired) readers. Iterator skips them.
+    #[inline]
+    pub fn expired_sources(&self) -> impl Iterator<Item = usize> + '_{
+        self.0.expired_sources()
+    }
+
+    /// See [Iter::refresh](crate::mpmc::Iter::refresh).
+    #[inline]
+    pub fn refresh(&mut self){
+        self.0.refresh()
+    }
+}
+
+impl<'a, T, S: Settings> LendingIterator for MergeIter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        self.0.next()
+    }
+}
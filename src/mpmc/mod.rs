@@ -4,23 +4,165 @@
 
 mod event_queue;
 mod event_reader;
+mod builder;
+mod subscriber_factory;
+mod sender;
+mod merge_reader;
+mod topic_bus;
+mod priority_event_queue;
+mod sharded_event_queue;
+mod filtered_reader;
+mod mapped_reader;
+mod write_guard;
+mod weak_reader;
+mod committed_reader;
+#[cfg(all(feature = "std", not(loom)))]
+mod cleanup_thread;
 
-use crate::CleanupMode;
+use crate::{CleanupMode, GrowthStrategy, FreedChunkInfo, ChunkAllocator, LockBackend};
 use crate::event_queue::Settings as BaseSettings;
-use std::marker::PhantomData;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::time::Duration;
+#[cfg(feature = "std")]
+use crate::StaleReaderPolicy;
 
 pub use event_queue::*;
 pub use event_reader::*;
+pub use builder::*;
+pub use subscriber_factory::*;
+pub use sender::*;
+pub use merge_reader::*;
+pub use topic_bus::*;
+pub use priority_event_queue::*;
+pub use sharded_event_queue::*;
+pub use filtered_reader::*;
+pub use mapped_reader::*;
+pub use write_guard::*;
+pub use weak_reader::*;
+pub use committed_reader::*;
+#[cfg(all(feature = "std", not(loom)))]
+pub use cleanup_thread::*;
 
 pub trait Settings{
     const MIN_CHUNK_SIZE : u32 = 4;
     const MAX_CHUNK_SIZE : u32 = 4096;
     const CLEANUP: CleanupMode = CleanupMode::OnChunkRead;
+    /// Chunk capacity growth pattern. Chunks grow from `MIN_CHUNK_SIZE` to `MAX_CHUNK_SIZE`.
+    ///
+    /// [GrowthStrategy::Constant] with big `MIN_CHUNK_SIZE` - for steady load.
+    /// [GrowthStrategy::Doubling] - for bursty load, to reach optimal size faster.
+    const GROWTH: GrowthStrategy = GrowthStrategy::Default;
+    /// On `extend`, when chunk is full - allocate next one big enough for the rest
+    /// of iterator (by its `size_hint` lower bound, up to `MAX_CHUNK_SIZE`), instead of
+    /// following [GROWTH](Self::GROWTH) chunk after chunk.
+    ///
+    /// Faster bulk loads. But one big chunk breaks growth pattern - disabled by default.
+    const EXTEND_SIZE_HINT: bool = false;
+    /// Drop pushed values immediately, while there is no readers.
+    /// Counted in [EventQueue::dropped_count].
+    ///
+    /// Useful for fire-and-forget queues (logging, telemetry), which should not
+    /// accumulate when nobody listens.
+    const DROP_WITHOUT_READERS: bool = false;
+    /// Make pushed values visible to readers only every `PUBLISH_BATCH` push
+    /// (or when chunk is full, or on [EventQueue::flush]).
+    ///
+    /// Trades visibility latency for write throughput. 1 - every push visible immediately.
+    const PUBLISH_BATCH: u32 = 1;
+    /// Collect [Stats](crate::Stats), available through [EventQueue::stats].
+    ///
+    /// Adds relaxed atomic increment to each push and chunk operation.
+    const STATS: bool = false;
+    /// Store push time alongside each item. Read with [Iter::next_timestamped].
+    ///
+    /// Useful for latency measurement and age metrics. Costs `Instant::now()` per push,
+    /// and `size_of::<Instant>()` per slot.
+    #[cfg(feature = "std")]
+    const TIMESTAMPS: bool = false;
+    /// Automatically [truncate_older_than](EventQueue::truncate_older_than) this age,
+    /// on each new chunk. Bounds queue by time - for logging/telemetry buffers.
+    #[cfg(feature = "std")]
+    const RETENTION: Option<Duration> = None;
+    /// Time to live of each item, counted from its push time. Requires [TIMESTAMPS](Self::TIMESTAMPS).
+    ///
+    /// Expired items transparently skipped by [Iter] - readers never see them.
+    /// Expiration checked when iterator is created (and on [Iter::refresh]):
+    /// items, expired during the read session, are still returned.
+    ///
+    /// Chunks with all items expired are freed on each new chunk and [EventQueue::cleanup],
+    /// like with [RETENTION](Self::RETENTION).
+    #[cfg(feature = "std")]
+    const ITEM_TTL: Option<Duration> = None;
+    /// Automatically unsubscribe readers, which are idle or lag too much - as if each
+    /// reader was created with [EventReader::with_ttl]. Expired reader does not pin queue
+    /// memory anymore, and fails to read with [ReaderExpired](crate::ReaderExpired).
+    ///
+    /// Protects from leaked readers, which otherwise hold the whole history.
+    /// Checked on each cleanup. Costs `Instant::now()` per read session.
+    #[cfg(feature = "std")]
+    const STALE_READERS: StaleReaderPolicy = StaleReaderPolicy::NEVER;
+    /// Called each time chunk freed by cleanup (or recycled, with [RECYCLE_POOL_SIZE](Self::RECYCLE_POOL_SIZE) > 0).
+    /// Not called for chunks destructed with queue.
+    ///
+    /// Called under queue lock - keep it short. Useful for reclamation metrics.
+    const ON_CHUNK_FREE: Option<fn(FreedChunkInfo)> = None;
+    /// How many freed chunks keep for reuse. Biggest ones kept. 0 - no reuse.
+    ///
+    /// When queue reach its optimal size - chunks will be just swapped, without alloc/dealloc.
+    /// Bigger pool - less allocations for bursty workloads.
+    ///
+    /// Defaults to 1 with `double_buffering` feature, 0 otherwise.
+    const RECYCLE_POOL_SIZE: usize = if cfg!(feature = "double_buffering") {1} else {0};
+    /// Real-time mode. If > 0 - all `PREALLOCATED_CHUNKS` chunks allocated at queue construction,
+    /// and `push`/`extend` never allocate. Freed chunks returned back to the reserve.
+    ///
+    /// When all chunks are occupied, [EventQueue::try_push] returns value back, and
+    /// `push`/`extend` drop it (counted in [EventQueue::dropped_count]).
+    ///
+    /// Requires [MIN_CHUNK_SIZE](Self::MIN_CHUNK_SIZE) == [MAX_CHUNK_SIZE](Self::MAX_CHUNK_SIZE).
+    /// 0 - disabled.
+    const PREALLOCATED_CHUNKS: u32 = 0;
+    /// Allocator for chunks memory. Arena, pool, tracked allocator, etc.
+    ///
+    /// Can be called from any thread, which owns queue or reader.
+    const CHUNK_ALLOCATOR: ChunkAllocator = ChunkAllocator::GLOBAL;
+    /// Wake threads/tasks, waiting in [select](crate::select) for this queue readers.
+    ///
+    /// Costs `SeqCst` fence and relaxed atomic load per push/extend.
+    /// Without it, select on queue's readers panics.
+    const NOTIFY: bool = false;
+    /// Lock, used by writers (and readers, after `clear`/`truncate`).
+    ///
+    /// [LockBackend::Spin] - for latency-sensitive, [LockBackend::Std] or `ParkingLot` -
+    /// for many writers on oversubscribed cores. [LockBackend::Ticket] or `ParkingLotFair` -
+    /// when writers must be served in arrival order.
+    const LOCK: LockBackend = LockBackend::Yield;
+    /// If `size_of::<T>()` is bigger - each item stored in `Box`, and chunk slot holds
+    /// only pointer. For huge `T` (like enum with one big variant) - keeps chunks compact,
+    /// and chunk allocations cheap. Costs allocation per push.
+    ///
+    /// Readers see `&T` as usual. Over-aligned `T` (alignment above chunk header's) never boxed.
+    ///
+    /// `usize::MAX` - disabled.
+    const SPILL_THRESHOLD: usize = usize::MAX;
+
+    /// Set only by [RuntimeSettings].
+    #[doc(hidden)]
+    const RUNTIME_CONFIG: bool = false;
 }
 
 pub struct DefaultSettings{}
 impl Settings for DefaultSettings{}
 
+/// `MIN_CHUNK_SIZE`, `MAX_CHUNK_SIZE`, `CLEANUP` and `GROWTH` are set at runtime, with [EventQueue::builder].
+///
+/// A bit slower, than compile-time [Settings].
+pub struct RuntimeSettings{}
+impl Settings for RuntimeSettings{
+    const RUNTIME_CONFIG: bool = true;
+}
+
 /// mpmc::Settings -> event_queue::Settings
 pub(crate) struct BS<S: Settings>{
     _phantom: PhantomData<S>
@@ -29,6 +171,27 @@ impl<S: Settings> BaseSettings for BS<S>{
     const MIN_CHUNK_SIZE : u32 = S::MIN_CHUNK_SIZE;
     const MAX_CHUNK_SIZE : u32 = S::MAX_CHUNK_SIZE;
     const CLEANUP: CleanupMode = S::CLEANUP;
+    const GROWTH: GrowthStrategy = S::GROWTH;
+    const EXTEND_SIZE_HINT: bool = S::EXTEND_SIZE_HINT;
     const LOCK_ON_NEW_CHUNK_CLEANUP: bool = false;
     const CLEANUP_IN_UNSUBSCRIBE: bool = true;
+    const DROP_WITHOUT_READERS: bool = S::DROP_WITHOUT_READERS;
+    const PUBLISH_BATCH: u32 = S::PUBLISH_BATCH;
+    const RUNTIME_CONFIG: bool = S::RUNTIME_CONFIG;
+    const STATS: bool = S::STATS;
+    #[cfg(feature = "std")]
+    const TIMESTAMPS: bool = S::TIMESTAMPS;
+    #[cfg(feature = "std")]
+    const RETENTION: Option<Duration> = S::RETENTION;
+    #[cfg(feature = "std")]
+    const ITEM_TTL: Option<Duration> = S::ITEM_TTL;
+    #[cfg(feature = "std")]
+    const STALE_READERS: StaleReaderPolicy = S::STALE_READERS;
+    const ON_CHUNK_FREE: Option<fn(FreedChunkInfo)> = S::ON_CHUNK_FREE;
+    const RECYCLE_POOL_SIZE: usize = S::RECYCLE_POOL_SIZE;
+    const PREALLOCATED_CHUNKS: u32 = S::PREALLOCATED_CHUNKS;
+    const CHUNK_ALLOCATOR: ChunkAllocator = S::CHUNK_ALLOCATOR;
+    const NOTIFY: bool = S::NOTIFY;
+    const LOCK: LockBackend = S::LOCK;
+    const SPILL_THRESHOLD: usize = S::SPILL_THRESHOLD;
 }
\ No newline at end of file
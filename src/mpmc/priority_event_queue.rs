@@ -0,0 +1,145 @@
+use crate::mpmc::{DefaultSettings, EventQueue, EventReader, MergeIter, MergeReader, Settings};
+use crate::event_reader::LendingIterator;
+use crate::MergeOrder;
+use alloc::vec::Vec;
+
+/// Several [EventQueue]s (lanes) - one per priority. 0 - the highest priority.
+///
+/// [PriorityEventReader] returns items of higher priority lanes first.
+/// Within lane - FIFO.
+///
+/// Cloneable handle. All clones refer to the same lanes.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::{PriorityEventQueue, PriorityEventReader};
+/// let queue = PriorityEventQueue::<&str>::new(2);
+/// let mut reader = PriorityEventReader::new(&queue);
+///
+/// queue.push(1, "move");
+/// queue.push(0, "collision");
+/// queue.push(1, "idle");
+///
+/// let mut iter = reader.iter();
+/// assert_eq!(iter.next(), Some(&"collision"));
+/// assert_eq!(iter.next(), Some(&"move"));
+/// assert_eq!(iter.next(), Some(&"idle"));
+/// ```
+pub struct PriorityEventQueue<T, S: Settings = DefaultSettings>{
+    lanes: Vec<EventQueue<T, S>>
+}
+
+impl<T, S: Settings> PriorityEventQueue<T, S>{
+    /// Panics, if `lanes_count` is 0.
+    pub fn new(lanes_count: usize) -> Self {
+        assert!(lanes_count > 0, "At least one lane required.");
+        Self{ lanes: (0..lanes_count).map(|_| EventQueue::new()).collect() }
+    }
+
+    #[inline]
+    pub fn lanes_count(&self) -> usize{
+        self.lanes.len()
+    }
+
+    /// Queue of `priority` lane. Panics, if there is no such lane.
+    #[inline]
+    pub fn lane(&self, priority: usize) -> &EventQueue<T, S>{
+        &self.lanes[priority]
+    }
+
+    /// Push to `priority` lane. Panics, if there is no such lane.
+    #[inline]
+    pub fn push(&self, priority: usize, value: T){
+        self.lanes[priority].push(value);
+    }
+
+    /// Push to `priority` lane. Panics, if there is no such lane.
+    #[inline]
+    pub fn extend<I>(&self, priority: usize, iter: I)
+        where I: IntoIterator<Item = T>
+    {
+        self.lanes[priority].extend(iter);
+    }
+
+    /// [Flush](EventQueue::flush) all lanes.
+    pub fn flush(&self){
+        for lane in &self.lanes{
+            lane.flush();
+        }
+    }
+
+    /// [Clear](EventQueue::clear) all lanes.
+    pub fn clear(&self){
+        for lane in &self.lanes{
+            lane.clear();
+        }
+    }
+
+    /// Sum of lanes [len](EventQueue::len).
+    pub fn len(&self) -> usize{
+        self.lanes.iter().map(|lane| lane.len()).sum()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.lanes.iter().all(|lane| lane.is_empty())
+    }
+}
+
+impl<T, S: Settings> Clone for PriorityEventQueue<T, S>{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self{ lanes: self.lanes.clone() }
+    }
+}
+
+/// Reader of all [PriorityEventQueue] lanes.
+pub struct PriorityEventReader<T, S: Settings>(MergeReader<T, S>);
+impl<T, S: Settings> PriorityEventReader<T, S>{
+    #[inline]
+    pub fn new(queue: &PriorityEventQueue<T, S>) -> Self{
+        let readers = queue.lanes.iter().map(EventReader::new).collect();
+        Self{0: MergeReader::new(readers, MergeOrder::Unordered)}
+    }
+
+    /// Higher priority lanes drained first.
+    ///
+    /// Items, pushed to higher priority lane during iteration, will be returned
+    /// after [refresh](PriorityIter::refresh) or with the next iterator.
+    #[inline]
+    pub fn iter(&mut self) -> PriorityIter<'_, T, S>{
+        PriorityIter{0: self.0.iter()}
+    }
+}
+
+/// Consuming iterator of [PriorityEventReader].
+pub struct PriorityIter<'a, T, S: Settings>(MergeIter<'a, T, S>);
+impl<'a, T, S: Settings> PriorityIter<'a, T, S>{
+    /// Same as [next](LendingIterator::next), but also returns item's priority.
+    #[inline]
+    pub fn next_with_priority(&mut self) -> Option<(usize, &T)>{
+        self.0.next_with_source()
+    }
+
+    /// Priorities of lanes, whose reader [expired](crate::mpmc::EventReader::is_expired).
+    /// Iterator skips them.
+    #[inline]
+    pub fn expired_priorities(&self) -> impl Iterator<Item = usize> + '_{
+        self.0.expired_sources()
+    }
+
+    /// Pick up items, pushed after iterator started. Continues from the highest priority lane.
+    #[inline]
+    pub fn refresh(&mut self){
+        self.0.refresh()
+    }
+}
+
+impl<'a, T, S: Settings> LendingIterator for PriorityIter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        self.0.next()
+    }
+}
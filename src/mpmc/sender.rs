@@ -0,0 +1,173 @@
+use crate::mpmc::{DefaultSettings, EventQueue, Settings, SubscriberFactory};
+use crate::{AllocError, TryPushError};
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+
+/// Cloneable handle, which can only push.
+///
+/// Pass it to components, which should write to queue, but should not
+/// subscribe/clear/etc. Keeps [EventQueue] alive. Pair with [SubscriberFactory] -
+/// see [channel].
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// let (sender, subscribers) = rc_event_queue::mpmc::channel::<usize>();
+///
+/// let mut reader = subscribers.subscribe();
+/// sender.clone().push(1);
+/// assert_eq!(reader.iter().next(), Some(&1));
+/// ```
+pub struct Sender<T, S: Settings = DefaultSettings>(
+    EventQueue<T, S>
+);
+
+impl<T, S: Settings> Sender<T, S>{
+    #[inline]
+    pub fn new(event_queue: &EventQueue<T, S>) -> Self {
+        Self{0: event_queue.clone()}
+    }
+
+    /// Same as [EventQueue::push].
+    #[inline]
+    pub fn push(&self, value: T){
+        self.0.push(value);
+    }
+
+    /// Same as [EventQueue::try_push].
+    #[inline]
+    pub fn try_push(&self, value: T) -> Result<(), AllocError<T>>{
+        self.0.try_push(value)
+    }
+
+    /// Same as [EventQueue::try_push_nonblocking].
+    #[inline]
+    pub fn try_push_nonblocking(&self, value: T) -> Result<(), TryPushError<T>>{
+        self.0.try_push_nonblocking(value)
+    }
+
+    /// Same as [EventQueue::extend].
+    #[inline]
+    pub fn extend<I>(&self, iter: I)
+        where I: IntoIterator<Item = T>
+    {
+        self.0.extend(iter);
+    }
+
+    /// Same as [EventQueue::try_extend].
+    #[inline]
+    pub fn try_extend<I>(&self, iter: I) -> Result<(), AllocError<T>>
+        where I: IntoIterator<Item = T>
+    {
+        self.0.try_extend(iter)
+    }
+
+    /// Same as [EventQueue::flush].
+    #[inline]
+    pub fn flush(&self){
+        self.0.flush();
+    }
+}
+
+/// Same as [Sender::extend].
+impl<T, S: Settings> Extend<T> for Sender<T, S>{
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        Sender::extend(self, iter);
+    }
+}
+
+impl<T, S: Settings> Clone for Sender<T, S>{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self{0: self.0.clone()}
+    }
+}
+
+/// Queue is unbounded - always ready. `start_send` is [try_push](Sender::try_push) -
+/// fails only on chunk allocation failure (or exhausted [Settings::PREALLOCATED_CHUNKS]).
+/// Flush/close [publish](Sender::flush) batched values.
+#[cfg(feature = "async")]
+impl<T, S: Settings> futures_sink::Sink<T> for Sender<T, S>{
+    type Error = AllocError<T>;
+
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        self.try_push(item)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.flush();
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
+    }
+}
+
+/// Borrowed [EventQueue], for [std::iter::Extend].
+///
+/// [EventQueue::extend] takes `&self`, and can not implement `Extend::extend(&mut self)`.
+/// Costs nothing - just a reference.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::{EventQueue, EventReader};
+/// fn fill(out: &mut impl Extend<usize>){
+///     out.extend(0..3);
+/// }
+///
+/// let event = EventQueue::<usize>::new();
+/// let mut reader = EventReader::new(&event);
+/// fill(&mut event.writer());
+/// assert_eq!(reader.iter().next(), Some(&0));
+/// ```
+pub struct Writer<'a, T, S: Settings = DefaultSettings>(
+    &'a EventQueue<T, S>
+);
+
+impl<'a, T, S: Settings> Writer<'a, T, S>{
+    #[inline]
+    pub fn new(event_queue: &'a EventQueue<T, S>) -> Self {
+        Self{0: event_queue}
+    }
+}
+
+impl<'a, T, S: Settings> Extend<T> for Writer<'a, T, S>{
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl<T, S: Settings> EventQueue<T, S>{
+    /// [Writer] for generic code, which wants [std::iter::Extend].
+    #[inline]
+    pub fn writer(&self) -> Writer<'_, T, S> {
+        Writer::new(self)
+    }
+
+    /// Split into write-only and subscribe-only handles.
+    #[inline]
+    pub fn into_channel(self) -> (Sender<T, S>, SubscriberFactory<T, S>) {
+        let subscribers = SubscriberFactory::new(&self);
+        (Sender{0: self}, subscribers)
+    }
+}
+
+/// New queue with [DefaultSettings], as [Sender] + [SubscriberFactory] pair.
+///
+/// For custom settings use [EventQueue::into_channel].
+#[inline]
+pub fn channel<T>() -> (Sender<T>, SubscriberFactory<T>) {
+    EventQueue::<T>::new().into_channel()
+}
@@ -0,0 +1,192 @@
+use crate::sync::{Arc, AtomicUsize, Ordering};
+use crate::mpmc::{DefaultSettings, EventQueue, EventReader, MergeIter, MergeReader, Settings};
+use crate::event_reader::LendingIterator;
+use crate::MergeOrder;
+use alloc::vec::Vec;
+
+/// Several [EventQueue]s (shards) - producers write each to its own shard, readers see
+/// all shards as one stream.
+///
+/// Producer's shard lock is not shared with other producers - so writers do not contend.
+/// In exchange - there is no global FIFO: items of one [ShardProducer] come in FIFO order,
+/// between shards - see [MergeOrder].
+///
+/// Shards count is fixed. [producer](Self::producer)s get shards round-robin - with more
+/// producers than shards, some shards are shared.
+///
+/// Cloneable handle. All clones refer to the same shards.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::{ShardedEventQueue, ShardedEventReader};
+/// let queue = ShardedEventQueue::<usize>::new(2);
+/// let mut reader = ShardedEventReader::new(&queue);
+///
+/// let producer0 = queue.producer();
+/// let producer1 = queue.producer();
+/// producer0.extend([0, 2]);
+/// producer1.extend([1, 3]);
+///
+/// let mut iter = reader.iter();
+/// assert_eq!(iter.next_with_shard(), Some((0, &0)));
+/// assert_eq!(iter.next_with_shard(), Some((1, &1)));
+/// assert_eq!(iter.next_with_shard(), Some((0, &2)));
+/// assert_eq!(iter.next_with_shard(), Some((1, &3)));
+/// ```
+pub struct ShardedEventQueue<T, S: Settings = DefaultSettings>(Arc<Shards<T, S>>);
+
+struct Shards<T, S: Settings>{
+    shards: Vec<EventQueue<T, S>>,
+    /// Shard of the next producer, modulo shards count.
+    next_shard: AtomicUsize,
+}
+
+impl<T, S: Settings> ShardedEventQueue<T, S>{
+    /// Panics, if `shards_count` is 0.
+    pub fn new(shards_count: usize) -> Self {
+        assert!(shards_count > 0, "At least one shard required.");
+        Self{0: Arc::new(Shards{
+            shards: (0..shards_count).map(|_| EventQueue::new()).collect(),
+            next_shard: AtomicUsize::new(0),
+        })}
+    }
+
+    #[inline]
+    pub fn shards_count(&self) -> usize{
+        self.0.shards.len()
+    }
+
+    /// Queue of `index` shard. Panics, if there is no such shard.
+    #[inline]
+    pub fn shard(&self, index: usize) -> &EventQueue<T, S>{
+        &self.0.shards[index]
+    }
+
+    /// Writer to the next shard, round-robin.
+    ///
+    /// Use one producer per thread.
+    pub fn producer(&self) -> ShardProducer<T, S>{
+        let index = self.0.next_shard.fetch_add(1, Ordering::Relaxed) % self.shards_count();
+        ShardProducer{ shard: self.0.shards[index].clone(), index }
+    }
+
+    /// [Flush](EventQueue::flush) all shards.
+    pub fn flush(&self){
+        for shard in &self.0.shards{
+            shard.flush();
+        }
+    }
+
+    /// [Clear](EventQueue::clear) all shards.
+    pub fn clear(&self){
+        for shard in &self.0.shards{
+            shard.clear();
+        }
+    }
+
+    /// Sum of shards [len](EventQueue::len).
+    pub fn len(&self) -> usize{
+        self.0.shards.iter().map(|shard| shard.len()).sum()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.0.shards.iter().all(|shard| shard.is_empty())
+    }
+}
+
+impl<T, S: Settings> Clone for ShardedEventQueue<T, S>{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self{0: self.0.clone()}
+    }
+}
+
+/// Writer of one [ShardedEventQueue] shard. See [ShardedEventQueue::producer].
+pub struct ShardProducer<T, S: Settings = DefaultSettings>{
+    shard: EventQueue<T, S>,
+    index: usize,
+}
+
+impl<T, S: Settings> ShardProducer<T, S>{
+    /// Index of producer's shard.
+    #[inline]
+    pub fn shard_index(&self) -> usize{
+        self.index
+    }
+
+    /// Producer's shard queue.
+    #[inline]
+    pub fn shard(&self) -> &EventQueue<T, S>{
+        &self.shard
+    }
+
+    #[inline]
+    pub fn push(&self, value: T){
+        self.shard.push(value);
+    }
+
+    #[inline]
+    pub fn extend<I>(&self, iter: I)
+        where I: IntoIterator<Item = T>
+    {
+        self.shard.extend(iter);
+    }
+}
+
+/// Reader of all [ShardedEventQueue] shards.
+pub struct ShardedEventReader<T, S: Settings>(MergeReader<T, S>);
+impl<T, S: Settings> ShardedEventReader<T, S>{
+    /// Shards interleaved by [MergeOrder::Sequence].
+    #[inline]
+    pub fn new(queue: &ShardedEventQueue<T, S>) -> Self{
+        Self::with_order(queue, MergeOrder::Sequence)
+    }
+
+    /// [MergeOrder::Timestamp] - for push order between shards.
+    ///
+    /// Panics on [MergeOrder::Timestamp], if [Settings::TIMESTAMPS] is disabled.
+    #[inline]
+    pub fn with_order(queue: &ShardedEventQueue<T, S>, order: MergeOrder) -> Self{
+        let readers = queue.0.shards.iter().map(EventReader::new).collect();
+        Self{0: MergeReader::new(readers, order)}
+    }
+
+    /// See [MergeReader::iter].
+    #[inline]
+    pub fn iter(&mut self) -> ShardedIter<'_, T, S>{
+        ShardedIter{0: self.0.iter()}
+    }
+}
+
+/// Consuming iterator of [ShardedEventReader].
+pub struct ShardedIter<'a, T, S: Settings>(MergeIter<'a, T, S>);
+impl<'a, T, S: Settings> ShardedIter<'a, T, S>{
+    /// Same as [next](LendingIterator::next), but also returns item's shard index.
+    #[inline]
+    pub fn next_with_shard(&mut self) -> Option<(usize, &T)>{
+        self.0.next_with_source()
+    }
+
+    /// Indices of shards, whose reader [expired](crate::mpmc::EventReader::is_expired).
+    /// Iterator skips them.
+    #[inline]
+    pub fn expired_shards(&self) -> impl Iterator<Item = usize> + '_{
+        self.0.expired_sources()
+    }
+
+    /// See [Iter::refresh](crate::mpmc::Iter::refresh).
+    #[inline]
+    pub fn refresh(&mut self){
+        self.0.refresh()
+    }
+}
+
+impl<'a, T, S: Settings> LendingIterator for ShardedIter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        self.0.next()
+    }
+}
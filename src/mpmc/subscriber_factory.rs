@@ -0,0 +1,66 @@
+#[cfg(feature = "std")]
+use std::time::Duration;
+use crate::mpmc::{DefaultSettings, EventQueue, EventReader, FilteredReader, Settings};
+use crate::ReaderOptions;
+
+/// Cloneable handle, which can only create [EventReader]s.
+///
+/// Pass it to components, which should be able to subscribe, but should not
+/// push/clear/etc. Keeps [EventQueue] alive.
+///
+/// There is no spmc version, since spmc subscription requires exclusive access to queue.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::{EventQueue, SubscriberFactory};
+/// let event = EventQueue::<usize>::new();
+/// let factory = SubscriberFactory::new(&event);
+///
+/// let mut reader = factory.clone().subscribe();
+/// event.push(1);
+/// assert_eq!(reader.iter().next(), Some(&1));
+/// ```
+pub struct SubscriberFactory<T, S: Settings = DefaultSettings>(
+    EventQueue<T, S>
+);
+
+impl<T, S: Settings> SubscriberFactory<T, S>{
+    #[inline]
+    pub fn new(event_queue: &EventQueue<T, S>) -> Self {
+        Self{0: event_queue.clone()}
+    }
+
+    /// Same as [EventReader::new].
+    #[inline]
+    pub fn subscribe(&self) -> EventReader<T, S> {
+        EventReader::new(&self.0)
+    }
+
+    /// Same as [EventReader::with_ttl].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn subscribe_with_ttl(&self, ttl: Duration) -> EventReader<T, S> {
+        EventReader::with_ttl(&self.0, ttl)
+    }
+
+    /// Same as [EventReader::with_options].
+    #[inline]
+    pub fn subscribe_with_options(&self, options: ReaderOptions) -> EventReader<T, S> {
+        EventReader::with_options(&self.0, options)
+    }
+
+    /// Same as [FilteredReader::new].
+    #[inline]
+    pub fn subscribe_with_filter<F>(&self, filter: F) -> FilteredReader<T, S, F>
+        where F: Fn(&T) -> bool
+    {
+        FilteredReader::new(&self.0, filter)
+    }
+}
+
+impl<T, S: Settings> Clone for SubscriberFactory<T, S>{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self{0: self.0.clone()}
+    }
+}
@@ -0,0 +1,224 @@
+use crate::sync::{Arc, Mutex};
+use crate::mpmc::{DefaultSettings, EventQueue, EventReader, MergeIter, MergeReader, Settings};
+use crate::event_reader::LendingIterator;
+use crate::MergeOrder;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// Keyed routing over several [EventQueue]s - one per topic.
+///
+/// Producers push `(key, value)`. Consumers subscribe to set of keys, and read all
+/// of them with one [TopicReader]. Topic's queue created on first subscribe.
+/// Values pushed to topic, nobody ever subscribed to, are dropped.
+///
+/// Cloneable handle. All clones refer to the same bus.
+///
+/// Topics without readers are not removed automatically - call [cleanup](Self::cleanup)
+/// from time to time.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::TopicBus;
+/// let bus = TopicBus::<&str, usize>::new();
+/// let mut reader = bus.subscribe(["input", "network"]);
+///
+/// bus.push("input", 1);
+/// bus.push("render", 2);  // no subscribers - dropped
+/// bus.push("network", 3);
+///
+/// let mut iter = reader.iter();
+/// assert_eq!(iter.next_with_key(), Some((&"input", &1)));
+/// assert_eq!(iter.next_with_key(), Some((&"network", &3)));
+/// assert_eq!(iter.next_with_key(), None);
+/// ```
+pub struct TopicBus<K, T, S: Settings = DefaultSettings>(
+    Arc<Mutex<BTreeMap<K, EventQueue<T, S>>>>
+);
+
+impl<K: Ord + Clone, T, S: Settings> TopicBus<K, T, S>{
+    #[inline]
+    pub fn new() -> Self {
+        Self{0: Arc::new(Mutex::new(BTreeMap::new()))}
+    }
+
+    /// Queue of topic, if exists.
+    #[inline]
+    fn queue(&self, key: &K) -> Option<EventQueue<T, S>>{
+        self.0.lock().get(key).cloned()
+    }
+
+    /// Push to topic `key`. Dropped, if topic does not exist.
+    ///
+    /// Returns false, if dropped.
+    #[inline]
+    pub fn push(&self, key: K, value: T) -> bool{
+        match self.queue(&key){
+            Some(queue) => {
+                queue.push(value);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Push several values to topic `key`, under single queue lock. Dropped, if topic does not exist.
+    ///
+    /// Returns false, if dropped.
+    #[inline]
+    pub fn extend<I>(&self, key: K, iter: I) -> bool
+        where I: IntoIterator<Item = T>
+    {
+        match self.queue(&key){
+            Some(queue) => {
+                queue.extend(iter);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Subscribe to `keys`, creating missing topics. Topics read one after another -
+    /// see [MergeOrder::Unordered].
+    ///
+    /// Reader receives only values, pushed after subscribe.
+    #[inline]
+    pub fn subscribe<I>(&self, keys: I) -> TopicReader<K, T, S>
+        where I: IntoIterator<Item = K>
+    {
+        self.subscribe_ordered(keys, MergeOrder::Unordered)
+    }
+
+    /// Same as [subscribe](Self::subscribe), with specific order between topics.
+    pub fn subscribe_ordered<I>(&self, keys: I, order: MergeOrder) -> TopicReader<K, T, S>
+        where I: IntoIterator<Item = K>
+    {
+        let mut topic_keys: Vec<K> = Vec::new();
+        let mut readers = Vec::new();
+
+        // Subscribe under bus lock - so cleanup will not remove topic in between.
+        let mut topics = self.0.lock();
+        for key in keys{
+            if topic_keys.contains(&key){
+                continue;
+            }
+            let queue = topics.entry(key.clone()).or_insert_with(EventQueue::new);
+            readers.push(EventReader::new(queue));
+            topic_keys.push(key);
+        }
+        drop(topics);
+
+        TopicReader{
+            keys: topic_keys,
+            reader: MergeReader::new(readers, order),
+        }
+    }
+
+    /// Underlying queue of topic, if exists.
+    #[inline]
+    pub fn topic(&self, key: &K) -> Option<EventQueue<T, S>>{
+        self.queue(key)
+    }
+
+    #[inline]
+    pub fn topics_count(&self) -> usize{
+        self.0.lock().len()
+    }
+
+    /// Remove topic. Existing subscribers of it will not receive new values.
+    ///
+    /// Returns false, if there was no such topic.
+    #[inline]
+    pub fn remove_topic(&self, key: &K) -> bool{
+        self.0.lock().remove(key).is_some()
+    }
+
+    /// Remove topics without readers, and [cleanup](EventQueue::cleanup) the rest.
+    ///
+    /// Returns number of removed topics.
+    pub fn cleanup(&self) -> usize{
+        // Remove under lock, but drop queues outside.
+        let mut removed = Vec::new();
+        {
+            let mut topics = self.0.lock();
+            let empty_keys: Vec<K> = topics.iter()
+                .filter(|(_, queue)| queue.readers_count() == 0)
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in empty_keys{
+                removed.push(topics.remove(&key));
+            }
+            for queue in topics.values(){
+                queue.cleanup();
+            }
+        }
+        removed.len()
+    }
+}
+
+impl<K, T, S: Settings> Clone for TopicBus<K, T, S>{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self{0: self.0.clone()}
+    }
+}
+
+/// Reader of several [TopicBus] topics.
+pub struct TopicReader<K, T, S: Settings>{
+    keys: Vec<K>,
+    reader: MergeReader<T, S>,
+}
+
+impl<K, T, S: Settings> TopicReader<K, T, S>{
+    /// Subscribed topics.
+    #[inline]
+    pub fn keys(&self) -> &[K]{
+        &self.keys
+    }
+
+    /// See [MergeReader::iter].
+    #[inline]
+    pub fn iter(&mut self) -> TopicIter<'_, K, T, S>{
+        TopicIter{
+            keys: &self.keys,
+            iter: self.reader.iter(),
+        }
+    }
+}
+
+/// Consuming iterator of [TopicReader].
+pub struct TopicIter<'a, K, T, S: Settings>{
+    keys: &'a [K],
+    iter: MergeIter<'a, T, S>,
+}
+
+impl<'a, K, T, S: Settings> TopicIter<'a, K, T, S>{
+    /// Same as [next](LendingIterator::next), but also returns item's topic.
+    #[inline]
+    pub fn next_with_key(&mut self) -> Option<(&K, &T)>{
+        let (source, value) = self.iter.next_with_source()?;
+        Some((&self.keys[source], value))
+    }
+
+    /// Topics, whose reader [expired](crate::mpmc::EventReader::is_expired).
+    /// Iterator skips them.
+    #[inline]
+    pub fn expired_keys(&self) -> impl Iterator<Item = &K> + '_{
+        let keys = self.keys;
+        self.iter.expired_sources().map(move |source| &keys[source])
+    }
+
+    /// See [Iter::refresh](crate::mpmc::Iter::refresh).
+    #[inline]
+    pub fn refresh(&mut self){
+        self.iter.refresh()
+    }
+}
+
+impl<'a, K, T, S: Settings> LendingIterator for TopicIter<'a, K, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        self.iter.next()
+    }
+}
@@ -0,0 +1,118 @@
+use crate::event_reader::{LendingIterator, Lagged};
+use crate::mpmc::{EventQueue, EventReader, Iter, Settings};
+use crate::event_queue::EventQueue as BaseEventQueue;
+use crate::Position;
+use core::mem::ManuallyDrop;
+
+/// Reader, which does not hold queue's memory.
+///
+/// Only remembers [Position] between reads - it is not counted as queue reader, and chunks
+/// are reclaimed regardless of it. [iter](Self::iter) reads whatever is still retained,
+/// or reports [Lagged] with count of missed items. For best-effort taps (debug, monitoring)
+/// into production queue.
+///
+/// Each [iter](Self::iter) call subscribes under queue lock, and iterator holds queue's memory
+/// like ordinary [EventReader] - till drop. Without ordinary readers, queue keeps only the
+/// last chunk.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::{EventQueue, EventReader, WeakReader, Settings};
+/// # use rc_event_queue::Lagged;
+/// struct S{} impl Settings for S{
+///     const MIN_CHUNK_SIZE: u32 = 4;
+///     const MAX_CHUNK_SIZE: u32 = 4;
+/// }
+/// let event = EventQueue::<usize, S>::new();
+/// let mut reader = EventReader::new(&event);
+/// let mut tap = WeakReader::new(&event);
+///
+/// event.extend(0..12);
+/// reader.iter().for_each(|_|{});   // chunks freed - tap does not hold them
+///
+/// assert!(matches!(tap.iter(), Err(Lagged(8))));
+/// let mut sum = 0;
+/// tap.iter().unwrap().for_each(|value| sum += value);
+/// assert_eq!(sum, 8 + 9 + 10 + 11);
+/// ```
+pub struct WeakReader<T, S: Settings>{
+    event: EventQueue<T, S>,
+    position: Position,
+    /// Missed during last [WeakIter] - not reported yet.
+    missed: u64,
+    /// Only while [WeakIter] alive.
+    session: Option<EventReader<T, S>>,
+}
+
+impl<T, S: Settings> WeakReader<T, S>{
+    /// Starts from NOW, like [EventReader::new].
+    #[inline]
+    pub fn new(event_queue: &EventQueue<T, S>) -> Self {
+        let position = event_queue.0.subscribe_position(&mut event_queue.0.list.lock());
+        Self{event: event_queue.clone(), position, missed: 0, session: None}
+    }
+
+    /// Position of the next item to read.
+    #[inline]
+    pub fn position(&self) -> Position{
+        self.position
+    }
+
+    /// Iterator over retained items, from [position](Self::position).
+    ///
+    /// If some items were reclaimed (or truncated) before we read them - skips them, and returns
+    /// [Lagged] with their count. Next call will iterate from the first retained item.
+    /// Items skipped in the middle of previous iteration (see [EventReader::take_missed_count])
+    /// reported the same way.
+    pub fn iter(&mut self) -> Result<WeakIter<'_, T, S>, Lagged>{
+        if self.missed != 0 {
+            return Err(Lagged(core::mem::take(&mut self.missed)));
+        }
+
+        let (reader, skipped) = BaseEventQueue::subscribe_at(&self.event.0, &mut self.event.0.list.lock(), self.position);
+        let reader = EventReader{0: reader};
+        if skipped != 0 {
+            self.position = Position::from_sequence(self.position.sequence() + skipped);
+            return Err(Lagged(skipped));
+        }
+
+        let session: *mut Option<EventReader<T, S>> = &mut self.session;
+        let iter = unsafe{ (*session).insert(reader) }.iter();
+        Ok(WeakIter{
+            iter: ManuallyDrop::new(iter),
+            session,
+            position: &mut self.position,
+            missed: &mut self.missed
+        })
+    }
+}
+
+/// Consuming iterator of [WeakReader]. Same as [Iter], but also releases queue's memory on drop.
+pub struct WeakIter<'a, T, S: Settings>{
+    /// Borrows `session`.
+    iter: ManuallyDrop<Iter<'a, T, S>>,
+    session: *mut Option<EventReader<T, S>>,
+    position: &'a mut Position,
+    missed: &'a mut u64,
+}
+
+impl<'a, T, S: Settings> LendingIterator for WeakIter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        self.iter.next()
+    }
+}
+
+impl<'a, T, S: Settings> Drop for WeakIter<'a, T, S>{
+    fn drop(&mut self) {
+        unsafe{
+            ManuallyDrop::drop(&mut self.iter);
+            if let Some(mut reader) = (*self.session).take(){
+                *self.missed = reader.take_missed_count();
+                *self.position = reader.position();
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::sync::SettingsMutexGuard;
+use crate::event_queue::{EventQueue as BaseEventQueue, List};
+use crate::mpmc::{BS, DefaultSettings, EventQueue, Settings};
+use crate::AllocError;
+
+/// Holds [EventQueue] lock across many pushes. Created by [EventQueue::write_batch].
+///
+/// Pushed values published to readers once - on drop (and each time chunk is full).
+/// All other queue operations wait, while guard alive - keep it short.
+///
+/// Do not read queue from the same thread, while guard alive - reader's cleanup
+/// may need queue lock, and will dead-lock.
+///
+/// ```
+/// # use rc_event_queue::prelude::*;
+/// # use rc_event_queue::mpmc::{EventQueue, EventReader};
+/// let event = EventQueue::<usize>::new();
+/// let mut reader = EventReader::new(&event);
+/// {
+///     let mut batch = event.write_batch();
+///     for i in 0..1000 {
+///         batch.push(i);
+///     }
+/// }
+/// assert_eq!(reader.iter().next(), Some(&0));
+/// ```
+pub struct WriteGuard<'a, T, S: Settings = DefaultSettings>{
+    event_queue: &'a BaseEventQueue<T, BS<S>>,
+    list: SettingsMutexGuard<'a, BS<S>, List<T, BS<S>>>,
+}
+
+impl<'a, T, S: Settings> WriteGuard<'a, T, S>{
+    #[inline]
+    pub(crate) fn new(event_queue: &'a EventQueue<T, S>) -> Self{
+        Self{
+            event_queue: &event_queue.0,
+            list: event_queue.0.list.lock(),
+        }
+    }
+
+    /// Same as [EventQueue::push], but value not visible to readers until guard drop.
+    #[inline]
+    pub fn push(&mut self, value: T){
+        self.event_queue.push_unpublished(&mut self.list, value);
+    }
+
+    /// Same as [EventQueue::try_push], but value not visible to readers until guard drop.
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), AllocError<T>>{
+        self.event_queue.try_push_unpublished(&mut self.list, value)
+    }
+
+    /// Make values pushed so far visible to readers, without releasing lock.
+    #[inline]
+    pub fn publish(&mut self){
+        self.event_queue.publish(&mut self.list);
+    }
+}
+
+impl<'a, T, S: Settings> Extend<T> for WriteGuard<'a, T, S>{
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter{
+            self.push(value);
+        }
+    }
+}
+
+impl<'a, T, S: Settings> Drop for WriteGuard<'a, T, S>{
+    #[inline]
+    fn drop(&mut self) {
+        self.event_queue.publish(&mut self.list);
+    }
+}
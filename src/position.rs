@@ -0,0 +1,34 @@
+use crate::cursor::Cursor;
+use crate::event_queue::Settings;
+
+/// Position in queue, as sequence number of item. First pushed item have 0,
+/// next one - 1, and so on. Does not reset on clear/truncate.
+///
+/// Pointer-free - can be stored, compared, hashed and sent anywhere.
+/// Positions from different queues are not comparable (meaningfully).
+///
+/// With `serde` feature - serializable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position(u64);
+
+impl Position{
+    #[inline]
+    pub fn from_sequence(seq: u64) -> Self{
+        Self{0: seq}
+    }
+
+    #[inline]
+    pub fn sequence(self) -> u64{
+        self.0
+    }
+}
+
+impl<T, S: Settings> From<Cursor<T, S>> for Position{
+    /// Cursor's chunk must be alive.
+    #[inline]
+    fn from(cursor: Cursor<T, S>) -> Self {
+        let chunk = unsafe{&*cursor.chunk};
+        Self{0: chunk.first_seq() + cursor.index as u64}
+    }
+}
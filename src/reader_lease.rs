@@ -0,0 +1,123 @@
+// Shared state between TTL EventReader and EventQueue.
+//
+// Reader switches IDLE <-> READING around each read session (lock-free CAS).
+// Queue, under list lock, switches IDLE -> EXPIRED, if reader was not polled for `ttl`.
+// After that, queue marks reader's chunk as read on reader's behalf. Reader never touch chunks again.
+//
+// State and last poll time packed together, so queue's CAS fails if reader polled in between.
+//
+// Named readers have lease too - so queue can find them for diagnostics.
+//
+
+use std::time::{Duration, Instant};
+use crate::sync::{Ordering, AtomicPtr, AtomicU64};
+use crate::dynamic_chunk::DynamicChunk;
+use crate::event_queue::Settings;
+
+const IDLE    : u64 = 0;
+const READING : u64 = 1;
+const EXPIRED : u64 = 2;
+const STATE_MASK: u64 = 0b11;
+
+pub(crate) struct ReaderLease<T, S: Settings>{
+    ttl: Duration,
+    created_at: Instant,
+    /// nanoseconds since `created_at` << 2 | state
+    state: AtomicU64,
+    /// Chunk occupied by reader. Valid only in IDLE state.
+    chunk: AtomicPtr<DynamicChunk<T, S>>,
+    name: Option<String>,
+}
+
+impl<T, S: Settings> ReaderLease<T, S>{
+    pub fn new(
+        ttl: Duration,
+        chunk: *const DynamicChunk<T, S>,
+        name: Option<String>
+    ) -> Self{
+        Self{
+            ttl,
+            created_at: Instant::now(),
+            state: AtomicU64::new(IDLE),
+            chunk: AtomicPtr::new(chunk as *mut _),
+            name,
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> Option<&str>{
+        self.name.as_deref()
+    }
+
+    #[inline]
+    pub fn ttl(&self) -> Duration{
+        self.ttl
+    }
+
+    /// Returns false if expired.
+    #[inline]
+    pub fn begin_read(&self) -> bool{
+        let mut state = self.state.load(Ordering::Acquire);
+        loop{
+            debug_assert!(state & STATE_MASK != READING);
+            if state & STATE_MASK == EXPIRED{
+                return false;
+            }
+            match self.state.compare_exchange_weak(
+                state, state | READING, Ordering::AcqRel, Ordering::Acquire
+            ){
+                Ok(_) => return true,
+                Err(actual) => state = actual,
+            }
+        }
+    }
+
+    #[inline]
+    pub fn end_read(&self, chunk: *const DynamicChunk<T, S>){
+        self.chunk.store(chunk as *mut _, Ordering::Relaxed);
+        let elapsed = self.created_at.elapsed().as_nanos() as u64;
+        self.state.store(elapsed << 2 | IDLE, Ordering::Release);
+    }
+
+    #[inline]
+    pub fn is_expired(&self) -> bool{
+        self.state.load(Ordering::Acquire) & STATE_MASK == EXPIRED
+    }
+
+    /// Should be called under list lock.
+    ///
+    /// Chunk occupied by reader. None, if reader expired or reading now.
+    pub fn idle_chunk(&self) -> Option<*const DynamicChunk<T, S>>{
+        let state = self.state.load(Ordering::Acquire);
+        if state & STATE_MASK != IDLE{
+            return None;
+        }
+        // Even if reader leaves chunk right now - it can not be freed, while we're under lock.
+        Some(self.chunk.load(Ordering::Relaxed))
+    }
+
+    /// Should be called under list lock.
+    ///
+    /// Expires reader, not polled for ttl, or if `is_lagging(reader's chunk)`.
+    /// Returns chunk, occupied by reader, if reader just expired.
+    pub fn try_expire<F>(&self, now: Instant, is_lagging: F) -> Option<*mut DynamicChunk<T, S>>
+        where F: FnOnce(&DynamicChunk<T, S>) -> bool
+    {
+        let state = self.state.load(Ordering::Acquire);
+        if state & STATE_MASK != IDLE{
+            return None;
+        }
+
+        let last_poll = self.created_at + Duration::from_nanos(state >> 2);
+        if now.saturating_duration_since(last_poll) < self.ttl {
+            // Reader occupy chunk - it can not be freed, while we're under lock.
+            let chunk = unsafe{&*self.chunk.load(Ordering::Relaxed)};
+            if !is_lagging(chunk){
+                return None;
+            }
+        }
+
+        self.state.compare_exchange(state, EXPIRED, Ordering::AcqRel, Ordering::Acquire).ok()?;
+        Some(self.chunk.load(Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,106 @@
+//! OS-level readiness object - for epoll/mio/poll-based event loops.
+//!
+//! [Readiness] is file descriptor (one end of unix socket pair), which becomes readable,
+//! when any of armed readers have new items. Register it in your loop alongside sockets.
+//! Queues must have [Settings::NOTIFY](crate::mpmc::Settings::NOTIFY) enabled.
+//!
+//! ```
+//! # use rc_event_queue::prelude::*;
+//! # use rc_event_queue::mpmc::{EventQueue, EventReader, Settings};
+//! # use rc_event_queue::readiness::Readiness;
+//! # use std::os::unix::io::AsRawFd;
+//! struct S{} impl Settings for S{
+//!     const NOTIFY: bool = true;
+//! }
+//! let event = EventQueue::<usize, S>::new();
+//! let mut reader = EventReader::new(&event);
+//!
+//! let readiness = Readiness::new().unwrap();
+//! readiness.arm(&[&reader]).unwrap();
+//! let fd = readiness.as_raw_fd();     // register `fd` for read in epoll/mio/poll
+//!
+//! event.push(1);
+//! // ... `fd` is readable now
+//! reader.iter().for_each(|_|{});
+//! readiness.arm(&[&reader]).unwrap(); // before waiting again
+//! ```
+
+use crate::select::Selectable;
+use core::task::Waker;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::sync::Arc;
+use std::task::Wake;
+
+struct SocketWaker(UnixStream);
+impl Wake for SocketWaker{
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // WouldBlock - socket buffer full, so it is readable anyway.
+        let _ = (&self.0).write(&[1]);
+    }
+}
+
+/// File descriptor, readable while armed readers have new items.
+///
+/// Level-triggered - stays readable until next [arm](Self::arm).
+pub struct Readiness{
+    read: UnixStream,
+    waker: Waker,
+}
+
+impl Readiness{
+    pub fn new() -> io::Result<Self>{
+        let (read, write) = UnixStream::pair()?;
+        read.set_nonblocking(true)?;
+        write.set_nonblocking(true)?;
+        Ok(Self{
+            read,
+            waker: Waker::from(Arc::new(SocketWaker(write))),
+        })
+    }
+
+    /// Make descriptor non-readable, until any of `readers` have new items.
+    ///
+    /// If some of them already have - descriptor stays readable.
+    /// Call each time before waiting. Panics, if queue does not have `NOTIFY` setting.
+    pub fn arm(&self, readers: &[&dyn Selectable]) -> io::Result<()>{
+        let mut buf = [0u8; 64];
+        loop{
+            match (&self.read).read(&mut buf){
+                Ok(0) => break,
+                Ok(_) => continue,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        for reader in readers{
+            reader.register_waker(&self.waker);
+        }
+        // Writer could publish before we registered.
+        if readers.iter().any(|reader| reader.is_ready()){
+            self.waker.wake_by_ref();
+        }
+        Ok(())
+    }
+}
+
+impl AsRawFd for Readiness{
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.read.as_raw_fd()
+    }
+}
+
+impl AsFd for Readiness{
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.read.as_fd()
+    }
+}
@@ -0,0 +1,164 @@
+//! Wait for any of several [EventReader](crate::mpmc::EventReader)s to have new items.
+//!
+//! Queues must have [Settings::NOTIFY](crate::mpmc::Settings::NOTIFY) enabled -
+//! otherwise there is no one to wake waiting thread/task.
+//!
+//! ```
+//! # use rc_event_queue::prelude::*;
+//! # use rc_event_queue::mpmc::{EventQueue, EventReader, Settings};
+//! # use rc_event_queue::select::select;
+//! struct S{} impl Settings for S{
+//!     const NOTIFY: bool = true;
+//! }
+//! let events  = EventQueue::<usize, S>::new();
+//! let commands = EventQueue::<usize, S>::new();
+//! let mut events_reader  = EventReader::new(&events);
+//! let mut commands_reader = EventReader::new(&commands);
+//!
+//! commands.push(1);
+//! match select(&[&events_reader, &commands_reader]){
+//!     0 => { events_reader.iter().for_each(|_|{}); }
+//!     1 => { assert_eq!(commands_reader.iter().next(), Some(&1)); }
+//!     _ => unreachable!()
+//! }
+//! ```
+//!
+//! Registered wakers are stored in queue until next publish. Only one copy of the same
+//! waker is stored.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::sync::Arc;
+use std::task::Wake;
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+/// Reader, which can be waited on.
+pub trait Selectable{
+    /// Have unread items, or expired.
+    fn is_ready(&self) -> bool;
+
+    /// Wake `waker` on next publish. Panics, if queue does not have `NOTIFY` setting.
+    fn register_waker(&self, waker: &Waker);
+}
+
+impl<T, S: crate::mpmc::Settings> Selectable for crate::mpmc::EventReader<T, S>{
+    #[inline]
+    fn is_ready(&self) -> bool {
+        self.has_new() || self.is_expired()
+    }
+
+    #[inline]
+    fn register_waker(&self, waker: &Waker) {
+        self.register_waker(waker)
+    }
+}
+
+impl<T, S: crate::spmc::Settings> Selectable for crate::spmc::EventReader<T, S>{
+    #[inline]
+    fn is_ready(&self) -> bool {
+        self.has_new() || self.is_expired()
+    }
+
+    #[inline]
+    fn register_waker(&self, waker: &Waker) {
+        self.register_waker(waker)
+    }
+}
+
+struct ThreadWaker(Thread);
+impl Wake for ThreadWaker{
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+thread_local! {
+    // Same waker for all selects on this thread - queue stores it only once.
+    static THREAD_WAKER: Waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+}
+
+#[inline]
+fn find_ready(readers: &[&dyn Selectable]) -> Option<usize>{
+    readers.iter().position(|reader| reader.is_ready())
+}
+
+fn select_impl(readers: &[&dyn Selectable], deadline: Option<Instant>) -> Option<usize>{
+    assert!(!readers.is_empty(), "Nothing to select from.");
+    THREAD_WAKER.with(|waker| loop {
+        if let Some(index) = find_ready(readers){
+            return Some(index);
+        }
+        for reader in readers{
+            reader.register_waker(waker);
+        }
+        // Writer could publish before we registered.
+        if let Some(index) = find_ready(readers){
+            return Some(index);
+        }
+
+        match deadline{
+            None => thread::park(),
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline{
+                    return None;
+                }
+                thread::park_timeout(deadline - now);
+            }
+        }
+    })
+}
+
+/// Block current thread, until one of `readers` is ready. Returns its index.
+///
+/// Ready reader have new items, or [expired](crate::mpmc::EventReader::is_expired).
+/// If several readers are ready - returns the first one.
+///
+/// Panics, if `readers` is empty.
+#[inline]
+pub fn select(readers: &[&dyn Selectable]) -> usize{
+    select_impl(readers, None).unwrap()
+}
+
+/// Same as [select], but gives up after `timeout`.
+#[inline]
+pub fn select_timeout(readers: &[&dyn Selectable], timeout: Duration) -> Option<usize>{
+    select_impl(readers, Some(Instant::now() + timeout))
+}
+
+/// Async version of [select]. Executor agnostic.
+///
+/// Panics, if `readers` is empty.
+#[inline]
+pub fn select_async<'a>(readers: &'a [&'a dyn Selectable]) -> SelectFuture<'a>{
+    assert!(!readers.is_empty(), "Nothing to select from.");
+    SelectFuture{readers}
+}
+
+/// Returned by [select_async]. Resolves to ready reader index.
+pub struct SelectFuture<'a>{
+    readers: &'a [&'a dyn Selectable]
+}
+
+impl<'a> Future for SelectFuture<'a>{
+    type Output = usize;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(index) = find_ready(self.readers){
+            return Poll::Ready(index);
+        }
+        for reader in self.readers{
+            reader.register_waker(cx.waker());
+        }
+        match find_ready(self.readers){
+            Some(index) => Poll::Ready(index),
+            None => Poll::Pending
+        }
+    }
+}
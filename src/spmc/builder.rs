@@ -0,0 +1,70 @@
+use core::marker::PhantomData;
+use crate::{CleanupMode, GrowthStrategy};
+use crate::event_queue::{EventQueue as BaseEventQueue, Config};
+use crate::spmc::{BS, EventQueue, RuntimeSettings};
+
+/// See [mpmc](crate::mpmc::Builder) documentation.
+pub struct Builder<T>{
+    config: Config,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Builder<T>{
+    #[inline]
+    pub fn new() -> Self{
+        Self{
+            config: Config::from_settings::<BS<RuntimeSettings>>(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// See [Settings::MIN_CHUNK_SIZE](crate::spmc::Settings::MIN_CHUNK_SIZE).
+    #[inline]
+    pub fn min_chunk_size(mut self, size: u32) -> Self{
+        self.config.min_chunk_size = size;
+        self
+    }
+
+    /// See [Settings::MAX_CHUNK_SIZE](crate::spmc::Settings::MAX_CHUNK_SIZE).
+    #[inline]
+    pub fn max_chunk_size(mut self, size: u32) -> Self{
+        self.config.max_chunk_size = size;
+        self
+    }
+
+    /// See [Settings::CLEANUP](crate::spmc::Settings::CLEANUP).
+    #[inline]
+    pub fn cleanup(mut self, cleanup: CleanupMode) -> Self{
+        self.config.cleanup = cleanup;
+        self
+    }
+
+    /// See [Settings::GROWTH](crate::spmc::Settings::GROWTH).
+    #[inline]
+    pub fn growth(mut self, growth: GrowthStrategy) -> Self{
+        self.config.growth = growth;
+        self
+    }
+
+    /// Panics, if min_chunk_size > max_chunk_size, or cleanup is [CleanupMode::OnChunkRead].
+    pub fn build(self) -> EventQueue<T, RuntimeSettings>{
+        let base = BaseEventQueue::<T, BS<RuntimeSettings>>::with_config(
+            self.config, self.config.min_chunk_size
+        );
+        EventQueue::from_base(base)
+    }
+}
+
+impl<T> EventQueue<T, RuntimeSettings>{
+    #[inline]
+    pub fn builder() -> Builder<T>{
+        Builder::new()
+    }
+}
+
+impl<T> Default for Builder<T>{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,59 @@
+use crate::event_reader::ReaderExpired;
+use crate::spmc::{EventQueue, EventReader, Iter, Settings};
+use crate::Position;
+
+/// See [mpmc](crate::mpmc::CommittedReader) documentation.
+pub struct CommittedReader<T, S: Settings>{
+    committed: EventReader<T, S>,
+    pending: Option<EventReader<T, S>>,
+}
+
+impl<T, S: Settings> CommittedReader<T, S>{
+    #[inline]
+    pub fn new(event_queue: &mut EventQueue<T, S>) -> Self {
+        Self::from_reader(EventReader::new(event_queue))
+    }
+
+    #[inline]
+    pub fn from_reader(reader: EventReader<T, S>) -> Self {
+        Self{committed: reader, pending: None}
+    }
+
+    #[inline]
+    pub fn into_reader(self) -> EventReader<T, S>{
+        self.committed
+    }
+
+    #[inline]
+    pub fn iter(&mut self) -> Iter<'_, T, S>{
+        match self.committed.try_fork(){
+            Ok(pending) => self.pending.insert(pending).iter(),
+            Err(_) => {
+                self.pending = None;
+                self.committed.iter()
+            }
+        }
+    }
+
+    pub fn try_iter(&mut self) -> Result<Iter<'_, T, S>, ReaderExpired>{
+        let pending = self.committed.try_fork()?;
+        self.pending.insert(pending).try_iter()
+    }
+
+    #[inline]
+    pub fn commit(&mut self){
+        if let Some(pending) = self.pending.take(){
+            self.committed = pending;
+        }
+    }
+
+    #[inline]
+    pub fn position(&self) -> Position{
+        self.committed.position()
+    }
+
+    #[inline]
+    pub fn is_expired(&self) -> bool{
+        self.committed.is_expired()
+    }
+}
@@ -1,15 +1,24 @@
-use std::pin::Pin;
 use crate::sync::Arc;
-use crate::event_queue::{EventQueue as BaseEventQueue, List};
+use crate::event_queue::{EventQueue as BaseEventQueue, IntoIter as BaseIntoIter, List};
 use crate::spmc::{BS, DefaultSettings, Settings};
-use crate::CleanupMode;
+use crate::{AllocError, CleanupMode, Position, QueueDiagnostics, Stats};
+use crate::utils::DebugFn;
+use core::ops::Range;
+use core::fmt;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::time::Duration;
+#[cfg(feature = "wal")]
+use crate::wal::WalSink;
+#[cfg(feature = "wal")]
+use std::io;
 
 /// See [mpmc](crate::mpmc::EventQueue) documentation.
 ///
 /// Only [cleanup](EventQueue::cleanup) and `unsubscribe`(on `EventReader::drop`) are synchronized.
 /// Everything else - overhead free.
 ///
-/// Insert performance in the `std::vec::Vec` league.
+/// Insert performance in the `alloc::vec::Vec` league.
 pub struct EventQueue<T, S: Settings = DefaultSettings>(
     pub(crate) Arc<BaseEventQueue<T, BS<S>>>
 );
@@ -23,12 +32,13 @@ impl<T, S: Settings> EventQueue<T, S>{
     // Hide for a while.
     #[inline]
     fn with_capacity(new_capacity: u32) -> Self {
-        assert!(S::CLEANUP!=CleanupMode::OnChunkRead, "CleanupMode::OnChunkRead is not valid mode for spmc");
-        let base = BaseEventQueue::<T, BS<S>>::with_capacity(new_capacity);
-        unsafe {
-            let base_arc = Pin::into_inner_unchecked(base);
-            Self{0: base_arc}
-        }
+        Self::from_base(BaseEventQueue::<T, BS<S>>::with_capacity(new_capacity))
+    }
+
+    #[inline]
+    pub(crate) fn from_base(base: Arc<BaseEventQueue<T, BS<S>>>) -> Self {
+        assert!(base.cleanup_mode()!=CleanupMode::OnChunkRead, "CleanupMode::OnChunkRead is not valid mode for spmc");
+        Self{0: base}
     }
 
     // without lock
@@ -38,6 +48,7 @@ impl<T, S: Settings> EventQueue<T, S>{
     }
     // should be &mut self ... But... self-references comes later...
     #[inline]
+    #[allow(clippy::mut_from_ref)]
     pub(crate) fn get_list_mut(&self) -> &mut List<T, BS<S>> {
         unsafe{ &mut *self.0.list.data_ptr() }
     }
@@ -48,6 +59,13 @@ impl<T, S: Settings> EventQueue<T, S>{
         self.0.push(list, value);
     }
 
+    /// See [mpmc](crate::mpmc::EventQueue::try_push) documentation.
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), AllocError<T>>{
+        let list = self.get_list_mut();
+        self.0.try_push(list, value)
+    }
+
     #[inline]
     pub fn extend<I>(&mut self, iter: I)
         where I: IntoIterator<Item = T>
@@ -55,6 +73,56 @@ impl<T, S: Settings> EventQueue<T, S>{
         self.0.extend(self.get_list_mut(), iter);
     }
 
+    /// See [mpmc](crate::mpmc::EventQueue::push_with_position) documentation.
+    #[inline]
+    pub fn push_with_position(&mut self, value: T) -> Option<Position>{
+        let list = self.get_list_mut();
+        self.0.push_with_position(list, value)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::extend_with_position) documentation.
+    #[inline]
+    pub fn extend_with_position<I>(&mut self, iter: I) -> Range<Position>
+        where I: IntoIterator<Item = T>
+    {
+        self.0.extend_with_position(self.get_list_mut(), iter)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::end_position) documentation.
+    #[inline]
+    pub fn end_position(&self) -> Position{
+        self.0.end_position(self.get_list())
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::push_repeated) documentation.
+    #[inline]
+    pub fn push_repeated(&mut self, value: T, n: usize)
+        where T: Clone
+    {
+        self.0.extend(self.get_list_mut(), core::iter::repeat_n(value, n));
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::extend_from_slice) documentation.
+    #[inline]
+    pub fn extend_from_slice(&mut self, values: &[T])
+        where T: Copy
+    {
+        self.0.extend_from_slice(self.get_list_mut(), values);
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::try_extend) documentation.
+    #[inline]
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), AllocError<T>>
+        where I: IntoIterator<Item = T>
+    {
+        self.0.try_extend(self.get_list_mut(), iter)
+    }
+
+    #[inline]
+    pub fn flush(&mut self){
+        self.0.flush(self.get_list_mut());
+    }
+
     #[inline]
     pub fn cleanup(&mut self){
         self.0.cleanup();
@@ -65,25 +133,214 @@ impl<T, S: Settings> EventQueue<T, S>{
         self.0.clear(self.get_list_mut());
     }
 
+    /// See [mpmc](crate::mpmc::EventQueue::clear_now) documentation.
+    #[inline]
+    pub fn clear_now(&mut self){
+        self.0.clear_now(self.get_list_mut());
+    }
+
     #[inline]
     pub fn truncate_front(&mut self, len: usize){
         self.0.truncate_front(self.get_list_mut(), len);
     }
 
+    /// See [mpmc](crate::mpmc::EventQueue::truncate_older_than) documentation.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn truncate_older_than(&mut self, age: Duration){
+        self.0.truncate_older_than(self.get_list_mut(), age);
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::compact_by_key) documentation.
+    pub fn compact_by_key<K, F>(&mut self, key: F) -> usize
+        where T: Clone, K: Ord, F: FnMut(&T) -> K
+    {
+        self.0.compact_by_key(self.get_list_mut(), key)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::cleanup_force) documentation.
+    #[inline]
+    pub fn cleanup_force(&mut self, keep_chunks: usize){
+        self.0.cleanup_force(self.get_list_mut(), keep_chunks);
+    }
+
     #[inline]
     pub fn change_chunk_capacity(&mut self, new_capacity: u32){
         self.0.change_chunk_capacity(self.get_list_mut(), new_capacity);
     }
 
+    /// See [mpmc](crate::mpmc::EventQueue::resize_to_fit) documentation.
+    #[inline]
+    pub fn resize_to_fit(&mut self){
+        self.0.resize_to_fit(self.get_list_mut());
+    }
+
     #[inline]
     pub fn total_capacity(&self) -> usize{
         self.0.total_capacity(self.get_list())
     }
 
+    /// See [mpmc](crate::mpmc::EventQueue::len) documentation.
+    #[inline]
+    pub fn len(&self) -> usize{
+        self.0.len_lock_free()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::chunks_count) documentation.
+    #[inline]
+    pub fn chunks_count(&self) -> usize{
+        self.0.chunks_count()
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::bytes_allocated) documentation.
+    #[inline]
+    pub fn bytes_allocated(&self) -> usize{
+        self.0.bytes_allocated(self.get_list())
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::reserve) documentation.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize){
+        self.0.reserve(self.get_list_mut(), additional);
+    }
+
     #[inline]
     pub fn chunk_capacity(&self) -> usize{
         self.0.chunk_capacity(self.get_list())
     }
+
+    /// See [mpmc](crate::mpmc::EventQueue::try_into_iter) documentation.
+    pub fn try_into_iter(mut self) -> Result<IntoIter<T, S>, Self>{
+        // Weak handles (cleanup thread) count too - they can upgrade at any moment.
+        if Arc::get_mut(&mut self.0).is_none() {
+            return Err(self);
+        }
+        Ok(IntoIter{0: BaseIntoIter::new(self.0)})
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::stats) documentation.
+    #[inline]
+    pub fn stats(&self) -> Stats{
+        self.0.stats()
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::write_stats) documentation.
+    #[inline]
+    pub fn write_stats(&self, w: &mut impl core::fmt::Write) -> core::fmt::Result{
+        // readers_count changed by unsubscribe under lock.
+        let list = self.0.list.lock();
+        self.0.write_stats(&list, w)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::record_metrics) documentation.
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn record_metrics(&self, name: &str){
+        let list = self.0.list.lock();
+        self.0.record_metrics(&list, name);
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::snapshot_vec) documentation.
+    #[inline]
+    pub fn snapshot_vec(&self) -> Vec<T>
+        where T: Clone
+    {
+        let list = self.0.list.lock();
+        self.0.snapshot_vec(&list)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::snapshot_serialize) documentation.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn snapshot_serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+        where T: serde::Serialize, Ser: serde::Serializer
+    {
+        let list = self.0.list.lock();
+        self.0.snapshot_serialize(&list, serializer)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::snapshot_deserialize) documentation.
+    #[cfg(feature = "serde")]
+    pub fn snapshot_deserialize<'de, D>(deserializer: D) -> Result<Self, D::Error>
+        where T: serde::Deserialize<'de>, D: serde::Deserializer<'de>
+    {
+        let this = Self::new();
+        let list = this.get_list_mut();
+        this.0.snapshot_deserialize(list, deserializer)?;
+        Ok(this)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::set_wal_sink) documentation.
+    #[cfg(feature = "wal")]
+    #[inline]
+    pub fn set_wal_sink(&mut self, sink: Option<Box<dyn WalSink<T>>>) -> Option<Box<dyn WalSink<T>>>{
+        let list = self.get_list_mut();
+        self.0.set_wal_sink(list, sink)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::sync_wal) documentation.
+    #[cfg(feature = "wal")]
+    #[inline]
+    pub fn sync_wal(&mut self) -> io::Result<()>{
+        let list = self.get_list_mut();
+        self.0.sync_wal(list)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::take_wal_error) documentation.
+    #[cfg(feature = "wal")]
+    #[inline]
+    pub fn take_wal_error(&mut self) -> Option<io::Error>{
+        let list = self.get_list_mut();
+        self.0.take_wal_error(list)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::diagnostics) documentation.
+    #[inline]
+    pub fn diagnostics(&self) -> QueueDiagnostics{
+        let list = self.0.list.lock();
+        self.0.diagnostics(&list)
+    }
+
+    /// See [mpmc](crate::mpmc::EventQueue::fmt_chunks) documentation.
+    #[inline]
+    pub fn fmt_chunks(&self) -> impl fmt::Debug + '_{
+        DebugFn(move |f: &mut fmt::Formatter<'_>| {
+            let list = self.0.list.lock();
+            self.0.fmt_chunks(&list, f)
+        })
+    }
+}
+
+impl<T, S: Settings> fmt::Debug for EventQueue<T, S>{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = self.0.list.lock();
+        self.0.fmt_debug(&list, f)
+    }
+}
+
+impl<T, S: Settings> Extend<T> for EventQueue<T, S>{
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        EventQueue::extend(self, iter);
+    }
+}
+
+/// See [mpmc](crate::mpmc::IntoIter) documentation.
+pub struct IntoIter<T, S: Settings>(BaseIntoIter<T, BS<S>>);
+impl<T, S: Settings> Iterator for IntoIter<T, S>{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.0.next()
+    }
 }
 
-unsafe impl<T, S: Settings> Send for EventQueue<T, S>{}
+// See mpmc::EventQueue.
+unsafe impl<T: Send + Sync, S: Settings> Send for EventQueue<T, S>{}
+unsafe impl<T: Send, S: Settings> Send for IntoIter<T, S>{}
@@ -1,15 +1,53 @@
 // new-type EventReader, mostly to hide `BS`
 
-use crate::event_reader::{EventReader as BaseEventReader, LendingIterator};
+use crate::event_reader::{EventReader as BaseEventReader, LendingIterator, ReaderExpired, ReaderOptions};
+use crate::event_queue::EventQueue as BaseEventQueue;
 use crate::event_reader::Iter as BaseIter;
+use crate::event_reader::PeekIter as BasePeekIter;
+use crate::event_reader::Latest as BaseLatest;
+use alloc::vec::Vec;
 use crate::spmc::{BS, EventQueue, Settings};
+use crate::Position;
+use core::ops::Deref;
+use core::task::{Context, Poll, Waker};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
 ///  See [mpmc](crate::mpmc::EventReader) documentation.
-pub struct EventReader<T, S: Settings>(BaseEventReader<T, BS<S>>);
+pub struct EventReader<T, S: Settings>(pub(crate) BaseEventReader<T, BS<S>>);
 impl<T, S: Settings> EventReader<T, S>{
     #[inline]
     pub fn new(event_queue: &mut EventQueue<T, S>) -> Self {
-        Self{0: event_queue.0.subscribe(event_queue.get_list_mut())}
+        Self{0: BaseEventQueue::subscribe(&event_queue.0, event_queue.get_list_mut())}
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::with_ttl) documentation.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn with_ttl(event_queue: &mut EventQueue<T, S>, ttl: Duration) -> Self {
+        Self{0: BaseEventQueue::subscribe_with_ttl(&event_queue.0, event_queue.get_list_mut(), ttl)}
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::new_named) documentation.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn new_named(event_queue: &mut EventQueue<T, S>, name: impl Into<String>) -> Self {
+        Self{0: BaseEventQueue::subscribe_named(&event_queue.0, event_queue.get_list_mut(), name.into())}
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::with_options) documentation.
+    #[inline]
+    pub fn with_options(event_queue: &mut EventQueue<T, S>, options: ReaderOptions) -> Self {
+        Self{0: BaseEventQueue::subscribe_with_options(&event_queue.0, event_queue.get_list_mut(), options)}
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::new_batch) documentation.
+    #[inline]
+    pub fn new_batch(event_queue: &mut EventQueue<T, S>, count: u32) -> Vec<Self> {
+        BaseEventQueue::subscribe_batch(&event_queue.0, event_queue.get_list_mut(), count)
+            .into_iter()
+            .map(|reader| Self{0: reader})
+            .collect()
     }
 
     #[inline]
@@ -18,13 +56,168 @@ impl<T, S: Settings> EventReader<T, S>{
     }
 
     #[inline]
-    pub fn iter(&mut self) -> Iter<T, S>{
+    pub fn iter(&mut self) -> Iter<'_, T, S>{
         Iter{ 0: self.0.iter() }
     }
+
+    #[inline]
+    pub fn try_iter(&mut self) -> Result<Iter<'_, T, S>, ReaderExpired>{
+        Ok(Iter{ 0: self.0.try_iter()? })
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::peek_iter) documentation.
+    #[inline]
+    pub fn peek_iter(&mut self) -> PeekIter<'_, T, S>{
+        PeekIter{ 0: self.0.peek_iter() }
+    }
+
+    #[inline]
+    pub fn try_peek_iter(&mut self) -> Result<PeekIter<'_, T, S>, ReaderExpired>{
+        Ok(PeekIter{ 0: self.0.try_peek_iter()? })
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::position) documentation.
+    #[inline]
+    pub fn position(&self) -> Position{
+        self.0.position()
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::take_missed_count) documentation.
+    #[inline]
+    pub fn take_missed_count(&mut self) -> u64{
+        self.0.take_missed_count()
+    }
+
+    #[inline]
+    pub fn fork(&self) -> Self{
+        Self{0: self.0.fork()}
+    }
+
+    #[inline]
+    pub fn try_fork(&self) -> Result<Self, ReaderExpired>{
+        Ok(Self{0: self.0.try_fork()?})
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::latest) documentation.
+    #[inline]
+    pub fn latest(&mut self) -> Option<Latest<'_, T, S>>{
+        Some(Latest{ 0: self.0.latest()? })
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::skip_to_end) documentation.
+    #[inline]
+    pub fn skip_to_end(&mut self) -> usize{
+        self.0.skip_to_end()
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::lag) documentation.
+    #[inline]
+    pub fn lag(&self) -> usize{
+        self.0.lag()
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::has_new) documentation.
+    #[inline]
+    pub fn has_new(&self) -> bool{
+        self.0.has_new()
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::unread_estimate) documentation.
+    #[inline]
+    pub fn unread_estimate(&self) -> usize{
+        self.0.unread_estimate()
+    }
+
+    #[inline]
+    pub fn is_expired(&self) -> bool{
+        self.0.is_expired()
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::name) documentation.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn name(&self) -> Option<&str>{
+        self.0.name()
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::register_waker) documentation.
+    #[inline]
+    pub fn register_waker(&self, waker: &Waker){
+        self.0.register_waker(waker)
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::try_recv) documentation.
+    #[inline]
+    pub fn try_recv(&mut self) -> Result<Option<T>, ReaderExpired>
+        where T: Clone
+    {
+        self.0.try_recv()
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::poll_recv) documentation.
+    #[inline]
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, ReaderExpired>>
+        where T: Clone
+    {
+        self.0.poll_recv(cx)
+    }
+
+    /// See [mpmc](crate::mpmc::EventReader::recv) documentation.
+    #[inline]
+    pub async fn recv(&mut self) -> Result<T, ReaderExpired>
+        where T: Clone
+    {
+        self.0.recv().await
+    }
+}
+
+/// See [mpmc](crate::mpmc::Latest) documentation.
+pub struct Latest<'a, T, S: Settings> (BaseLatest<'a, T, BS<S>>);
+impl<'a, T, S: Settings> Deref for Latest<'a, T, S>{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// See [mpmc](crate::mpmc::PeekIter) documentation.
+pub struct PeekIter<'a, T, S: Settings> (BasePeekIter<'a, T, BS<S>>);
+impl <'a, T, S: Settings> LendingIterator for PeekIter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        self.0.next()
+    }
 }
 
 ///  See [mpmc](crate::mpmc::Iter) documentation.
 pub struct Iter<'a, T, S: Settings> (BaseIter<'a, T, BS<S>>);
+impl <'a, T, S: Settings> Iter<'a, T, S>{
+    #[inline]
+    pub fn peek(&mut self) -> Option<&T> {
+        self.0.peek()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn next_timestamped(&mut self) -> Option<(&T, Instant)> {
+        self.0.next_timestamped()
+    }
+
+    #[inline]
+    pub fn refresh(&mut self){
+        self.0.refresh()
+    }
+
+    /// See [mpmc](crate::mpmc::Iter::rollback) documentation.
+    #[inline]
+    pub fn rollback(self){
+        self.0.rollback()
+    }
+}
 impl <'a, T, S: Settings> LendingIterator for Iter<'a, T, S>{
     type ItemValue = T;
 
@@ -32,4 +225,41 @@ impl <'a, T, S: Settings> LendingIterator for Iter<'a, T, S>{
     fn next(&mut self) -> Option<&Self::ItemValue> {
         self.0.next()
     }
-}
\ No newline at end of file
+
+    #[inline]
+    fn for_each<F>(self, f: F)
+        where F: FnMut(&Self::ItemValue)
+    {
+        self.0.for_each(f)
+    }
+
+    #[inline]
+    fn fold<B, F>(self, init: B, f: F) -> B
+        where F: FnMut(B, &Self::ItemValue) -> B
+    {
+        self.0.fold(init, f)
+    }
+
+    #[inline]
+    fn try_for_each<F, E>(&mut self, f: F) -> Result<(), E>
+        where F: FnMut(&Self::ItemValue) -> Result<(), E>
+    {
+        self.0.try_for_each(f)
+    }
+}
+
+/// Requires `streaming_iterator` feature. See [mpmc](crate::mpmc::Iter) implementation notes.
+#[cfg(feature = "streaming_iterator")]
+impl <'a, T, S: Settings> streaming_iterator::StreamingIterator for Iter<'a, T, S>{
+    type Item = T;
+
+    #[inline]
+    fn advance(&mut self) {
+        streaming_iterator::StreamingIterator::advance(&mut self.0)
+    }
+
+    #[inline]
+    fn get(&self) -> Option<&T> {
+        streaming_iterator::StreamingIterator::get(&self.0)
+    }
+}
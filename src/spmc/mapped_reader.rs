@@ -0,0 +1,103 @@
+use crate::event_reader::{LendingIterator, ReaderExpired};
+use crate::spmc::{EventQueue, EventReader, Iter, Settings};
+use core::marker::PhantomData;
+
+/// See [mpmc](crate::mpmc::MappedReader) documentation.
+pub struct MappedReader<T, U, S: Settings, F>{
+    reader: EventReader<T, S>,
+    map: F,
+    phantom: PhantomData<fn(&T) -> &U>,
+}
+
+impl<T, U, S: Settings, F: Fn(&T) -> Option<&U>> MappedReader<T, U, S, F>{
+    #[inline]
+    pub fn new(event_queue: &mut EventQueue<T, S>, map: F) -> Self {
+        Self::from_reader(EventReader::new(event_queue), map)
+    }
+
+    #[inline]
+    pub fn from_reader(reader: EventReader<T, S>, map: F) -> Self {
+        Self{reader, map, phantom: PhantomData}
+    }
+
+    #[inline]
+    pub fn into_reader(self) -> EventReader<T, S>{
+        self.reader
+    }
+
+    #[inline]
+    pub fn iter(&mut self) -> MappedIter<'_, T, U, S, F>{
+        MappedIter{iter: self.reader.iter(), map: &self.map, phantom: PhantomData}
+    }
+
+    #[inline]
+    pub fn try_iter(&mut self) -> Result<MappedIter<'_, T, U, S, F>, ReaderExpired>{
+        Ok(MappedIter{iter: self.reader.try_iter()?, map: &self.map, phantom: PhantomData})
+    }
+
+    #[inline]
+    pub fn is_expired(&self) -> bool{
+        self.reader.is_expired()
+    }
+}
+
+/// See [mpmc](crate::mpmc::MappedIter) documentation.
+pub struct MappedIter<'a, T, U, S: Settings, F>{
+    iter: Iter<'a, T, S>,
+    map: &'a F,
+    phantom: PhantomData<fn(&T) -> &U>,
+}
+
+impl<'a, T, U, S: Settings, F: Fn(&T) -> Option<&U>> MappedIter<'a, T, U, S, F>{
+    #[inline]
+    pub fn refresh(&mut self){
+        self.iter.refresh()
+    }
+}
+
+impl<'a, T, U, S: Settings, F: Fn(&T) -> Option<&U>> LendingIterator for MappedIter<'a, T, U, S, F>{
+    type ItemValue = U;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        // Projection called twice for returned item - borrow checker does not allow
+        // to return from loop, which continues with the same borrow.
+        loop{
+            let is_some = (self.map)(self.iter.peek()?).is_some();
+            if is_some{
+                return (self.map)(self.iter.next()?);
+            }
+            self.iter.next();
+        }
+    }
+
+    #[inline]
+    fn for_each<G>(self, mut f: G)
+        where G: FnMut(&Self::ItemValue)
+    {
+        let map = self.map;
+        self.iter.for_each(|value| if let Some(value) = map(value) { f(value) })
+    }
+
+    #[inline]
+    fn fold<B, G>(self, init: B, mut f: G) -> B
+        where G: FnMut(B, &Self::ItemValue) -> B
+    {
+        let map = self.map;
+        self.iter.fold(init, |acc, value| match map(value){
+            Some(value) => f(acc, value),
+            None => acc
+        })
+    }
+
+    #[inline]
+    fn try_for_each<G, E>(&mut self, mut f: G) -> Result<(), E>
+        where G: FnMut(&Self::ItemValue) -> Result<(), E>
+    {
+        let map = self.map;
+        self.iter.try_for_each(|value| match map(value){
+            Some(value) => f(value),
+            None => Ok(())
+        })
+    }
+}
@@ -0,0 +1,65 @@
+use crate::merge_reader::{MergeReader as BaseMergeReader, MergeIter as BaseMergeIter};
+use crate::event_reader::LendingIterator;
+use crate::spmc::{BS, EventReader, Settings};
+use crate::MergeOrder;
+use alloc::vec::Vec;
+
+/// See [mpmc](crate::mpmc::MergeReader) documentation.
+pub struct MergeReader<T, S: Settings>(BaseMergeReader<T, BS<S>>);
+impl<T, S: Settings> MergeReader<T, S>{
+    /// See [mpmc](crate::mpmc::MergeReader::new) documentation.
+    #[inline]
+    pub fn new(readers: Vec<EventReader<T, S>>, order: MergeOrder) -> Self{
+        Self{0: BaseMergeReader::new(readers.into_iter().map(|reader| reader.0).collect(), order)}
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize{
+        self.0.readers().len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool{
+        self.0.readers().is_empty()
+    }
+
+    #[inline]
+    pub fn into_readers(self) -> Vec<EventReader<T, S>>{
+        self.0.into_readers().into_iter().map(|reader| EventReader{0: reader}).collect()
+    }
+
+    /// See [mpmc](crate::mpmc::MergeReader::iter) documentation.
+    #[inline]
+    pub fn iter(&mut self) -> MergeIter<'_, T, S>{
+        MergeIter{0: self.0.iter()}
+    }
+}
+
+/// See [mpmc](crate::mpmc::MergeIter) documentation.
+pub struct MergeIter<'a, T, S: Settings>(BaseMergeIter<'a, T, BS<S>>);
+impl<'a, T, S: Settings> MergeIter<'a, T, S>{
+    #[inline]
+    pub fn next_with_source(&mut self) -> Option<(usize, &T)>{
+        self.0.next_with_source()
+    }
+
+    /// See [mpmc](crate::mpmc::MergeIter::expired_sources) documentation.
+    #[inline]
+    pub fn expired_sources(&self) -> impl Iterator<Item = usize> + '_{
+        self.0.expired_sources()
+    }
+
+    #[inline]
+    pub fn refresh(&mut self){
+        self.0.refresh()
+    }
+}
+
+impl<'a, T, S: Settings> LendingIterator for MergeIter<'a, T, S>{
+    type ItemValue = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&Self::ItemValue> {
+        self.0.next()
+    }
+}
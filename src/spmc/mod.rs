@@ -7,23 +7,83 @@
 
 mod event_queue;
 mod event_reader;
+mod builder;
+mod merge_reader;
+mod filtered_reader;
+mod mapped_reader;
+mod committed_reader;
 
-use std::marker::PhantomData;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::time::Duration;
+#[cfg(feature = "std")]
+use crate::StaleReaderPolicy;
 use crate::event_queue::Settings as BaseSettings;
-use crate::CleanupMode;
+use crate::{CleanupMode, GrowthStrategy, FreedChunkInfo, ChunkAllocator, LockBackend};
 
 pub use event_queue::*;
 pub use event_reader::*;
+pub use builder::*;
+pub use merge_reader::*;
+pub use filtered_reader::*;
+pub use mapped_reader::*;
+pub use committed_reader::*;
 
 pub trait Settings{
     const MIN_CHUNK_SIZE : u32 = 4;
     const MAX_CHUNK_SIZE : u32 = 4096;
     const CLEANUP: CleanupMode = CleanupMode::OnNewChunk;
+    /// See [mpmc](crate::mpmc::Settings::GROWTH) documentation.
+    const GROWTH: GrowthStrategy = GrowthStrategy::Default;
+    /// See [mpmc](crate::mpmc::Settings::EXTEND_SIZE_HINT) documentation.
+    const EXTEND_SIZE_HINT: bool = false;
+    /// See [mpmc](crate::mpmc::Settings::PUBLISH_BATCH) documentation.
+    const PUBLISH_BATCH: u32 = 1;
+    /// See [mpmc](crate::mpmc::Settings::STATS) documentation.
+    const STATS: bool = false;
+    /// See [mpmc](crate::mpmc::Settings::TIMESTAMPS) documentation.
+    #[cfg(feature = "std")]
+    const TIMESTAMPS: bool = false;
+    /// See [mpmc](crate::mpmc::Settings::RETENTION) documentation.
+    #[cfg(feature = "std")]
+    const RETENTION: Option<Duration> = None;
+    /// See [mpmc](crate::mpmc::Settings::ITEM_TTL) documentation.
+    #[cfg(feature = "std")]
+    const ITEM_TTL: Option<Duration> = None;
+    /// See [mpmc](crate::mpmc::Settings::STALE_READERS) documentation.
+    #[cfg(feature = "std")]
+    const STALE_READERS: StaleReaderPolicy = StaleReaderPolicy::NEVER;
+    /// See [mpmc](crate::mpmc::Settings::ON_CHUNK_FREE) documentation.
+    const ON_CHUNK_FREE: Option<fn(FreedChunkInfo)> = None;
+    /// See [mpmc](crate::mpmc::Settings::RECYCLE_POOL_SIZE) documentation.
+    const RECYCLE_POOL_SIZE: usize = if cfg!(feature = "double_buffering") {1} else {0};
+    /// See [mpmc](crate::mpmc::Settings::PREALLOCATED_CHUNKS) documentation.
+    const PREALLOCATED_CHUNKS: u32 = 0;
+    /// See [mpmc](crate::mpmc::Settings::CHUNK_ALLOCATOR) documentation.
+    const CHUNK_ALLOCATOR: ChunkAllocator = ChunkAllocator::GLOBAL;
+    /// See [mpmc](crate::mpmc::Settings::NOTIFY) documentation.
+    const NOTIFY: bool = false;
+    /// See [mpmc](crate::mpmc::Settings::LOCK) documentation.
+    const LOCK: LockBackend = LockBackend::Yield;
+    /// See [mpmc](crate::mpmc::Settings::SPILL_THRESHOLD) documentation.
+    const SPILL_THRESHOLD: usize = usize::MAX;
+
+    /// Set only by [RuntimeSettings].
+    #[doc(hidden)]
+    const RUNTIME_CONFIG: bool = false;
 }
 
 pub struct DefaultSettings{}
 impl Settings for DefaultSettings{}
 
+/// `MIN_CHUNK_SIZE`, `MAX_CHUNK_SIZE`, `CLEANUP` and `GROWTH` are set at runtime, with [EventQueue::builder].
+///
+/// A bit slower, than compile-time [Settings].
+pub struct RuntimeSettings{}
+impl Settings for RuntimeSettings{
+    const RUNTIME_CONFIG: bool = true;
+}
+
 /// spmc::Settings -> event_queue::Settings
 pub(crate) struct BS<S: Settings>{
     _phantom: PhantomData<S>
@@ -32,6 +92,28 @@ impl<S: Settings> BaseSettings for BS<S>{
     const MIN_CHUNK_SIZE : u32 = S::MIN_CHUNK_SIZE;
     const MAX_CHUNK_SIZE : u32 = S::MAX_CHUNK_SIZE;
     const CLEANUP: CleanupMode = S::CLEANUP;
+    const GROWTH: GrowthStrategy = S::GROWTH;
+    const EXTEND_SIZE_HINT: bool = S::EXTEND_SIZE_HINT;
     const LOCK_ON_NEW_CHUNK_CLEANUP: bool = true;
     const CLEANUP_IN_UNSUBSCRIBE: bool = false;
+    // readers_count can not be read without lock.
+    const DROP_WITHOUT_READERS: bool = false;
+    const PUBLISH_BATCH: u32 = S::PUBLISH_BATCH;
+    const RUNTIME_CONFIG: bool = S::RUNTIME_CONFIG;
+    const STATS: bool = S::STATS;
+    #[cfg(feature = "std")]
+    const TIMESTAMPS: bool = S::TIMESTAMPS;
+    #[cfg(feature = "std")]
+    const RETENTION: Option<Duration> = S::RETENTION;
+    #[cfg(feature = "std")]
+    const ITEM_TTL: Option<Duration> = S::ITEM_TTL;
+    #[cfg(feature = "std")]
+    const STALE_READERS: StaleReaderPolicy = S::STALE_READERS;
+    const ON_CHUNK_FREE: Option<fn(FreedChunkInfo)> = S::ON_CHUNK_FREE;
+    const RECYCLE_POOL_SIZE: usize = S::RECYCLE_POOL_SIZE;
+    const PREALLOCATED_CHUNKS: u32 = S::PREALLOCATED_CHUNKS;
+    const CHUNK_ALLOCATOR: ChunkAllocator = S::CHUNK_ALLOCATOR;
+    const NOTIFY: bool = S::NOTIFY;
+    const LOCK: LockBackend = S::LOCK;
+    const SPILL_THRESHOLD: usize = S::SPILL_THRESHOLD;
 }
\ No newline at end of file
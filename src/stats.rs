@@ -0,0 +1,56 @@
+// Counters updated under list lock (or by spmc writer), read from anywhere without lock.
+// Relaxed everywhere - counters are not used for synchronization.
+
+use crate::sync::{AtomicUsize, Ordering};
+
+/// [EventQueue](crate::mpmc::EventQueue) statistics snapshot.
+///
+/// Collected only with `Settings::STATS` enabled. Otherwise - all zeros.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Stats{
+    /// Values pushed by `push`/`extend`. Values dropped due to absence of readers - not counted.
+    pub pushed: usize,
+    /// Chunks allocated from heap (including the first one).
+    pub chunks_allocated: usize,
+    /// Chunks reused, instead of allocating. See `Settings::RECYCLE_POOL_SIZE`.
+    pub chunks_recycled: usize,
+    /// Chunks removed from queue (deallocated, or kept for recycling).
+    pub chunks_freed: usize,
+    /// Allocation size of `chunks_freed` chunks.
+    pub bytes_freed: usize,
+    /// Cleanup passes run. Both automatic and manual.
+    pub cleanups: usize,
+}
+
+pub(crate) struct StatsCounters{
+    pub pushed: AtomicUsize,
+    pub chunks_allocated: AtomicUsize,
+    pub chunks_recycled: AtomicUsize,
+    pub chunks_freed: AtomicUsize,
+    pub bytes_freed: AtomicUsize,
+    pub cleanups: AtomicUsize,
+}
+
+impl StatsCounters{
+    pub fn new() -> Self{
+        Self{
+            pushed: AtomicUsize::new(0),
+            chunks_allocated: AtomicUsize::new(0),
+            chunks_recycled: AtomicUsize::new(0),
+            chunks_freed: AtomicUsize::new(0),
+            bytes_freed: AtomicUsize::new(0),
+            cleanups: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn snapshot(&self) -> Stats{
+        Stats{
+            pushed: self.pushed.load(Ordering::Relaxed),
+            chunks_allocated: self.chunks_allocated.load(Ordering::Relaxed),
+            chunks_recycled: self.chunks_recycled.load(Ordering::Relaxed),
+            chunks_freed: self.chunks_freed.load(Ordering::Relaxed),
+            bytes_freed: self.bytes_freed.load(Ordering::Relaxed),
+            cleanups: self.cleanups.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+// AtomicU64 replacement for targets without 64-bit atomics (thumbv7, riscv32, mips...).
+// Lock-protected. Lock acquire/release gives at least Acquire/Release for every operation,
+// so `Ordering` arguments are ignored.
+
+use core::sync::atomic::Ordering;
+use spin::mutex::SpinMutex;
+
+#[derive(Debug)]
+pub(crate) struct AtomicU64(SpinMutex<u64>);
+
+impl AtomicU64{
+    #[inline]
+    pub const fn new(value: u64) -> Self{
+        Self{0: SpinMutex::new(value)}
+    }
+
+    #[inline]
+    pub fn load(&self, _ordering: Ordering) -> u64{
+        *self.0.lock()
+    }
+
+    #[inline]
+    pub fn store(&self, value: u64, _ordering: Ordering){
+        *self.0.lock() = value;
+    }
+
+    #[inline]
+    pub fn compare_exchange(&self, current: u64, new: u64, _success: Ordering, _failure: Ordering)
+        -> Result<u64, u64>
+    {
+        let mut value = self.0.lock();
+        if *value == current{
+            *value = new;
+            Ok(current)
+        } else {
+            Err(*value)
+        }
+    }
+
+    #[inline]
+    pub fn compare_exchange_weak(&self, current: u64, new: u64, success: Ordering, failure: Ordering)
+        -> Result<u64, u64>
+    {
+        self.compare_exchange(current, new, success, failure)
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use core::sync::atomic::Ordering;
+    use super::AtomicU64;
+
+    #[test]
+    fn atomic_u64_fallback_test(){
+        let value = AtomicU64::new(1 << 40);
+        assert_eq!(value.load(Ordering::Acquire), 1 << 40);
+        value.store(u64::MAX, Ordering::Release);
+        assert_eq!(value.compare_exchange(0, 1, Ordering::AcqRel, Ordering::Acquire), Err(u64::MAX));
+        assert_eq!(value.compare_exchange_weak(u64::MAX, 2, Ordering::AcqRel, Ordering::Acquire), Ok(u64::MAX));
+        assert_eq!(value.load(Ordering::Acquire), 2);
+    }
+}
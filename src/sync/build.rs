@@ -1,5 +1,5 @@
 #[cfg(loom)]
-pub(crate) use loom::sync::atomic::{AtomicPtr, AtomicUsize, AtomicU64, Ordering};
+pub(crate) use loom::sync::atomic::{AtomicPtr, AtomicUsize, AtomicU64, AtomicBool, Ordering, fence};
 
 #[cfg(loom)]
 pub(crate) use loom::sync::Arc;
@@ -25,14 +25,14 @@ impl<T> Mutex<T>{
     pub fn get_mut(&mut self) -> &mut T {
         // There is no way to get without lock in loom
         unsafe{
-            use std::ops::DerefMut;
+            use core::ops::DerefMut;
             &mut *(self.0.lock().unwrap().deref_mut() as *mut T)
         }
     }
 
     pub fn data_ptr(&self) -> *mut T {
         // There is no way to get without lock in loom
-        use std::ops::DerefMut;
+        use core::ops::DerefMut;
         self.0.lock().unwrap().deref_mut() as *mut T
     }
 }
@@ -40,23 +40,86 @@ impl<T> Mutex<T>{
 #[cfg(loom)]
 pub type SpinMutex<T> = Mutex<T>;
 
+/// Mutex with [LockBackend](crate::LockBackend) from `S::LOCK`. Under loom - always loom mutex.
+#[cfg(loom)]
+#[derive(Debug)]
+pub(crate) struct SettingsMutex<S, T>(Mutex<T>, core::marker::PhantomData<S>);
+
+#[cfg(loom)]
+pub(crate) struct SettingsMutexGuard<'a, S, T>(loom::sync::MutexGuard<'a, T>, core::marker::PhantomData<S>);
+
+#[cfg(loom)]
+impl<'a, S, T> core::ops::Deref for SettingsMutexGuard<'a, S, T>{
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(loom)]
+impl<'a, S, T> core::ops::DerefMut for SettingsMutexGuard<'a, S, T>{
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(loom)]
+impl<S, T> SettingsMutex<S, T>{
+    pub fn new(data: T) -> Self {
+        Self(Mutex::new(data), core::marker::PhantomData)
+    }
+
+    pub fn lock(&self) -> SettingsMutexGuard<'_, S, T> {
+        SettingsMutexGuard(self.0.lock(), core::marker::PhantomData)
+    }
+
+    pub fn try_lock(&self) -> Option<SettingsMutexGuard<'_, S, T>> {
+        let guard = self.0.0.try_lock().ok()?;
+        Some(SettingsMutexGuard(guard, core::marker::PhantomData))
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+
+    pub fn data_ptr(&self) -> *mut T {
+        self.0.data_ptr()
+    }
+}
+
 #[cfg(loom)]
 pub(crate) type SpinSharedMutex<T> = loom::sync::RwLock<T>;
 
 // ==========================================================================================
 
 #[cfg(not(loom))]
-pub(crate) use std::sync::atomic::{AtomicPtr, AtomicUsize, AtomicU64, Ordering};
+pub(crate) use core::sync::atomic::{AtomicPtr, AtomicUsize, AtomicBool, Ordering, fence};
 
-#[cfg(not(loom))]
-pub(crate) use std::sync::Arc;
+#[cfg(all(not(loom), target_has_atomic = "64"))]
+pub(crate) use core::sync::atomic::AtomicU64;
+
+#[cfg(all(not(loom), not(target_has_atomic = "64")))]
+pub(crate) use super::atomic_u64::AtomicU64;
 
 #[cfg(not(loom))]
+pub(crate) use alloc::sync::Arc;
+
+#[cfg(all(not(loom), feature = "std"))]
 //pub(crate) use parking_lot::{Mutex};
 pub(crate) type Mutex<T> = lock_api::Mutex<spin::mutex::Mutex<(), spin::relax::Yield>, T>;
 
+#[cfg(all(not(loom), not(feature = "std")))]
+pub(crate) type Mutex<T> = lock_api::Mutex<spin::mutex::Mutex<(), spin::relax::Spin>, T>;
+
 #[cfg(not(loom))]
 pub(crate) use spin::mutex::{SpinMutex};
 
+/// Mutex with [LockBackend](crate::LockBackend) from `S::LOCK`.
+#[cfg(not(loom))]
+pub(crate) type SettingsMutex<S, T> = lock_api::Mutex<super::raw_mutex::RawMutex<S>, T>;
+
+#[cfg(not(loom))]
+pub(crate) type SettingsMutexGuard<'a, S, T> = lock_api::MutexGuard<'a, super::raw_mutex::RawMutex<S>, T>;
+
 #[cfg(not(loom))]
 pub(crate) use spin::rwlock::RwLock as SpinSharedMutex;
\ No newline at end of file
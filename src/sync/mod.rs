@@ -1,9 +1,14 @@
 mod build;
+#[cfg(not(loom))]
+mod raw_mutex;
+#[cfg(any(test, all(not(loom), not(target_has_atomic = "64"))))]
+#[cfg_attr(any(test, not(feature = "std")), allow(dead_code))]  // CAS used only by readers with TTL.
+mod atomic_u64;
 pub(crate) use build::*;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 #[allow(dead_code)]
 #[allow(unused_imports)]
 mod dev;
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 pub(crate) use dev::*;
@@ -0,0 +1,214 @@
+// lock_api::RawMutex, with implementation chosen by Settings::LOCK.
+// All backends present in each mutex, but only one used - branches are constexpr.
+
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicUsize;
+use crate::event_queue::{LockBackend, Settings};
+
+pub(crate) struct RawMutex<S: Settings>{
+    locked: AtomicBool,
+    /// Ticket lock: next ticket to take.
+    next_ticket: AtomicU32,
+    /// Ticket lock: ticket of the lock owner.
+    now_serving: AtomicU32,
+    #[cfg(feature = "std")]
+    blocking: StdRawMutex,
+    #[cfg(feature = "parking_lot")]
+    parking_lot: parking_lot::RawMutex,
+    _phantom: PhantomData<fn() -> S>,
+}
+
+unsafe impl<S: Settings> lock_api::RawMutex for RawMutex<S>{
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self{
+        locked: AtomicBool::new(false),
+        next_ticket: AtomicU32::new(0),
+        now_serving: AtomicU32::new(0),
+        #[cfg(feature = "std")]
+        blocking: StdRawMutex::INIT,
+        #[cfg(feature = "parking_lot")]
+        parking_lot: <parking_lot::RawMutex as lock_api::RawMutex>::INIT,
+        _phantom: PhantomData,
+    };
+
+    // Do not allow unlock from the other thread - parking_lot forbids it.
+    type GuardMarker = lock_api::GuardNoSend;
+
+    #[inline]
+    fn lock(&self) {
+        match S::LOCK{
+            #[cfg(feature = "std")]
+            LockBackend::Std => self.blocking.lock(),
+            #[cfg(feature = "parking_lot")]
+            LockBackend::ParkingLot | LockBackend::ParkingLotFair => lock_api::RawMutex::lock(&self.parking_lot),
+            LockBackend::Spin | LockBackend::Yield => {
+                while self.locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+                    while self.locked.load(Ordering::Relaxed) {
+                        Self::relax();
+                    }
+                }
+            }
+            LockBackend::Ticket => {
+                let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+                while self.now_serving.load(Ordering::Acquire) != ticket {
+                    Self::relax();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn try_lock(&self) -> bool {
+        match S::LOCK{
+            #[cfg(feature = "std")]
+            LockBackend::Std => self.blocking.try_lock(),
+            #[cfg(feature = "parking_lot")]
+            LockBackend::ParkingLot | LockBackend::ParkingLotFair => lock_api::RawMutex::try_lock(&self.parking_lot),
+            LockBackend::Spin | LockBackend::Yield =>
+                self.locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok(),
+            LockBackend::Ticket => {
+                // Take ticket only if it is served right away.
+                // Acquire - synchronize with previous owner's unlock.
+                let ticket = self.now_serving.load(Ordering::Acquire);
+                self.next_ticket.compare_exchange(ticket, ticket.wrapping_add(1), Ordering::Acquire, Ordering::Relaxed).is_ok()
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        match S::LOCK{
+            #[cfg(feature = "std")]
+            LockBackend::Std => self.blocking.unlock(),
+            #[cfg(feature = "parking_lot")]
+            LockBackend::ParkingLot => lock_api::RawMutex::unlock(&self.parking_lot),
+            #[cfg(feature = "parking_lot")]
+            LockBackend::ParkingLotFair => lock_api::RawMutexFair::unlock_fair(&self.parking_lot),
+            LockBackend::Spin | LockBackend::Yield => self.locked.store(false, Ordering::Release),
+            LockBackend::Ticket => {
+                // Only owner changes now_serving.
+                let ticket = self.now_serving.load(Ordering::Relaxed);
+                self.now_serving.store(ticket.wrapping_add(1), Ordering::Release);
+            }
+        }
+    }
+}
+
+impl<S: Settings> RawMutex<S>{
+    #[inline]
+    fn relax(){
+        #[cfg(feature = "std")]
+        if let LockBackend::Yield | LockBackend::Ticket = S::LOCK {
+            std::thread::yield_now();
+            return;
+        }
+        core::hint::spin_loop();
+    }
+}
+
+/// Blocking lock. Uncontended lock/unlock - single atomic operation each.
+/// `std::sync::{Mutex, Condvar}` used only while someone waits.
+#[cfg(feature = "std")]
+struct StdRawMutex{
+    locked: AtomicBool,
+    /// Threads sleeping (or going to sleep) on `unlocked`. Changed under `sleep` lock.
+    waiters: AtomicUsize,
+    sleep: std::sync::Mutex<()>,
+    unlocked: std::sync::Condvar,
+}
+
+#[cfg(feature = "std")]
+impl StdRawMutex{
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self{
+        locked: AtomicBool::new(false),
+        waiters: AtomicUsize::new(0),
+        sleep: std::sync::Mutex::new(()),
+        unlocked: std::sync::Condvar::new(),
+    };
+
+    // Nothing panics under inner lock - poison is impossible.
+    #[inline]
+    fn sleep_lock(&self) -> std::sync::MutexGuard<'_, ()>{
+        self.sleep.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[inline]
+    fn try_lock(&self) -> bool{
+        self.locked.compare_exchange(false, true, Ordering::SeqCst, Ordering::Relaxed).is_ok()
+    }
+
+    #[inline]
+    fn lock(&self){
+        if !self.try_lock(){
+            self.lock_slow();
+        }
+    }
+
+    #[cold]
+    fn lock_slow(&self){
+        let mut guard = self.sleep_lock();
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        // Unlock either sees us in `waiters`, or we see it unlocked.
+        while !self.try_lock(){
+            guard = self.unlocked.wait(guard).unwrap_or_else(|e| e.into_inner());
+        }
+        self.waiters.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn unlock(&self){
+        self.locked.store(false, Ordering::SeqCst);
+        if self.waiters.load(Ordering::SeqCst) != 0 {
+            // Under sleep lock - waiter is either before try_lock, or sleeping already.
+            let _guard = self.sleep_lock();
+            self.unlocked.notify_one();
+        }
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod test{
+    use lock_api::RawMutex as _;
+    use crate::event_queue::LockBackend;
+    use crate::mpmc::{BS, Settings};
+
+    fn try_lock<S: Settings>(){
+        let mutex = super::RawMutex::<BS<S>>::INIT;
+        assert!(mutex.try_lock());
+        assert!(!mutex.try_lock());
+        unsafe{ mutex.unlock(); }
+        assert!(mutex.try_lock());
+        unsafe{ mutex.unlock(); }
+
+        mutex.lock();
+        assert!(!mutex.try_lock());
+        unsafe{ mutex.unlock(); }
+    }
+
+    #[test]
+    fn try_lock_test(){
+        struct Spin{} impl Settings for Spin{ const LOCK: LockBackend = LockBackend::Spin; }
+        struct Yield{} impl Settings for Yield{ const LOCK: LockBackend = LockBackend::Yield; }
+        struct Ticket{} impl Settings for Ticket{ const LOCK: LockBackend = LockBackend::Ticket; }
+        try_lock::<Spin>();
+        try_lock::<Yield>();
+        try_lock::<Ticket>();
+
+        #[cfg(feature = "std")]
+        {
+            struct Std{} impl Settings for Std{ const LOCK: LockBackend = LockBackend::Std; }
+            try_lock::<Std>();
+        }
+
+        #[cfg(feature = "parking_lot")]
+        {
+            struct ParkingLot{} impl Settings for ParkingLot{ const LOCK: LockBackend = LockBackend::ParkingLot; }
+            struct ParkingLotFair{} impl Settings for ParkingLotFair{ const LOCK: LockBackend = LockBackend::ParkingLotFair; }
+            try_lock::<ParkingLot>();
+            try_lock::<ParkingLotFair>();
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Yield points for deterministic concurrency testing. Only with `test_hooks` feature.
+//!
+//! Queue calls installed hook at points, where interleaving matters. Scheduler-driven
+//! test frameworks (like [shuttle](https://docs.rs/shuttle)) can yield there, to explore
+//! interleavings deterministically and reproduce races:
+//!
+//! ```
+//! # use rc_event_queue::test_hooks::{set_yield_hook, YieldPoint};
+//! set_yield_hook(Some(|_point: YieldPoint|{
+//!     // shuttle::thread::yield_now();
+//!     std::thread::yield_now();
+//! }));
+//! # set_yield_hook(None);
+//! ```
+//!
+//! Hook is global, for all queues. Without installed hook - each point costs one atomic load.
+
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// Where hook was called from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum YieldPoint{
+    /// Writer, before value(s) write in push/extend. Under queue lock (for mpmc).
+    Push,
+    /// Writer, before new chunk become visible to readers.
+    AddChunk,
+    /// Writer, before readers start position change (clear/truncate/cleanup_force).
+    SetStartPosition,
+    /// Reader, before fast-forward to start position.
+    ReaderFastForward,
+}
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(null_mut());
+
+/// Install global hook, called at each [YieldPoint]. `None` - remove hook.
+pub fn set_yield_hook(hook: Option<fn(YieldPoint)>){
+    let ptr = match hook{
+        Some(hook) => hook as *mut (),
+        None => null_mut(),
+    };
+    HOOK.store(ptr, Ordering::Release);
+}
+
+#[inline]
+pub(crate) fn yield_point(point: YieldPoint){
+    let ptr = HOOK.load(Ordering::Acquire);
+    if !ptr.is_null(){
+        // Stored from `fn(YieldPoint)` in set_yield_hook.
+        let hook = unsafe{ core::mem::transmute::<*mut (), fn(YieldPoint)>(ptr) };
+        hook(point);
+    }
+}
@@ -1,10 +1,11 @@
-use crate::mpmc::{DefaultSettings, EventQueue, EventReader, Settings};
+use crate::mpmc::{channel, DefaultSettings, EventQueue, EventReader, Settings, SubscriberFactory};
+use crate::Stats;
 use crate::event_queue::{CleanupMode};
 use crate::sync::{AtomicUsize, Ordering, AtomicBool, Arc, thread};
 use itertools::{Itertools, assert_equal};
 use std::borrow::BorrowMut;
 use std::ops::Range;
-use crate::tests::utils::{consume_copies, consume_mapped};
+use crate::tests::utils::{consume_copies, consume_mapped, skip};
 use crate::event_reader::LendingIterator;
 use super::common::*;
 
@@ -150,6 +151,97 @@ fn extend_test() {
     );
 }
 
+#[test]
+fn push_repeated_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<String, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.push_repeated(String::from("a"), 0);
+    event.push(String::from("b"));
+    event.push_repeated(String::from("c"), 10);
+    assert_eq!(event.total_capacity(), 12);
+
+    let values = consume_mapped(&mut reader.iter(), |s| s.clone());
+    assert_equal(values.iter().map(String::as_str), std::iter::once("b").chain(std::iter::repeat_n("c", 10)));
+}
+
+#[test]
+fn spill_threshold_test() {
+    let destruct_counter = AtomicUsize::new(0);
+    let destruct_counter_ref = &destruct_counter;
+    let on_destroy = ||{destruct_counter_ref.fetch_add(1, Ordering::Relaxed);};
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const SPILL_THRESHOLD: usize = 16;
+    }
+    struct NotSpilled{} impl Settings for NotSpilled{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    {
+        let event = EventQueue::<_, S>::new();
+        let mut reader = EventReader::new(&event);
+        event.push(Data::from(0, on_destroy));
+        event.extend((1..6).map(|i| Data::from(i, on_destroy)));
+        assert_equal(consume_mapped(&mut reader.iter(), |data| data.id), 0..6);
+
+        // Only first chunk should be freed
+        assert_eq!(destruct_counter.load(Ordering::Relaxed), 4);
+    }
+    assert_eq!(destruct_counter.load(Ordering::Relaxed), 6);
+
+    let event = EventQueue::<[usize; 8], S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend_from_slice(&[[1; 8], [2; 8], [3; 8], [4; 8], [5; 8]]);
+    assert_equal(consume_copies(&mut reader.iter()), [[1; 8], [2; 8], [3; 8], [4; 8], [5; 8]]);
+
+    // Slots hold pointers only.
+    assert_eq!(
+        EventQueue::<[usize; 8], S>::new().bytes_allocated() + 4 * 7 * std::mem::size_of::<usize>(),
+        EventQueue::<[usize; 8], NotSpilled>::new().bytes_allocated()
+    );
+}
+
+#[test]
+fn extend_from_slice_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 8;
+        const STATS: bool = true;
+        const TIMESTAMPS: bool = true;
+    }
+
+    let event = EventQueue::<u8, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend_from_slice(&[]);
+    event.push(0);
+    let values: Vec<u8> = (1..30).collect();
+    event.extend_from_slice(&values);
+    event.extend_from_slice(&[30, 31]);
+    assert_eq!(event.total_capacity(), 4+4+8+8+8);
+    assert_eq!(event.stats().pushed, 32);
+
+    let mut iter = reader.iter();
+    let mut prev_time = None;
+    let mut i = 0;
+    while let Some((value, time)) = iter.next_timestamped(){
+        assert_eq!(*value, i);
+        assert!(prev_time <= Some(time));
+        prev_time = Some(time);
+        i += 1;
+    }
+    assert_eq!(i, 32);
+}
+
 #[test]
 fn clear_test() {
     struct S{} impl Settings for S{
@@ -177,6 +269,45 @@ fn clear_test() {
     );
 }
 
+#[test]
+fn missed_count_test() {
+    use crate::mpmc::WeakReader;
+    use crate::Lagged;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    let mut tap = WeakReader::new(&event);
+
+    event.extend(0..6);
+    skip(&mut reader.iter(), 1);
+    event.clear();
+    assert_eq!(reader.take_missed_count(), 5);
+    assert_eq!(reader.take_missed_count(), 0);
+    assert!(matches!(tap.iter(), Err(Lagged(6))));
+
+    event.extend(6..16);
+    skip(&mut reader.iter(), 3);
+    event.truncate_front(2);
+    assert_equal(consume_copies(&mut reader.iter()), 14..16);
+    assert_eq!(reader.take_missed_count(), 5);
+
+    // Truncated in the middle of tap's iteration.
+    event.extend(16..20);
+    assert!(matches!(tap.iter(), Err(Lagged(8))));
+    {
+        let mut iter = tap.iter().unwrap();
+        assert_eq!(iter.next(), Some(&14));
+        event.truncate_front(1);
+    }
+    assert!(matches!(tap.iter(), Err(Lagged(4))));
+    assert_equal(consume_copies(&mut tap.iter().unwrap()), [19]);
+}
+
 #[test]
 #[cfg(any(not(miri), not(target_os = "windows")))]
 fn mt_push_truncate_test() {
@@ -342,4 +473,2629 @@ for _ in 0..if cfg!(miri){10} else {100} {
         thread.join().unwrap();
     }
 }
-}
\ No newline at end of file
+}
+
+#[test]
+fn drop_without_readers_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const DROP_WITHOUT_READERS: bool = true;
+    }
+
+    let destruct_counter = AtomicUsize::new(0);
+    let destruct_counter_ref = &destruct_counter;
+    let on_destroy = ||{destruct_counter_ref.fetch_add(1, Ordering::Relaxed);};
+
+    let event = EventQueue::<_, S>::new();
+    event.push(Data::from(0, on_destroy));
+    event.extend((1..10).map(|i| Data::from(i, on_destroy)));
+    assert_eq!(event.dropped_count(), 10);
+    assert_eq!(destruct_counter.load(Ordering::Relaxed), 10);
+    assert_eq!(event.total_capacity(), 4);
+
+    let mut reader = EventReader::new(&event);
+    event.push(Data::from(10, on_destroy));
+    event.extend((11..13).map(|i| Data::from(i, on_destroy)));
+    assert_eq!(event.dropped_count(), 10);
+    assert_equal(
+        consume_mapped(&mut reader.iter(), |data| data.id),
+        [10, 11, 12]
+    );
+}
+
+#[test]
+fn internal_iteration_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..10);
+    let mut v = Vec::new();
+    reader.iter().for_each(|i| v.push(*i));
+    assert_equal(v, 0..10);
+
+    event.extend(10..20);
+    assert_eq!(reader.iter().fold(0, |acc, i| acc + i), (10..20).sum());
+
+    event.extend(20..30);
+    let mut v = Vec::new();
+    let result = reader.iter().try_for_each(|i|{
+        v.push(*i);
+        if *i == 24 { Err(*i) } else { Ok(()) }
+    });
+    assert_eq!(result, Err(24));
+    assert_equal(v, 20..25);
+    assert_equal(consume_copies(&mut reader.iter()), 25..30);
+}
+
+#[test]
+fn peek_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert!(reader.iter().peek().is_none());
+
+    event.extend(0..6);
+    {
+        let mut iter = reader.iter();
+        assert_eq!(iter.peek(), Some(&0));
+        assert_eq!(iter.peek(), Some(&0));
+        assert_eq!(iter.next(), Some(&0));
+
+        // peek across chunk boundary
+        skip(&mut iter, 3);
+        assert_eq!(iter.peek(), Some(&4));
+    }
+    // peeked item is not consumed
+    assert_equal(consume_copies(&mut reader.iter()), [4, 5]);
+    assert!(reader.iter().peek().is_none());
+}
+
+#[test]
+fn reader_ttl_test() {
+    use std::time::Duration;
+    use crate::ReaderExpired;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    let mut ttl_reader = EventReader::with_ttl(&event, Duration::from_millis(50));
+
+    event.extend(0..10);
+    assert_equal(consume_copies(&mut ttl_reader.try_iter().unwrap()), 0..10);
+    consume_copies(&mut reader.iter());
+    assert!(!ttl_reader.is_expired());
+
+    event.extend(10..20);
+    consume_copies(&mut reader.iter());
+    // ttl_reader holds 3rd chunk.
+    assert_eq!(event.total_capacity(), 12);
+
+    thread::sleep(Duration::from_millis(100));
+    event.cleanup();
+    assert!(ttl_reader.is_expired());
+    assert_eq!(event.total_capacity(), 4);
+    assert!(matches!(ttl_reader.try_iter(), Err(ReaderExpired)));
+    ttl_reader.update_position();
+
+    drop(ttl_reader);
+    event.extend(20..22);
+    assert_equal(consume_copies(&mut reader.iter()), 20..22);
+}
+
+#[test]
+fn stale_readers_test() {
+    use crate::{ReaderExpired, StaleReaderPolicy};
+
+    // Lag
+    {
+        struct S{} impl Settings for S{
+            const MIN_CHUNK_SIZE: u32 = 4;
+            const MAX_CHUNK_SIZE: u32 = 4;
+            const CLEANUP: CleanupMode = CleanupMode::OnNewChunk;
+            const STALE_READERS: StaleReaderPolicy = StaleReaderPolicy{
+                idle: None,
+                max_lag_bytes: Some(8 * std::mem::size_of::<usize>())
+            };
+        }
+
+        let event = EventQueue::<usize, S>::new();
+        let mut leaked = EventReader::new(&event);
+        let mut reader = EventReader::new(&event);
+
+        event.extend(0..12);
+        assert_equal(consume_copies(&mut reader.iter()), 0..12);
+        assert!(!leaked.is_expired());
+        assert_eq!(event.total_capacity(), 12);
+
+        // Leaked reader pins 12 items now.
+        event.push(12);
+        assert!(leaked.is_expired());
+        assert!(matches!(leaked.try_iter(), Err(ReaderExpired)));
+        assert_eq!(event.total_capacity(), 8);
+        assert_equal(consume_copies(&mut reader.iter()), [12]);
+
+        // Reads of expired reader - empty.
+        assert!(leaked.iter().next().is_none());
+        assert!(leaked.peek_iter().next().is_none());
+        assert_eq!(leaked.skip_to_end(), 0);
+        assert_eq!(leaked.rewind(1), 0);
+        let mut leaked = crate::mpmc::CommittedReader::from_reader(leaked);
+        assert!(leaked.iter().next().is_none());
+
+        // Merged readers report expired ones.
+        let mut merged = crate::mpmc::MergeReader::new(
+            vec![leaked.into_reader(), reader],
+            crate::MergeOrder::Unordered
+        );
+        event.push(13);
+        let mut iter = merged.iter();
+        assert_equal(iter.expired_sources(), [0]);
+        assert_eq!(iter.next_with_source(), Some((1, &13)));
+    }
+
+    // Idle
+    #[cfg(not(miri))]
+    {
+        use std::time::Duration;
+        struct S{} impl Settings for S{
+            const STALE_READERS: StaleReaderPolicy = StaleReaderPolicy{
+                idle: Some(Duration::from_millis(25)),
+                max_lag_bytes: None
+            };
+        }
+
+        let event = EventQueue::<usize, S>::new();
+        let mut readers = EventReader::new_batch(&event, 2);
+        event.push(0);
+        thread::sleep(Duration::from_millis(50));
+        assert!(readers[0].try_iter().is_ok());
+        event.cleanup();
+        assert!(!readers[0].is_expired());
+        assert!(readers[1].is_expired());
+    }
+}
+
+#[test]
+fn weak_reader_test() {
+    use crate::mpmc::WeakReader;
+    use crate::Lagged;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    let mut tap = WeakReader::new(&event);
+    assert_eq!(event.readers_count(), 1);
+
+    event.extend(0..6);
+    assert_equal(consume_copies(&mut tap.iter().unwrap()), 0..6);
+    assert_eq!(tap.position().sequence(), 6);
+    assert_eq!(event.readers_count(), 1);
+
+    // Iterator holds memory.
+    {
+        event.extend(6..10);
+        let mut iter = tap.iter().unwrap();
+        assert_eq!(iter.next(), Some(&6));
+        assert_equal(consume_copies(&mut reader.iter()), 0..10);
+        assert_eq!(event.total_capacity(), 8);
+        assert_equal(consume_copies(&mut iter), 7..10);
+    }
+    assert_eq!(tap.position().sequence(), 10);
+
+    // Tap does not.
+    event.extend(10..14);
+    assert_equal(consume_copies(&mut reader.iter()), 10..14);
+    assert!(matches!(tap.iter(), Err(Lagged(2))));
+    assert_equal(consume_copies(&mut tap.iter().unwrap()), 12..14);
+
+    event.extend(14..22);
+    assert_equal(consume_copies(&mut reader.iter()), 14..22);
+    assert!(matches!(tap.iter(), Err(Lagged(6))));
+    assert_equal(consume_copies(&mut tap.iter().unwrap()), 20..22);
+
+    event.extend(22..25);
+    event.truncate_front(1);
+    assert!(matches!(tap.iter(), Err(Lagged(2))));
+    assert_equal(consume_copies(&mut tap.iter().unwrap()), [24]);
+    assert!(tap.iter().unwrap().next().is_none());
+}
+
+#[test]
+fn fork_test() {
+    use std::time::Duration;
+    use crate::ReaderExpired;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..10);
+    assert_equal(consume_copies(&mut reader.iter()), 0..10);
+    event.extend(10..14);
+    let mut fork = reader.fork();
+    event.extend(14..16);
+
+    assert_equal(consume_copies(&mut reader.iter()), 10..16);
+    // Passed chunks freed, fork still holds its chunk.
+    assert_eq!(event.total_capacity(), 8);
+    assert_equal(consume_copies(&mut fork.iter()), 10..16);
+    assert_eq!(event.total_capacity(), 4);
+
+    drop(reader);
+    event.push(16);
+    assert_equal(consume_copies(&mut fork.iter()), [16]);
+
+    // TTL inherited
+    let ttl_reader = EventReader::with_ttl(&event, Duration::from_millis(50));
+    let ttl_fork = ttl_reader.try_fork().unwrap();
+    event.extend(0..8);
+    thread::sleep(Duration::from_millis(100));
+    event.cleanup();
+    assert!(ttl_reader.is_expired());
+    assert!(ttl_fork.is_expired());
+    assert!(matches!(ttl_reader.try_fork(), Err(ReaderExpired)));
+}
+
+#[test]
+fn publish_batch_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 8;
+        const MAX_CHUNK_SIZE: u32 = 8;
+        const PUBLISH_BATCH: u32 = 3;
+    }
+
+    let destruct_counter = AtomicUsize::new(0);
+    let destruct_counter_ref = &destruct_counter;
+    let on_destroy = ||{destruct_counter_ref.fetch_add(1, Ordering::Relaxed);};
+
+    {
+        let event = EventQueue::<_, S>::new();
+        let mut reader = EventReader::new(&event);
+
+        for i in 0..5 { event.push(Data::from(i, on_destroy)); }
+        assert_equal(consume_mapped(&mut reader.iter(), |data| data.id), [0, 1, 2]);
+
+        event.flush();
+        assert_equal(consume_mapped(&mut reader.iter(), |data| data.id), [3, 4]);
+
+        // chunk seal publish
+        for i in 5..8 { event.push(Data::from(i, on_destroy)); }
+        assert_equal(consume_mapped(&mut reader.iter(), |data| data.id), [5, 6, 7]);
+
+        // extend after unpublished push
+        event.push(Data::from(8, on_destroy));
+        event.extend((9..11).map(|i| Data::from(i, on_destroy)));
+        assert_equal(consume_mapped(&mut reader.iter(), |data| data.id), [8, 9, 10]);
+
+        // unpublished values dropped with queue
+        event.push(Data::from(11, on_destroy));
+    }
+    assert_eq!(destruct_counter.load(Ordering::Relaxed), 12);
+}
+
+#[test]
+fn latest_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert!(reader.latest().is_none());
+
+    event.extend(0..10);
+    assert_eq!(reader.latest().as_deref(), Some(&9));
+    assert!(reader.latest().is_none());
+    assert_eq!(event.total_capacity(), 4);
+
+    // last item at the end of chunk, followed by empty chunk
+    event.extend(10..12);
+    event.change_chunk_capacity(4);
+    assert_eq!(reader.latest().as_deref(), Some(&11));
+    event.push(12);
+    assert_equal(consume_copies(&mut reader.iter()), [12]);
+}
+
+#[test]
+fn latest_expire_test() {
+    use crate::StaleReaderPolicy;
+    use std::time::Duration;
+
+    // Held `latest` keeps reader from expiring - and its chunk from cleanup.
+    // TTL
+    {
+        struct S{} impl Settings for S{
+            const MIN_CHUNK_SIZE: u32 = 4;
+            const MAX_CHUNK_SIZE: u32 = 4;
+        }
+
+        let event = EventQueue::<String, S>::new();
+        let mut reader = EventReader::with_ttl(&event, Duration::from_millis(1));
+        event.extend((0..6).map(|i| i.to_string()));
+
+        let latest = reader.latest().unwrap();
+        thread::sleep(Duration::from_millis(10));
+        event.extend((6..12).map(|i| i.to_string()));
+        event.cleanup();
+        assert_eq!(*latest, "5");
+        drop(latest);
+
+        assert!(!reader.is_expired());
+        assert_equal(consume_mapped(&mut reader.iter(), |s| s.clone()), (6..12).map(|i| i.to_string()));
+    }
+
+    // Lag
+    {
+        struct S{} impl Settings for S{
+            const MIN_CHUNK_SIZE: u32 = 4;
+            const MAX_CHUNK_SIZE: u32 = 4;
+            const STALE_READERS: StaleReaderPolicy = StaleReaderPolicy{
+                idle: None,
+                max_lag_bytes: Some(8 * std::mem::size_of::<String>())
+            };
+        }
+
+        let event = EventQueue::<String, S>::new();
+        let mut reader = EventReader::new(&event);
+        event.extend((0..6).map(|i| i.to_string()));
+
+        let latest = reader.latest().unwrap();
+        event.extend((6..20).map(|i| i.to_string()));
+        event.cleanup();
+        assert_eq!(*latest, "5");
+        drop(latest);
+        assert!(!reader.is_expired());
+
+        // Idle now - lagging reader expires.
+        event.cleanup();
+        assert!(reader.is_expired());
+    }
+}
+
+#[test]
+fn rewind_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const CLEANUP: CleanupMode = CleanupMode::Never;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert_eq!(reader.rewind(10), 0);
+
+    event.extend(0..10);
+    assert_equal(consume_copies(&mut reader.iter()), 0..10);
+
+    // within chunk
+    assert_eq!(reader.rewind(1), 1);
+    assert_equal(consume_copies(&mut reader.iter()), [9]);
+
+    // across chunks
+    assert_eq!(reader.rewind(7), 7);
+    assert_equal(consume_copies(&mut reader.iter()), 3..10);
+
+    // limited by history
+    assert_eq!(reader.rewind(100), 10);
+    assert_equal(consume_copies(&mut reader.iter()), 0..10);
+
+    // Chunk counters balanced - everything freed after read.
+    let mut reader2 = EventReader::new(&event);
+    event.extend(10..14);
+    consume_copies(&mut reader.iter());
+    consume_copies(&mut reader2.iter());
+    event.cleanup();
+    assert_eq!(event.total_capacity(), 4);
+
+    // limited by clear
+    event.extend(14..16);
+    event.clear();
+    event.extend(16..18);
+    assert_equal(consume_copies(&mut reader.iter()), 16..18);
+    assert_eq!(reader.rewind(100), 2);
+    assert_equal(consume_copies(&mut reader.iter()), 16..18);
+}
+
+#[test]
+fn position_test() {
+    use crate::Position;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const RECYCLE_POOL_SIZE: usize = 1;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert_eq!(reader.position(), Position::from_sequence(0));
+
+    event.extend(0..10);
+    assert_eq!(reader.position().sequence(), 0);
+    let mut iter = reader.iter();
+    for _ in 0..6 { iter.next(); }
+    drop(iter);
+    assert_eq!(reader.position().sequence(), 6);
+    let fork = reader.fork();
+    assert_eq!(fork.position(), reader.position());
+
+    // new reader starts at the end
+    let reader2 = EventReader::new(&event);
+    assert_eq!(reader2.position().sequence(), 10);
+    assert!(reader.position() < reader2.position());
+
+    // continues through recycled chunks, and clear
+    consume_copies(&mut reader.iter());
+    drop(fork);
+    drop(reader2);
+    event.extend(10..20);
+    event.clear();
+    event.extend(20..22);
+    assert_eq!(reader.position().sequence(), 10);
+    assert_equal(consume_copies(&mut reader.iter()), 20..22);
+    assert_eq!(reader.position().sequence(), 22);
+}
+
+#[test]
+fn push_with_position_test() {
+    use crate::Position;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const DROP_WITHOUT_READERS: bool = true;
+        const PUBLISH_BATCH: u32 = 3;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    assert_eq!(event.push_with_position(0), None);
+    assert_eq!(event.extend_with_position(0..2), Position::from_sequence(0)..Position::from_sequence(0));
+
+    let mut reader = EventReader::new(&event);
+    assert_eq!(event.end_position(), Position::from_sequence(0));
+    assert_eq!(event.push_with_position(0), Some(Position::from_sequence(0)));
+    assert_eq!(event.extend_with_position(1..6), Position::from_sequence(1)..Position::from_sequence(6));
+    assert_eq!(event.push_with_position(6), Some(Position::from_sequence(6)));
+    event.clear();
+    assert_eq!(event.push_with_position(7), Some(Position::from_sequence(7)));
+    assert_eq!(event.end_position(), Position::from_sequence(8));
+
+    event.flush();
+    assert_equal(consume_copies(&mut reader.iter()), [7]);
+    assert_eq!(reader.position(), event.end_position());
+}
+
+#[test]
+fn skip_to_end_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert_eq!(reader.skip_to_end(), 0);
+
+    event.extend(0..10);
+    assert_eq!(reader.iter().next(), Some(&0));
+    assert_eq!(event.total_capacity(), 12);
+    assert_eq!(reader.skip_to_end(), 9);
+    assert_eq!(event.total_capacity(), 4);
+    assert_eq!(reader.lag(), 0);
+
+    // followed by empty chunk
+    event.extend(10..12);
+    event.change_chunk_capacity(4);
+    assert_eq!(reader.skip_to_end(), 2);
+    event.push(12);
+    assert_equal(consume_copies(&mut reader.iter()), [12]);
+}
+
+#[test]
+fn fused_refresh_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..2);
+    let mut iter = reader.iter();
+    assert_equal(consume_copies(&mut iter), 0..2);
+
+    // same chunk
+    event.extend(2..3);
+    assert!(iter.next().is_none());
+    iter.refresh();
+    assert_equal(consume_copies(&mut iter), 2..3);
+
+    // new empty chunk
+    event.change_chunk_capacity(4);
+    event.change_chunk_capacity(4);
+    assert!(iter.next().is_none());
+    event.extend(3..5);
+    assert!(iter.next().is_none());
+    iter.refresh();
+    assert_equal(consume_copies(&mut iter), 3..5);
+}
+
+#[test]
+fn subscriber_factory_test() {
+    let event = EventQueue::<usize>::new();
+    let factory = SubscriberFactory::new(&event);
+
+    let mut reader1 = factory.subscribe();
+    let factory2 = factory.clone();
+    drop(factory);
+    let mut reader2 = factory2.subscribe();
+
+    event.extend(0..4);
+    drop(event);
+
+    // factory keeps queue alive
+    let mut reader3 = factory2.subscribe();
+    assert_equal(consume_copies(&mut reader1.iter()), 0..4);
+    assert_equal(consume_copies(&mut reader2.iter()), 0..4);
+    assert!(reader3.iter().next().is_none());
+}
+
+#[test]
+fn clone_handle_test() {
+    let event = EventQueue::<usize>::new();
+    let mut reader = EventReader::new(&event);
+
+    let event2 = event.clone();
+    drop(event);
+    event2.extend(0..4);
+
+    let handle = thread::spawn(move || {
+        event2.push(4);
+    });
+    handle.join().unwrap();
+
+    // reader keeps queue alive
+    assert_equal(consume_copies(&mut reader.iter()), 0..5);
+}
+
+#[test]
+fn channel_test() {
+    let (sender, subscribers) = channel::<usize>();
+    let mut reader1 = subscribers.subscribe();
+
+    let sender2 = sender.clone();
+    let handle = thread::spawn(move || {
+        sender2.extend(0..4);
+    });
+    handle.join().unwrap();
+    drop(subscribers);
+
+    // sender alone keeps queue alive
+    sender.push(4);
+    assert_equal(consume_copies(&mut reader1.iter()), 0..5);
+
+    // custom settings
+    struct S{} impl Settings for S{
+        const PUBLISH_BATCH: u32 = 4;
+    }
+    let (sender, subscribers) = EventQueue::<usize, S>::new().into_channel();
+    let mut reader = subscribers.subscribe();
+    sender.push(0);
+    assert!(reader.iter().next().is_none());
+    sender.flush();
+    assert_equal(consume_copies(&mut reader.iter()), [0]);
+}
+
+#[test]
+fn std_extend_test() {
+    fn fill(out: &mut impl Extend<usize>, range: core::ops::Range<usize>){
+        out.extend(range);
+    }
+
+    let event = EventQueue::<usize>::new();
+    let mut reader = EventReader::new(&event);
+    fill(&mut event.writer(), 0..3);
+    assert_equal(consume_copies(&mut reader.iter()), 0..3);
+
+    let (mut sender, subscribers) = event.into_channel();
+    let mut reader2 = subscribers.subscribe();
+    fill(&mut sender, 3..6);
+    assert_equal(consume_copies(&mut reader.iter()), 3..6);
+    assert_equal(consume_copies(&mut reader2.iter()), 3..6);
+}
+
+#[cfg(feature = "streaming_iterator")]
+#[test]
+fn streaming_iterator_test() {
+    use streaming_iterator::StreamingIterator;
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend(0..10);
+
+    {
+        let mut iter = reader.iter();
+        assert!(iter.get().is_none());
+        iter.advance();
+        assert_eq!(iter.get(), Some(&0));
+        assert_eq!(iter.get(), Some(&0));
+        let evens: Vec<usize> = iter.filter(|value| *value % 2 == 0).map_deref(|value| *value).collect();
+        assert_eq!(evens, [2, 4, 6, 8]);
+    }
+    assert!(reader.iter().get().is_none());
+
+    event.extend(10..13);
+    let mut iter = reader.iter();
+    assert_eq!(StreamingIterator::next(&mut iter), Some(&10));
+    assert_eq!(iter.skip(1).next(), Some(&12));
+}
+
+#[test]
+fn gat_lending_iterator_test() {
+    use crate::gat;
+    use crate::mpmc::FilteredReader;
+    fn first<I: gat::LendingIterator>(iter: &mut I) -> Option<I::Item<'_>>{
+        iter.next()
+    }
+    fn count<I: gat::LendingIterator>(mut iter: I) -> usize{
+        let mut count = 0;
+        while gat::LendingIterator::next(&mut iter).is_some(){
+            count += 1;
+        }
+        count
+    }
+
+    let event = EventQueue::<String>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend((0..5).map(|i| i.to_string()));
+
+    let mut iter = reader.iter();
+    let value: &String = first(&mut iter).unwrap();
+    assert_eq!(value, "0");
+    assert_eq!(count(iter), 4);
+
+    event.extend((5..8).map(|i| i.to_string()));
+    let mut filtered = FilteredReader::from_reader(reader, |value: &String| value != "6");
+    assert_eq!(count(filtered.iter()), 2);
+}
+
+#[test]
+fn select_test() {
+    use crate::select::{select, select_timeout};
+    use std::time::Duration;
+
+    struct S{} impl Settings for S{
+        const NOTIFY: bool = true;
+    }
+    let event1 = EventQueue::<usize, S>::new();
+    let event2 = EventQueue::<usize, S>::new();
+    let mut reader1 = EventReader::new(&event1);
+    let mut reader2 = EventReader::new(&event2);
+
+    assert_eq!(select_timeout(&[&reader1, &reader2], Duration::from_millis(1)), None);
+
+    let handle = {
+        let event2 = event2.clone();
+        thread::spawn(move || {
+            event2.extend(0..4);
+        })
+    };
+    assert_eq!(select(&[&reader1, &reader2]), 1);
+    handle.join().unwrap();
+    assert_equal(consume_copies(&mut reader2.iter()), 0..4);
+
+    event1.push(4);
+    assert_eq!(select(&[&reader1, &reader2]), 0);
+    assert_equal(consume_copies(&mut reader1.iter()), [4]);
+}
+
+#[test]
+fn select_async_test() {
+    use crate::select::select_async;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use std::task::Wake;
+
+    struct CountWaker(AtomicUsize);
+    impl Wake for CountWaker{
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    struct S{} impl Settings for S{
+        const NOTIFY: bool = true;
+    }
+    let event1 = EventQueue::<usize, S>::new();
+    let event2 = EventQueue::<usize, S>::new();
+    let reader1 = EventReader::new(&event1);
+    let reader2 = EventReader::new(&event2);
+
+    let count_waker = Arc::new(CountWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(count_waker.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let readers: [&dyn crate::select::Selectable; 2] = [&reader1, &reader2];
+    let mut future = pin!(select_async(&readers));
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+
+    event2.push(0);
+    event2.push(1);
+    assert!(count_waker.0.load(Ordering::Relaxed) > 0);
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(1));
+}
+
+#[test]
+#[should_panic]
+fn select_without_notify_test() {
+    let event = EventQueue::<usize>::new();
+    let reader = EventReader::new(&event);
+    crate::select::select(&[&reader]);
+}
+
+#[test]
+fn merge_reader_test() {
+    use crate::mpmc::MergeReader;
+    use crate::MergeOrder;
+
+    let event0 = EventQueue::<usize>::new();
+    let event1 = EventQueue::<usize>::new();
+    let new_readers = || vec![EventReader::new(&event0), EventReader::new(&event1)];
+    let mut unordered = MergeReader::new(new_readers(), MergeOrder::Unordered);
+    let mut sequence  = MergeReader::new(new_readers(), MergeOrder::Sequence);
+
+    event0.extend([0, 2, 4]);
+    event1.extend([1, 3]);
+
+    assert_equal(consume_copies(&mut unordered.iter()), [0, 2, 4, 1, 3]);
+
+    let mut iter = sequence.iter();
+    let mut read = Vec::new();
+    while let Some((source, value)) = iter.next_with_source(){
+        read.push((source, *value));
+    }
+    assert_eq!(read, [(0, 0), (1, 1), (0, 2), (1, 3), (0, 4)]);
+
+    // fused, until refresh
+    event1.push(5);
+    assert!(iter.next().is_none());
+    iter.refresh();
+    assert_equal(consume_copies(&mut iter), [5]);
+    drop(iter);
+
+    let mut readers = sequence.into_readers();
+    assert!(readers[0].iter().next().is_none());
+    assert!(readers[1].iter().next().is_none());
+}
+
+#[test]
+fn merge_reader_timestamp_test() {
+    use crate::mpmc::MergeReader;
+    use crate::MergeOrder;
+
+    struct S{} impl Settings for S{
+        const TIMESTAMPS: bool = true;
+    }
+    let event0 = EventQueue::<usize, S>::new();
+    let event1 = EventQueue::<usize, S>::new();
+    let mut reader = MergeReader::new(
+        vec![EventReader::new(&event0), EventReader::new(&event1)],
+        MergeOrder::Timestamp
+    );
+
+    // Instant may have coarse resolution.
+    let tick = || thread::sleep(std::time::Duration::from_millis(1));
+    event1.push(0); tick();
+    event0.push(1); tick();
+    event1.push(2); tick();
+    event0.push(3);
+    assert_equal(consume_copies(&mut reader.iter()), 0..4);
+}
+
+#[test]
+fn topic_bus_test() {
+    use crate::mpmc::TopicBus;
+
+    let bus = TopicBus::<u32, usize>::new();
+    let mut reader01 = bus.subscribe([0, 1, 0]);
+    let mut reader1  = bus.subscribe([1]);
+    assert_eq!(reader01.keys(), [0, 1]);
+    assert_eq!(bus.topics_count(), 2);
+
+    assert!(bus.push(0, 0));
+    assert!(bus.extend(1, [1, 2]));
+    assert!(!bus.push(2, 3));
+
+    let bus2 = bus.clone();
+    thread::spawn(move || {
+        bus2.push(0, 4);
+    }).join().unwrap();
+
+    let mut read = Vec::new();
+    let mut iter = reader01.iter();
+    while let Some((key, value)) = iter.next_with_key(){
+        read.push((*key, *value));
+    }
+    assert_eq!(read, [(0, 0), (0, 4), (1, 1), (1, 2)]);
+    drop(iter);
+    assert_equal(consume_copies(&mut reader1.iter()), [1, 2]);
+
+    // topic 0 still have reader01
+    assert_eq!(bus.cleanup(), 0);
+    drop(reader01);
+    assert_eq!(bus.cleanup(), 1);
+    assert_eq!(bus.topics_count(), 1);
+    assert!(bus.topic(&0).is_none());
+    assert!(!bus.push(0, 5));
+
+    assert_eq!(bus.topic(&1).unwrap().readers_count(), 1);
+    assert!(bus.remove_topic(&1));
+    assert!(!bus.push(1, 6));
+    assert!(reader1.iter().next().is_none());
+}
+
+#[test]
+fn priority_event_queue_test() {
+    use crate::mpmc::{PriorityEventQueue, PriorityEventReader};
+
+    let queue = PriorityEventQueue::<usize>::new(3);
+    let mut reader = PriorityEventReader::new(&queue);
+
+    queue.push(2, 0);
+    queue.extend(1, [1, 2]);
+    queue.push(2, 3);
+    queue.push(0, 4);
+    assert_eq!(queue.len(), 5);
+
+    let mut iter = reader.iter();
+    assert_eq!(iter.next_with_priority(), Some((0, &4)));
+    assert_eq!(iter.next_with_priority(), Some((1, &1)));
+
+    // visible after refresh
+    queue.push(0, 5);
+    assert_eq!(iter.next_with_priority(), Some((1, &2)));
+    iter.refresh();
+    assert_equal(consume_copies(&mut iter), [5, 0, 3]);
+    drop(iter);
+
+    let queue2 = queue.clone();
+    thread::spawn(move || {
+        queue2.push(1, 6);
+        queue2.push(0, 7);
+    }).join().unwrap();
+    assert_equal(consume_copies(&mut reader.iter()), [7, 6]);
+
+    queue.clear();
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn sharded_event_queue_test() {
+    use crate::mpmc::{ShardedEventQueue, ShardedEventReader};
+
+    let writer_chunk = if cfg!(miri){ 100 } else { 10000 };
+    let queue = ShardedEventQueue::<usize>::new(4);
+    let mut reader = ShardedEventReader::new(&queue);
+
+    // Each producer - own shard.
+    let mut writer_threads = Vec::new();
+    for thread_id in 0..4{
+        let producer = queue.producer();
+        assert_eq!(producer.shard_index(), thread_id);
+        writer_threads.push(thread::spawn(move || {
+            for i in thread_id*writer_chunk..(thread_id+1)*writer_chunk{
+                producer.push(i);
+            }
+        }));
+    }
+    for thread in writer_threads{
+        thread.join().unwrap();
+    }
+    assert_eq!(queue.len(), 4*writer_chunk);
+    for shard in 0..4{
+        assert_eq!(queue.shard(shard).len(), writer_chunk);
+    }
+
+    // FIFO within shard.
+    let mut last: [Option<usize>; 4] = [None; 4];
+    let mut sum = 0;
+    let mut iter = reader.iter();
+    while let Some((shard, &value)) = iter.next_with_shard(){
+        assert_eq!(value / writer_chunk, shard);
+        assert!(last[shard] < Some(value));
+        last[shard] = Some(value);
+        sum += value;
+    }
+    drop(iter);
+    assert_eq!(sum, (0..4*writer_chunk).sum());
+
+    // Round-robin.
+    assert_eq!(queue.producer().shard_index(), 0);
+
+    queue.clear();
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn filtered_reader_test() {
+    use crate::mpmc::FilteredReader;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let factory = SubscriberFactory::new(&event);
+    let mut even = factory.subscribe_with_filter(|value: &usize| value & 1 == 0);
+    let mut big  = FilteredReader::new(&event, |value: &usize| *value >= 10);
+
+    event.extend(0..12);
+    assert_equal(consume_copies(&mut even.iter()), (0..12).step_by(2));
+    assert_eq!(big.iter().fold(0, |acc, value| acc + value), 10 + 11);
+
+    // skipped items marked read
+    assert_eq!(event.total_capacity(), 4);
+
+    event.extend(12..16);
+    let mut sum = 0;
+    even.iter().for_each(|value| sum += value);
+    assert_eq!(sum, 12 + 14);
+
+    let mut reader = big.into_reader();
+    assert_equal(consume_copies(&mut reader.iter()), 12..16);
+}
+
+#[test]
+fn mapped_reader_test() {
+    use crate::mpmc::MappedReader;
+
+    enum Event{ Value(usize), Other }
+    let event = EventQueue::<Event>::new();
+    let mut values = MappedReader::new(&event, |event: &Event| match event{
+        Event::Value(value) => Some(value),
+        Event::Other => None,
+    });
+
+    event.extend((0..8).map(|i| if i % 3 == 0 { Event::Other } else { Event::Value(i) }));
+    assert_equal(consume_copies(&mut values.iter()), [1, 2, 4, 5, 7]);
+
+    event.extend([Event::Value(8), Event::Other, Event::Value(9)]);
+    assert_eq!(values.iter().fold(0, |acc, value| acc + value), 8 + 9);
+
+    event.push(Event::Other);
+    assert!(values.iter().next().is_none());
+    assert!(values.into_reader().iter().next().is_none());
+}
+
+#[test]
+fn drain_test() {
+    fn test<S: Settings>(destruct_counter: &AtomicUsize) {
+        let on_destroy = ||{destruct_counter.fetch_add(1, Ordering::Relaxed);};
+        let destructed = || destruct_counter.swap(0, Ordering::Relaxed);
+
+        {
+            let event = EventQueue::<_, S>::new();
+            let mut reader = EventReader::new(&event);
+            event.extend((0..6).map(|i| Data::from(i, on_destroy)));
+            skip(&mut reader.iter(), 1);
+
+            // Partially consumed
+            {
+                let mut drain = reader.drain().unwrap();
+                assert_eq!(drain.next().unwrap().id, 1);
+                assert_eq!(drain.next().unwrap().id, 2);
+            }
+            // Read item 0 dropped at drain start.
+            assert_eq!(destructed(), 3);
+            assert_eq!(event.len(), 3);
+            assert_eq!(reader.rewind(5), 0);
+
+            assert_equal(reader.drain().unwrap().map(|data| data.id), 3..6);
+            assert_eq!(destructed(), 3);
+            assert_eq!(event.len(), 0);
+            assert_eq!(reader.take_missed_count(), 0);
+
+            event.extend((6..10).map(|i| Data::from(i, on_destroy)));
+            {
+                let _other = EventReader::new(&event);
+                assert!(reader.drain().is_none());
+            }
+            assert_equal(consume_mapped(&mut reader.iter(), |data| data.id), 6..10);
+        }
+        // Each item destructed exactly once.
+        assert_eq!(destructed(), 4);
+    }
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    struct Spilled{} impl Settings for Spilled{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const SPILL_THRESHOLD: usize = 0;
+    }
+    struct Never{} impl Settings for Never{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const CLEANUP: CleanupMode = CleanupMode::Never;
+    }
+    let destruct_counter = AtomicUsize::new(0);
+    test::<S>(&destruct_counter);
+    test::<Spilled>(&destruct_counter);
+    test::<Never>(&destruct_counter);
+
+    // Concurrent writer
+    let event = EventQueue::<String, S>::new();
+    let mut reader = EventReader::new(&event);
+    let writer = {
+        let event = event.clone();
+        thread::spawn(move || {
+            for i in 0..200 {
+                event.push(i.to_string());
+            }
+        })
+    };
+    let mut drained = Vec::new();
+    while drained.len() < 200 {
+        drained.extend(reader.drain().unwrap().map(|value| value.parse::<usize>().unwrap()));
+    }
+    writer.join().unwrap();
+    assert_equal(drained, 0..200);
+}
+
+#[test]
+fn try_into_iter_test() {
+    let destruct_counter = AtomicUsize::new(0);
+    let destruct_counter_ref = &destruct_counter;
+    let on_destroy = ||{destruct_counter_ref.fetch_add(1, Ordering::Relaxed);};
+    let destructed = || destruct_counter.swap(0, Ordering::Relaxed);
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const CLEANUP: CleanupMode = CleanupMode::Never;
+    }
+
+    let event = EventQueue::<_, S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend((0..10).map(|i| Data::from(i, on_destroy)));
+    event.truncate_front(7);
+    {
+        // Drained items are not dropped twice.
+        let mut drain = reader.drain().unwrap();
+        assert_eq!(drain.next().unwrap().id, 3);
+    }
+    // With truncated ones.
+    assert_eq!(destructed(), 4);
+
+    let event = event.try_into_iter().err().unwrap();
+    let clone = event.clone();
+    let event = event.try_into_iter().err().unwrap();
+    drop(clone);
+    let event = event.try_into_iter().err().unwrap();
+    drop(reader);
+
+    let mut iter = event.try_into_iter().ok().unwrap();
+    assert_eq!(iter.next().unwrap().id, 4);
+    assert_eq!(iter.next().unwrap().id, 5);
+    assert_eq!(destructed(), 2);
+    drop(iter);
+    assert_eq!(destructed(), 4);
+
+    // Truncated, not drained
+    let event = EventQueue::<_, S>::new();
+    event.extend((0..6).map(|i| Data::from(i, on_destroy)));
+    event.truncate_front(1);
+    assert_equal(event.try_into_iter().ok().unwrap().map(|data| data.id), [5]);
+    assert_eq!(destructed(), 6);
+}
+
+#[test]
+fn snapshot_vec_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    assert!(event.snapshot_vec().is_empty());
+
+    let mut reader = EventReader::new(&event);
+    event.extend(0..10);
+    assert_eq!(event.snapshot_vec(), (0..10).collect::<Vec<_>>());
+    // Readers unaffected.
+    assert_equal(consume_copies(&mut reader.iter()), 0..10);
+    // Read chunks freed.
+    assert_eq!(event.snapshot_vec(), [8, 9]);
+
+    event.extend(10..12);
+    event.truncate_front(3);
+    assert_eq!(event.snapshot_vec(), [9, 10, 11]);
+
+    {
+        let mut batch = event.write_batch();
+        batch.push(12);
+        drop(batch);
+    }
+    assert_eq!(event.snapshot_vec().len(), event.len());
+
+    event.clear();
+    assert!(event.snapshot_vec().is_empty());
+    event.push(13);
+    assert_eq!(event.snapshot_vec(), [13]);
+}
+
+#[test]
+fn iter_rollback_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..10);
+    skip(&mut reader.iter(), 2);
+
+    let mut iter = reader.iter();
+    let result = iter.try_for_each(|value| if *value == 7 { Err(()) } else { Ok(()) });
+    assert!(result.is_err());
+    iter.rollback();
+    assert_eq!(reader.position().sequence(), 2);
+    assert_eq!(event.total_capacity(), 12);
+
+    // Nothing entered - nothing to exit.
+    reader.iter().rollback();
+
+    assert_equal(consume_copies(&mut reader.iter()), 2..10);
+    assert_eq!(event.total_capacity(), 4);
+    assert_eq!(event.diagnostics().chunks.len(), 1);
+}
+
+#[test]
+fn reader_options_test() {
+    use crate::ReaderOptions;
+    use std::time::Duration;
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let no_cleanup = ReaderOptions{ cleanup: false, ..Default::default() };
+    let mut reader = EventReader::with_options(&event, no_cleanup.clone());
+
+    event.extend(0..10);
+    assert_equal(consume_copies(&mut reader.iter()), 0..10);
+    // Read, but not freed.
+    assert_eq!(event.total_capacity(), 12);
+    event.cleanup();
+    assert_eq!(event.total_capacity(), 4);
+
+    // Fork keeps options.
+    let mut fork = reader.fork();
+    event.extend(10..18);
+    assert_equal(consume_copies(&mut fork.iter()), 10..18);
+    assert_equal(consume_copies(&mut reader.iter()), 10..18);
+    assert_eq!(event.total_capacity(), 12);
+
+    // Housekeeping reader frees chunks read by all.
+    let mut housekeeper = EventReader::new(&event);
+    event.extend(18..22);
+    consume_copies(&mut reader.iter());
+    consume_copies(&mut fork.iter());
+    assert_equal(consume_copies(&mut housekeeper.iter()), 18..22);
+    assert_eq!(event.total_capacity(), 4);
+
+    // Name and ttl.
+    let named = EventReader::with_options(&event, ReaderOptions{
+        name: Some("named".into()),
+        ttl: Some(Duration::from_secs(60)),
+        ..no_cleanup
+    });
+    assert_eq!(named.name(), Some("named"));
+    assert!(!named.is_expired());
+}
+
+#[test]
+fn cleanup_thread_test() {
+    use std::time::{Duration, Instant};
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const CLEANUP: CleanupMode = CleanupMode::Never;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let cleanup_thread = event.spawn_cleanup_thread(Duration::from_millis(1));
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..10);
+    assert_equal(consume_copies(&mut reader.iter()), 0..10);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while event.total_capacity() != 4 {
+        assert!(Instant::now() < deadline);
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // Stops on drop.
+    cleanup_thread.stop();
+    event.extend(10..20);
+    assert_equal(consume_copies(&mut reader.iter()), 10..20);
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(event.total_capacity(), 12);
+
+    // Finishes with queue.
+    let cleanup_thread = event.spawn_cleanup_thread(Duration::from_millis(1));
+    drop(reader);
+    drop(event);
+    while !cleanup_thread.is_finished() {
+        assert!(Instant::now() < deadline);
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+#[cfg(not(miri))]
+fn cleanup_thread_into_iter_test() {
+    use std::time::Duration;
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const CLEANUP: CleanupMode = CleanupMode::Never;
+    }
+    let event = EventQueue::<String, S>::new();
+    event.extend((0..64).map(|i| i.to_string()));
+    let cleanup_thread = event.spawn_cleanup_thread(Duration::from_millis(1));
+
+    // Cleanup thread would free chunks under IntoIter.
+    let event = event.try_into_iter().err().unwrap();
+
+    cleanup_thread.stop();
+    let mut len = 0;
+    for (i, value) in event.try_into_iter().ok().unwrap().enumerate(){
+        assert_eq!(value, i.to_string());
+        thread::sleep(Duration::from_millis(5));
+        len += 1;
+    }
+    assert_eq!(len, 64);
+}
+
+#[test]
+fn peek_iter_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert!(reader.peek_iter().next().is_none());
+
+    event.extend(0..10);
+    skip(&mut reader.iter(), 2);
+
+    assert_equal(consume_copies(&mut reader.peek_iter()), 2..10);
+    assert_equal(consume_copies(&mut reader.peek_iter()), 2..10);
+    assert_eq!(reader.position().sequence(), 2);
+    assert_eq!(event.total_capacity(), 12);
+    let diagnostics = event.diagnostics();
+    for chunk in &diagnostics.chunks[1..] {
+        assert_eq!(chunk.readers_entered, chunk.read_completely_times);
+    }
+
+    assert_equal(consume_copies(&mut reader.iter()), 2..10);
+    assert_eq!(event.total_capacity(), 4);
+    assert!(reader.peek_iter().next().is_none());
+}
+
+#[test]
+fn committed_reader_test() {
+    use crate::mpmc::CommittedReader;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = CommittedReader::new(&event);
+
+    event.extend(0..10);
+    assert_equal(consume_copies(&mut reader.iter()), 0..10);
+    // Not committed - memory held.
+    assert_eq!(event.total_capacity(), 12);
+    assert_equal(consume_copies(&mut reader.iter()), 0..10);
+
+    // Partial batch.
+    skip(&mut reader.iter(), 5);
+    reader.commit();
+    assert_eq!(reader.position().sequence(), 5);
+    assert_eq!(event.total_capacity(), 8);
+    assert_equal(consume_copies(&mut reader.iter()), 5..10);
+
+    event.push(10);
+    assert_equal(consume_copies(&mut reader.iter()), 5..11);
+    reader.commit();
+    assert_eq!(event.readers_count(), 1);
+    assert!(reader.iter().next().is_none());
+
+    let mut reader = reader.into_reader();
+    event.push(11);
+    assert_equal(consume_copies(&mut reader.iter()), [11]);
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn sender_sink_test() {
+    use futures_sink::Sink;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use std::task::Wake;
+
+    struct NoopWaker;
+    impl Wake for NoopWaker{
+        fn wake(self: Arc<Self>) {}
+    }
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+
+    struct S{} impl Settings for S{
+        const PUBLISH_BATCH: u32 = 4;
+    }
+    let (mut sender, subscribers) = EventQueue::<usize, S>::new().into_channel();
+    let mut reader = subscribers.subscribe();
+
+    let mut sink = Pin::new(&mut sender);
+    for i in 0..2{
+        assert!(matches!(sink.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(()))));
+        sink.as_mut().start_send(i).unwrap();
+    }
+    assert!(reader.iter().next().is_none());
+
+    assert!(matches!(sink.as_mut().poll_flush(&mut cx), Poll::Ready(Ok(()))));
+    assert_equal(consume_copies(&mut reader.iter()), 0..2);
+
+    sink.as_mut().start_send(2).unwrap();
+    assert!(matches!(sink.as_mut().poll_close(&mut cx), Poll::Ready(Ok(()))));
+    assert_equal(consume_copies(&mut reader.iter()), [2]);
+}
+
+#[test]
+fn recv_test() {
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use std::task::Wake;
+
+    struct ThreadWaker(std::thread::Thread);
+    impl Wake for ThreadWaker{
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    fn block_on<F: Future>(future: F) -> F::Output{
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop{
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx){
+                return output;
+            }
+            std::thread::park();
+        }
+    }
+
+    struct S{} impl Settings for S{
+        const NOTIFY: bool = true;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    let count = if cfg!(miri){ 10 } else { 1000 };
+
+    let event2 = event.clone();
+    let writer = thread::spawn(move || {
+        for i in 0..count{
+            event2.push(i);
+        }
+    });
+    for i in 0..count{
+        assert_eq!(block_on(reader.recv()), Ok(i));
+    }
+    writer.join().unwrap();
+
+    // cancelled
+    {
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(reader.recv());
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+    }
+    event.push(count);
+    assert_eq!(block_on(reader.recv()), Ok(count));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tokio_recv_test() {
+    use std::time::Duration;
+
+    struct S{} impl Settings for S{
+        const NOTIFY: bool = true;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    let count = if cfg!(miri){ 10 } else { 1000 };
+
+    let event2 = event.clone();
+    let writer = tokio::spawn(async move {
+        for i in 0..count{
+            event2.push(i);
+            if i & 15 == 0{
+                tokio::task::yield_now().await;
+            }
+        }
+    });
+    for i in 0..count{
+        assert_eq!(reader.recv().await, Ok(i));
+    }
+    writer.await.unwrap();
+
+    // cancelled recv does not lose items
+    assert!(tokio::time::timeout(Duration::from_millis(1), reader.recv()).await.is_err());
+    event.push(count);
+    assert_eq!(reader.try_recv(), Ok(Some(count)));
+    assert_eq!(reader.try_recv(), Ok(None));
+
+    // poll_recv
+    let event2 = event.clone();
+    tokio::spawn(async move {
+        event2.push(count + 1);
+    });
+    let value = core::future::poll_fn(|cx| reader.poll_recv(cx)).await;
+    assert_eq!(value, Ok(count + 1));
+}
+
+#[test]
+#[cfg(all(unix, not(miri)))]
+fn readiness_test() {
+    use crate::readiness::Readiness;
+    use std::io::{ErrorKind, Read};
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+    use std::os::unix::net::UnixStream;
+    use std::mem::ManuallyDrop;
+
+    struct S{} impl Settings for S{
+        const NOTIFY: bool = true;
+    }
+    let event1 = EventQueue::<usize, S>::new();
+    let event2 = EventQueue::<usize, S>::new();
+    let mut reader1 = EventReader::new(&event1);
+    let reader2 = EventReader::new(&event2);
+
+    let readiness = Readiness::new().unwrap();
+    // Readable, if we can read from it. Consumes.
+    let fd = ManuallyDrop::new(unsafe{ UnixStream::from_raw_fd(readiness.as_raw_fd()) });
+    let is_readable = || {
+        match (&*fd).read(&mut [0u8; 64]){
+            Ok(_) => true,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => false,
+            Err(e) => panic!("{}", e),
+        }
+    };
+
+    readiness.arm(&[&reader1, &reader2]).unwrap();
+    assert!(!is_readable());
+
+    let event = event1.clone();
+    thread::spawn(move || {
+        event.push(0);
+    }).join().unwrap();
+    assert!(is_readable());
+
+    // still have unread item
+    readiness.arm(&[&reader1, &reader2]).unwrap();
+    assert!(is_readable());
+
+    assert_equal(consume_copies(&mut reader1.iter()), [0]);
+    readiness.arm(&[&reader1, &reader2]).unwrap();
+    assert!(!is_readable());
+    event2.push(1);
+    assert!(is_readable());
+}
+
+#[test]
+fn write_stats_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut stats = String::new();
+    event.write_stats(&mut stats).unwrap();
+    assert_eq!(stats, "chunks=1 capacity=4 events=0 readers=0 max_lag<=0");
+
+    let mut reader = EventReader::new(&event);
+    event.extend(0..12);
+    skip(&mut reader.iter(), 5);
+
+    stats.clear();
+    event.write_stats(&mut stats).unwrap();
+    assert_eq!(stats, "chunks=2 capacity=8 events=8 readers=1 max_lag<=8");
+}
+
+#[test]
+fn debug_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const RECYCLE_POOL_SIZE: usize = 1;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend(0..12);
+    skip(&mut reader.iter(), 5);
+
+    assert_eq!(format!("{:?}", event.fmt_chunks()), "[\
+        Chunk { id: 1, capacity: 4, len: 4, epoch: 0, readers_entered: 1, read_completely_times: 0, recycled: false }, \
+        Chunk { id: 2, capacity: 4, len: 4, epoch: 0, readers_entered: 0, read_completely_times: 0, recycled: false }, \
+        Chunk { capacity: 4, recycled: true }\
+    ]");
+    assert!(format!("{:?}", event).starts_with("EventQueue { readers: 1, total_capacity: 8, unpublished: 0, chunks: [Chunk { id: 1,"));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn snapshot_test() {
+    use crate::Position;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const PUBLISH_BATCH: u32 = 4;
+    }
+
+    let event = EventQueue::<String, S>::new();
+    let _reader = EventReader::new(&event);
+    event.extend((0..10).map(|i| i.to_string()));
+    event.truncate_front(3);
+    // Unpublished too.
+    event.push(String::from("10"));
+
+    let mut bytes = Vec::new();
+    event.snapshot_serialize(&mut serde_cbor::Serializer::new(&mut bytes)).unwrap();
+
+    #[derive(serde::Deserialize, PartialEq, Debug)]
+    struct Snapshot{ start: u64, events: Vec<String> }
+    assert_eq!(
+        serde_cbor::from_slice::<Snapshot>(&bytes).unwrap(),
+        Snapshot{ start: 7, events: vec!["7".into(), "8".into(), "9".into(), "10".into()] }
+    );
+
+    let restored = EventQueue::<String, S>::snapshot_deserialize(
+        &mut serde_cbor::Deserializer::from_slice(&bytes)
+    ).unwrap();
+    assert_eq!(restored.len(), 4);
+    assert_eq!(restored.end_position(), Position::from_sequence(11));
+
+    let mut reader = EventReader::new(&restored);
+    assert_eq!(reader.rewind(10), 4);
+    restored.push(String::from("11"));
+    restored.flush();
+    assert_equal(consume_mapped(&mut reader.iter(), |s| s.clone()), ["7", "8", "9", "10", "11"]);
+}
+
+#[test]
+#[cfg(feature = "wal")]
+fn wal_test() {
+    use crate::wal::{WalSegment, WalSink};
+    use crate::Position;
+    use std::io;
+    use std::sync::Mutex;
+
+    #[derive(PartialEq, Debug)]
+    enum Record{ Segment(u64, u64), Value(usize) }
+
+    #[derive(Clone, Default)]
+    struct Sink{ records: Arc<Mutex<Vec<Record>>>, fail_on: Option<usize> }
+    impl WalSink<usize> for Sink{
+        fn start_segment(&mut self, segment: WalSegment) -> io::Result<()> {
+            self.records.lock().unwrap().push(Record::Segment(segment.chunk_id, segment.first.sequence()));
+            Ok(())
+        }
+        fn append(&mut self, value: &usize) -> io::Result<()> {
+            if self.fail_on == Some(*value){
+                return Err(io::Error::other("fail"));
+            }
+            self.records.lock().unwrap().push(Record::Value(*value));
+            Ok(())
+        }
+    }
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const PUBLISH_BATCH: u32 = 2;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let _reader = EventReader::new(&event);
+    event.push(0);
+
+    let sink = Sink{ fail_on: Some(12), ..Default::default() };
+    assert!(event.set_wal_sink(Some(Box::new(sink.clone()))).is_none());
+    event.push(1);
+    event.extend(2..6);
+    event.extend_from_slice(&[6, 7, 8]);
+    event.write_batch().push(9);
+    event.push_repeated(10, 2);
+    assert!(event.take_wal_error().is_none());
+    event.sync_wal().unwrap();
+
+    use Record::*;
+    assert_eq!(*sink.records.lock().unwrap(), [
+        Segment(0, 1), Value(1), Value(2), Value(3),
+        Segment(1, 4), Value(4), Value(5), Value(6), Value(7),
+        Segment(2, 8), Value(8), Value(9), Value(10), Value(10),
+    ]);
+
+    // Error detaches sink.
+    event.extend(12..14);
+    assert_eq!(event.take_wal_error().unwrap().to_string(), "fail");
+    assert!(event.take_wal_error().is_none());
+    assert!(event.set_wal_sink(None).is_none());
+    assert_eq!(sink.records.lock().unwrap()[14..], [Segment(3, 12)]);
+    assert_eq!(event.end_position(), Position::from_sequence(14));
+}
+
+#[test]
+#[cfg(feature = "wal")]
+fn wal_file_test() {
+    use crate::wal::{FileWalSink, replay};
+    use crate::Position;
+    use std::convert::TryInto;
+    use std::io::Write;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let dir = std::env::temp_dir().join(format!("rc_event_queue_wal_test_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let encode = |value: &u32, out: &mut Vec<u8>| out.extend_from_slice(&value.to_le_bytes());
+
+    let event = EventQueue::<u32, S>::new();
+    event.set_wal_sink(Some(Box::new(FileWalSink::new(&dir, encode).unwrap())));
+    event.extend(0..10);
+    event.sync_wal().unwrap();
+    // Segment per chunk.
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+
+    // Torn tail record.
+    std::fs::OpenOptions::new().append(true)
+        .open(dir.join(format!("{:020}.wal", 8))).unwrap()
+        .write_all(&[4, 0, 0, 0, 1]).unwrap();
+
+    let mut replayed = Vec::new();
+    replay(&dir, |position, record| {
+        replayed.push((position, u32::from_le_bytes(record.try_into().unwrap())));
+    }).unwrap();
+    assert_equal(replayed, (0..10).map(|i| (Position::from_sequence(i as u64), i)));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn diagnostics_test() {
+    use crate::{ChunkDiagnostics, Position, QueueDiagnostics};
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const RECYCLE_POOL_SIZE: usize = 1;
+    }
+    let chunk = |id, len, readers_entered, read_completely_times| ChunkDiagnostics{
+        id, capacity: 4, len, epoch: 0, readers_entered, read_completely_times
+    };
+
+    let event = EventQueue::<usize, S>::new();
+    assert_eq!(event.diagnostics(), QueueDiagnostics{
+        chunks: vec![chunk(0, 0, 0, 0)],
+        readers_count: 0,
+        readers: vec![],
+        start_position: None,
+        unpublished_len: 0,
+        free_chunks_count: 0,
+        free_chunks_capacity: 0,
+    });
+
+    let mut reader = EventReader::new(&event);
+    event.extend(0..12);
+    skip(&mut reader.iter(), 5);
+    assert_eq!(event.diagnostics(), QueueDiagnostics{
+        chunks: vec![chunk(1, 4, 1, 0), chunk(2, 4, 0, 0)],
+        readers_count: 1,
+        readers: vec![],
+        start_position: None,
+        unpublished_len: 0,
+        free_chunks_count: 1,
+        free_chunks_capacity: 4,
+    });
+
+    event.truncate_front(2);
+    let diagnostics = event.diagnostics();
+    assert_eq!(diagnostics.start_position, Some(Position::from_sequence(10)));
+    assert!(diagnostics.chunks.iter().all(|chunk| chunk.epoch == 1));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn named_readers_test() {
+    use crate::dynamic_chunk::DynamicChunk;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let anonymous = EventReader::new(&event);
+    let mut renderer = EventReader::new_named(&event, "renderer");
+    assert_eq!(renderer.name(), Some("renderer"));
+    assert_eq!(anonymous.name(), None);
+
+    event.extend(0..10);
+    skip(&mut renderer.iter(), 5);
+
+    let readers = event.diagnostics().readers;
+    assert_eq!(readers.len(), 1);
+    let reader = &readers[0];
+    assert_eq!(reader.name.as_deref(), Some("renderer"));
+    assert!(!reader.expired);
+    assert_eq!(reader.chunk_id, Some(1));
+    assert_eq!(reader.lag, 6);
+    let chunk_size = DynamicChunk::<usize, crate::mpmc::BS<S>>::allocation_size(4);
+    assert_eq!(reader.pinned_bytes, 2 * chunk_size);
+
+    let mut stats = String::new();
+    event.write_stats(&mut stats).unwrap();
+    assert!(stats.contains(" renderer.lag<=6 "), "{}", stats);
+
+    drop(renderer);
+    assert!(event.diagnostics().readers.is_empty());
+}
+
+#[test]
+fn lag_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert_eq!(reader.lag(), 0);
+
+    event.extend(0..10);
+    assert_eq!(reader.lag(), 10);
+
+    skip(&mut reader.iter(), 5);
+    assert_eq!(reader.lag(), 5);
+
+    event.truncate_front(2);
+    assert_eq!(reader.lag(), 2);
+
+    event.clear();
+    assert_eq!(reader.lag(), 0);
+}
+
+#[test]
+fn stats_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const STATS: bool = true;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert_eq!(event.stats(), Stats{chunks_allocated: 1, ..Default::default()});
+
+    for i in 0..3 {
+        event.push(i);
+    }
+    event.extend(3..10);
+    assert_eq!(event.stats(), Stats{pushed: 10, chunks_allocated: 3, ..Default::default()});
+
+    consume_copies(&mut reader.iter());
+    let stats = event.stats();
+    assert_eq!(stats.chunks_freed, 2);
+    // All chunks are the same size.
+    assert_eq!(stats.bytes_freed, 2 * EventQueue::<usize, S>::new().bytes_allocated());
+    assert!(stats.cleanups >= 1);
+
+    // disabled
+    let event = EventQueue::<usize>::new();
+    event.extend(0..10);
+    assert_eq!(event.stats(), Stats::default());
+}
+
+#[test]
+#[cfg(feature = "metrics")]
+fn record_metrics_test() {
+    use metrics::{Counter, CounterFn, Gauge, GaugeFn, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    type Values = Arc<Mutex<HashMap<String, f64>>>;
+
+    /// Stores last value by `name{queue}`.
+    struct Value{ key: String, values: Values }
+    impl CounterFn for Value{
+        fn increment(&self, _: u64) { unimplemented!() }
+        fn absolute(&self, value: u64) { self.values.lock().unwrap().insert(self.key.clone(), value as f64); }
+    }
+    impl GaugeFn for Value{
+        fn increment(&self, _: f64) { unimplemented!() }
+        fn decrement(&self, _: f64) { unimplemented!() }
+        fn set(&self, value: f64) { self.values.lock().unwrap().insert(self.key.clone(), value); }
+    }
+
+    struct TestRecorder{ values: Values }
+    impl TestRecorder{
+        fn value(&self, key: &Key) -> Arc<Value>{
+            let labels: Vec<_> = key.labels().map(|label| label.value().to_string()).collect();
+            let key = format!("{}{{{}}}", key.name(), labels.join(","));
+            Arc::new(Value{key, values: self.values.clone()})
+        }
+    }
+    impl Recorder for TestRecorder{
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter { Counter::from_arc(self.value(key)) }
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge { Gauge::from_arc(self.value(key)) }
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram { Histogram::noop() }
+    }
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const STATS: bool = true;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    let _reader2 = EventReader::new(&event);
+    event.extend(0..10);
+    consume_copies(&mut reader.iter());
+    event.cleanup_force(1);
+
+    let recorder = TestRecorder{ values: Default::default() };
+    metrics::with_local_recorder(&recorder, || event.record_metrics("events"));
+
+    let values = recorder.values.lock().unwrap();
+    let stats = event.stats();
+    assert_eq!(values["rc_event_queue_items_pushed{events}"], 10.0);
+    assert_eq!(values["rc_event_queue_cleanup_count{events}"], stats.cleanups as f64);
+    assert_eq!(values["rc_event_queue_reclaimed_bytes{events}"], stats.bytes_freed as f64);
+    assert!(stats.bytes_freed > 0);
+    // First chunk held by reader2.
+    assert_eq!(values["rc_event_queue_chunks_live{events}"], 2.0);
+    assert_eq!(values["rc_event_queue_readers{events}"], 2.0);
+}
+
+#[test]
+fn chunks_count_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const RECYCLE_POOL_SIZE: usize = 1;
+    }
+    let event = EventQueue::<usize, S>::new();
+    let chunks = || event.diagnostics().chunks.len();
+    assert_eq!(event.chunks_count(), 1);
+
+    let mut reader = EventReader::new(&event);
+    let reader2 = EventReader::new(&event);
+    event.extend(0..10);
+    assert_eq!(event.chunks_count(), 3);
+
+    // Out of order.
+    consume_copies(&mut reader.iter());
+    event.cleanup_force(1);
+    assert_eq!(event.chunks_count(), 2);
+    assert_eq!(event.chunks_count(), chunks());
+
+    event.clear();
+    event.extend(10..20);
+    assert_eq!(event.chunks_count(), chunks());
+    drop(reader2);
+    consume_copies(&mut reader.iter());
+    // Recycled chunk not counted.
+    assert_eq!(event.chunks_count(), 1);
+    assert_eq!(event.diagnostics().free_chunks_count, 1);
+}
+
+#[test]
+fn len_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert!(event.is_empty());
+
+    event.extend(0..10);
+    assert_eq!(event.len(), 10);
+
+    event.truncate_front(3);
+    assert_eq!(event.len(), 3);
+
+    event.push(10);
+    assert_eq!(event.len(), 4);
+
+    // Read items counted, until their chunk cleaned up. Chunk of 7 freed on read,
+    // 8..=10 stay in the last chunk.
+    consume_copies(&mut reader.iter());
+    assert_eq!(event.len(), 3);
+
+    event.clear();
+    assert!(event.is_empty());
+}
+
+#[test]
+fn len_lock_free_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.push(0);
+    event.extend_from_slice(&[1, 2]);
+    event.try_extend(3..6).unwrap();
+    assert_eq!(event.len(), 6);
+
+    // Chunk of the first item freed.
+    skip(&mut reader.iter(), 5);
+    assert_eq!(event.len(), 2);
+
+    event.truncate_front(1);
+    assert_eq!(event.len(), 1);
+    assert_eq!(reader.unread_estimate(), 1);
+
+    event.clear();
+    assert!(event.is_empty());
+    assert_eq!(reader.unread_estimate(), 0);
+
+    // Never more, than was pushed.
+    let writer = {
+        let event = event.clone();
+        thread::spawn(move || {
+            for i in 0..1000 {
+                event.push(i);
+            }
+        })
+    };
+    let mut max_len = 0;
+    while !writer.is_finished() {
+        let len = event.len();
+        assert!(len >= max_len && len <= 1000);
+        max_len = len;
+    }
+    writer.join().unwrap();
+    assert_eq!(event.len(), 1000);
+}
+
+#[test]
+fn unread_estimate_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert_eq!(reader.unread_estimate(), 0);
+
+    event.extend(0..3);
+    assert_eq!(reader.unread_estimate(), 3);
+
+    event.extend(3..10);
+    assert_eq!(reader.unread_estimate(), 10);
+    assert_eq!(reader.lag(), 10);
+
+    skip(&mut reader.iter(), 9);
+    assert_eq!(reader.unread_estimate(), 1);
+
+    event.clear();
+    assert_eq!(reader.unread_estimate(), 0);
+}
+
+#[test]
+fn has_new_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert!(!reader.has_new());
+
+    event.extend(0..4);
+    assert!(reader.has_new());
+    consume_copies(&mut reader.iter());
+    assert!(!reader.has_new());
+
+    // next chunk
+    event.push(4);
+    assert!(reader.has_new());
+    consume_copies(&mut reader.iter());
+    assert!(!reader.has_new());
+
+    // empty next chunk
+    event.change_chunk_capacity(4);
+    assert!(!reader.has_new());
+
+    event.push(5);
+    event.clear();
+    assert!(!reader.has_new());
+}
+
+#[test]
+#[cfg(not(miri))]
+fn truncate_older_than_test() {
+    use std::time::Duration;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..8);
+    thread::sleep(Duration::from_millis(50));
+    event.extend(8..12);
+
+    event.truncate_older_than(Duration::from_secs(60));
+    event.truncate_older_than(Duration::from_millis(25));
+    assert_equal(consume_copies(&mut reader.iter()), 4..12);
+}
+
+#[test]
+#[cfg(not(miri))]
+fn retention_test() {
+    use std::time::Duration;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const RETENTION: Option<Duration> = Some(Duration::from_millis(25));
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    event.extend(0..8);
+    thread::sleep(Duration::from_millis(50));
+    event.extend(8..16);
+    assert_equal(consume_copies(&mut reader.iter()), 4..16);
+}
+
+#[test]
+fn timestamps_test() {
+    use std::time::Instant;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const TIMESTAMPS: bool = true;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    let before = Instant::now();
+    event.push(0);
+    event.extend(1..10);
+    let after = Instant::now();
+
+    let mut iter = reader.iter();
+    let mut prev_time = before;
+    for i in 0..10 {
+        let (value, time) = iter.next_timestamped().unwrap();
+        assert_eq!(*value, i);
+        assert!(prev_time <= time && time <= after);
+        prev_time = time;
+    }
+    assert!(iter.next_timestamped().is_none());
+}
+
+#[test]
+#[cfg(not(miri))]
+fn ttl_test() {
+    use std::time::Duration;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const TIMESTAMPS: bool = true;
+        const ITEM_TTL: Option<Duration> = Some(Duration::from_millis(25));
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader1 = EventReader::new(&event);
+    let mut reader2 = EventReader::new(&event);
+
+    event.extend(0..6);
+    assert_equal(consume_copies(&mut reader1.iter()), 0..6);
+    thread::sleep(Duration::from_millis(50));
+    event.extend(6..8);
+
+    // Item-granular skip.
+    assert_equal(consume_copies(&mut reader1.iter()), 6..8);
+
+    // Chunk-granular cleanup.
+    assert_eq!(event.len(), 8);
+    event.cleanup();
+    assert_eq!(event.len(), 4);
+    assert_equal(consume_copies(&mut reader2.iter()), 6..8);
+    assert_eq!(reader1.take_missed_count(), 0);
+    assert_eq!(reader2.take_missed_count(), 6);
+}
+
+#[test]
+fn clear_now_test() {
+    let destruct_counter = AtomicUsize::new(0);
+    let destruct_counter_ref = &destruct_counter;
+    let on_destroy = ||{destruct_counter_ref.fetch_add(1, Ordering::Relaxed);};
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<_, S>::new();
+    let mut reader = EventReader::new(&event);
+    for i in 0..6{
+        event.push(Data::from(i, on_destroy));
+    }
+
+    // clear keeps last chunk
+    event.clear();
+    assert_eq!(destruct_counter.load(Ordering::Relaxed), 0);
+
+    // Last chunk unoccupied - dropped right away.
+    // First chunk occupied by reader.
+    event.clear_now();
+    assert_eq!(destruct_counter.load(Ordering::Relaxed), 2);
+
+    assert!(reader.iter().next().is_none());
+    assert_eq!(destruct_counter.load(Ordering::Relaxed), 6);
+}
+
+#[test]
+fn compact_by_key_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<(char, usize), S>::new();
+    let mut behind = EventReader::new(&event);
+    let mut up_to_date = EventReader::new(&event);
+    event.extend([('a', 0), ('b', 1), ('a', 2), ('c', 3), ('b', 4), ('a', 5)]);
+    consume_copies(&mut up_to_date.iter());
+    assert_eq!(event.compact_by_key(|&(key, _)| key), 3);
+    assert_eq!(event.len(), 3);
+
+    let compacted = [('c', 3), ('b', 4), ('a', 5)];
+    assert_equal(consume_copies(&mut behind.iter()), compacted);
+    assert_equal(consume_copies(&mut up_to_date.iter()), compacted);
+
+    // Already compacted.
+    assert_eq!(event.compact_by_key(|&(key, _)| key), 0);
+    event.push(('c', 6));
+    assert_eq!(event.compact_by_key(|&(key, _)| key), 1);
+    assert_equal(consume_copies(&mut behind.iter()), [('b', 4), ('a', 5), ('c', 6)]);
+}
+
+#[test]
+fn on_chunk_free_test() {
+    use crate::FreedChunkInfo;
+    use std::sync::Mutex;
+
+    // fn pointer can not capture, so use static storage.
+    static FREED: Mutex<Vec<FreedChunkInfo>> = Mutex::new(Vec::new());
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 8;
+        const ON_CHUNK_FREE: Option<fn(FreedChunkInfo)> = Some(|info| FREED.lock().unwrap().push(info));
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend(0..10);
+    consume_copies(&mut reader.iter());
+
+    assert_equal(FREED.lock().unwrap().iter().copied(), [
+        FreedChunkInfo{id: 0, capacity: 4, len: 4},
+        FreedChunkInfo{id: 1, capacity: 4, len: 4},
+    ]);
+}
+
+#[test]
+fn preallocated_chunks_test() {
+    use crate::AllocError;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const PREALLOCATED_CHUNKS: u32 = 2;
+        const STATS: bool = true;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert_eq!(event.stats().chunks_allocated, 2);
+
+    event.extend(0..8);
+    assert_eq!(event.try_push(8), Err(AllocError(8)));
+    event.push(9);
+    event.extend(10..12);
+    assert_eq!(event.dropped_count(), 3);
+    assert_equal(consume_copies(&mut reader.iter()), 0..8);
+
+    // Read chunk returned to reserve.
+    assert_eq!(event.try_push(12), Ok(()));
+    assert_equal(consume_copies(&mut reader.iter()), [12]);
+    assert_eq!(event.stats().chunks_allocated, 2);
+}
+
+#[test]
+fn reserve_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 16;
+        const STATS: bool = true;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend(0..2);
+
+    event.reserve(30);
+    let chunks_allocated = event.stats().chunks_allocated;
+    assert!(chunks_allocated > 1);
+    assert_eq!(event.total_capacity(), 4);
+
+    // Already reserved.
+    event.reserve(30);
+    assert_eq!(event.stats().chunks_allocated, chunks_allocated);
+
+    event.extend(2..32);
+    assert_eq!(event.stats().chunks_allocated, chunks_allocated);
+    assert_equal(consume_copies(&mut reader.iter()), 0..32);
+}
+
+#[test]
+fn chunk_allocator_test() {
+    use crate::ChunkAllocator;
+    use std::sync::atomic::AtomicUsize;
+
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 8;
+        const CHUNK_ALLOCATOR: ChunkAllocator = ChunkAllocator{
+            alloc: |layout|{
+                ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+                (ChunkAllocator::GLOBAL.alloc)(layout)
+            },
+            dealloc: |ptr, layout|{
+                ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+                (ChunkAllocator::GLOBAL.dealloc)(ptr, layout)
+            },
+        };
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend(0..20);
+    assert_eq!(ALLOCATED.load(Ordering::Relaxed), event.bytes_allocated());
+
+    consume_copies(&mut reader.iter());
+    assert_eq!(ALLOCATED.load(Ordering::Relaxed), event.bytes_allocated());
+
+    drop(reader);
+    drop(event);
+    assert_eq!(ALLOCATED.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+#[cfg(all(feature = "mmap", unix))]
+fn mmap_chunk_allocator_test() {
+    use crate::{AllocError, ChunkAllocator};
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const CHUNK_ALLOCATOR: ChunkAllocator = ChunkAllocator::MMAP;
+        const RECYCLE_POOL_SIZE: usize = 0;
+    }
+
+    let dir = std::env::temp_dir().join(format!("rc_event_queue_mmap_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    crate::mmap::set_directory(&dir);
+    assert_eq!(crate::mmap::directory(), dir);
+
+    let event = EventQueue::<String, S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend((0..10).map(|i| i.to_string()));
+    // Files unlinked right away.
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    assert_equal(consume_mapped(&mut reader.iter(), |s| s.clone()), (0..10).map(|i| i.to_string()));
+
+    // Directory gone - allocation fails.
+    std::fs::remove_dir(&dir).unwrap();
+    event.extend((10..12).map(|i| i.to_string()));
+    assert_eq!(event.try_push(String::from("12")), Err(AllocError(String::from("12"))));
+    assert_equal(consume_mapped(&mut reader.iter(), |s| s.clone()), ["10", "11"]);
+}
+
+#[test]
+fn fallible_alloc_test() {
+    use crate::{AllocError, ChunkAllocator};
+    use std::sync::atomic::AtomicBool;
+
+    static FAIL: AtomicBool = AtomicBool::new(false);
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const CHUNK_ALLOCATOR: ChunkAllocator = ChunkAllocator{
+            alloc: |layout|{
+                if FAIL.load(Ordering::Relaxed) {
+                    return std::ptr::null_mut();
+                }
+                (ChunkAllocator::GLOBAL.alloc)(layout)
+            },
+            dealloc: ChunkAllocator::GLOBAL.dealloc,
+        };
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    event.extend(0..2);
+
+    FAIL.store(true, Ordering::Relaxed);
+    let mut iter = 2..10;
+    assert_eq!(event.try_extend(iter.by_ref()), Err(AllocError(4)));
+    assert_eq!(iter.next(), Some(5));
+    assert_eq!(event.try_push(4), Err(AllocError(4)));
+    assert_eq!(event.total_capacity(), 4);
+
+    FAIL.store(false, Ordering::Relaxed);
+    assert_eq!(event.try_push(4), Ok(()));
+    assert_equal(consume_copies(&mut reader.iter()), 0..5);
+}
+
+#[test]
+fn try_push_nonblocking_test() {
+    use crate::TryPushError;
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const PREALLOCATED_CHUNKS: u32 = 2;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    assert_eq!(event.try_push_nonblocking(0), Ok(()));
+
+    {
+        let _lock = event.0.list.lock();
+        let error = event.try_push_nonblocking(1);
+        assert_eq!(error, Err(TryPushError::WouldBlock(1)));
+        assert_eq!(error.unwrap_err().into_inner(), 1);
+    }
+
+    event.extend(1..8);
+    assert_eq!(event.try_push_nonblocking(8), Err(TryPushError::Alloc(8)));
+    assert_equal(consume_copies(&mut reader.iter()), 0..8);
+}
+
+#[test]
+fn mt_try_push_nonblocking_test() {
+    use crate::{LockBackend, TryPushError};
+
+    // Writers take Ticket lock only with try_lock - it alone should order them.
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+        const LOCK: LockBackend = LockBackend::Ticket;
+    }
+
+    let writers_count = 3;
+    let per_writer = if cfg!(miri) { 10 } else { 1000 };
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    let writers: Vec<_> = (0..writers_count).map(|writer| {
+        let event = event.clone();
+        thread::spawn(move || {
+            for i in writer*per_writer..(writer+1)*per_writer{
+                let mut value = i;
+                loop{
+                    match event.try_push_nonblocking(value){
+                        Ok(()) => break,
+                        Err(TryPushError::WouldBlock(v)) => {
+                            value = v;
+                            thread::yield_now();
+                        }
+                        Err(TryPushError::Alloc(_)) => unreachable!(),
+                    }
+                }
+            }
+        })
+    }).collect();
+    for writer in writers{
+        writer.join().unwrap();
+    }
+
+    let mut values = consume_copies(&mut reader.iter());
+    values.sort_unstable();
+    assert_equal(values, 0..writers_count*per_writer);
+}
+
+#[cfg(feature = "test_hooks")]
+#[test]
+fn test_hooks_test() {
+    use crate::test_hooks::{set_yield_hook, YieldPoint};
+    use std::cell::RefCell;
+
+    // Hook is global - other tests may run in parallel. Count only ours.
+    thread_local! {
+        static POINTS: RefCell<Vec<YieldPoint>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+
+    set_yield_hook(Some(|point| POINTS.with(|points| points.borrow_mut().push(point))));
+    event.push(0);
+    event.extend(1..5);
+    event.clear();
+    assert_equal(consume_copies(&mut reader.iter()), []);
+    set_yield_hook(None);
+
+    POINTS.with(|points|{
+        assert_equal(points.borrow().iter().copied(), [
+            YieldPoint::Push,
+            YieldPoint::Push,
+            YieldPoint::AddChunk,
+            YieldPoint::SetStartPosition,
+            YieldPoint::ReaderFastForward,
+        ]);
+    });
+}
+
+#[test]
+fn lock_backend_test() {
+    use crate::LockBackend;
+
+    fn mt_push<S: Settings + 'static>(){
+        let writer_chunk = if cfg!(miri){ 100 } else { 10000 };
+        let event = EventQueue::<usize, S>::new();
+        let mut reader = EventReader::new(&event);
+
+        let mut writer_threads = Vec::new();
+        for thread_id in 0..4{
+            let event = event.clone();
+            writer_threads.push(thread::spawn(move || {
+                for i in thread_id*writer_chunk..(thread_id+1)*writer_chunk{
+                    event.push(i);
+                }
+                event.clear();
+            }));
+        }
+        for thread in writer_threads{
+            thread.join().unwrap();
+        }
+        event.extend(0..4*writer_chunk);
+
+        let sum: usize = consume_copies(&mut reader.iter()).iter().sum();
+        assert_eq!(sum, (0..4*writer_chunk).sum());
+    }
+
+    struct Spin{} impl Settings for Spin{ const LOCK: LockBackend = LockBackend::Spin; }
+    struct Yield{} impl Settings for Yield{ const LOCK: LockBackend = LockBackend::Yield; }
+    struct Std{} impl Settings for Std{ const LOCK: LockBackend = LockBackend::Std; }
+    struct Ticket{} impl Settings for Ticket{ const LOCK: LockBackend = LockBackend::Ticket; }
+    mt_push::<Spin>();
+    mt_push::<Yield>();
+    mt_push::<Std>();
+    mt_push::<Ticket>();
+
+    #[cfg(feature = "parking_lot")]
+    {
+        struct ParkingLot{} impl Settings for ParkingLot{ const LOCK: LockBackend = LockBackend::ParkingLot; }
+        struct ParkingLotFair{} impl Settings for ParkingLotFair{ const LOCK: LockBackend = LockBackend::ParkingLotFair; }
+        mt_push::<ParkingLot>();
+        mt_push::<ParkingLotFair>();
+    }
+}
+
+#[test]
+fn write_batch_test() {
+    struct S{} impl Settings for S{
+        const MIN_CHUNK_SIZE: u32 = 4;
+        const MAX_CHUNK_SIZE: u32 = 4;
+    }
+
+    let event = EventQueue::<usize, S>::new();
+    let mut reader = EventReader::new(&event);
+    {
+        let mut batch = event.write_batch();
+        batch.push(0);
+        batch.extend(1..5);
+        assert_eq!(batch.try_push(5), Ok(()));
+        // Full chunk published. Check without read - reading through chunk end
+        // would cleanup, which needs queue lock.
+        assert!(reader.has_new());
+    }
+    assert_equal(consume_copies(&mut reader.iter()), 0..6);
+
+    {
+        let mut batch = event.write_batch();
+        batch.push(6);
+        assert!(!reader.has_new());
+        batch.publish();
+        assert!(reader.has_new());
+        batch.push(7);
+    }
+    assert_equal(consume_copies(&mut reader.iter()), 6..8);
+
+    event.push(8);
+    event.write_batch().extend(9..12);
+    assert_equal(consume_copies(&mut reader.iter()), 8..12);
+}
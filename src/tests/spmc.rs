@@ -89,4 +89,28 @@ for _ in 0..if cfg!(miri){10} else {100} {
         thread.join().unwrap();
     }
 }
-}
\ No newline at end of file
+}
+
+#[test]
+fn std_extend_test(){
+    fn fill(out: &mut impl Extend<usize>){
+        out.extend(0..5);
+    }
+
+    let mut event = EventQueue::<usize>::new();
+    let mut reader = EventReader::new(&mut event);
+
+    fill(&mut event);
+    assert_equal( consume_copies(&mut reader.iter()),  0..5);
+}
+
+#[test]
+#[should_panic]
+fn runtime_settings_on_chunk_read_test(){
+    use crate::CleanupMode;
+    use crate::spmc::RuntimeSettings;
+
+    EventQueue::<usize, RuntimeSettings>::builder()
+        .cleanup(CleanupMode::OnChunkRead)
+        .build();
+}
@@ -1,11 +1,24 @@
-use std::ops::{Add};
+use core::ops::{Add};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// `Instant::now()`, if platform have clock. wasm32-unknown-unknown does not - there it panics.
+#[cfg(feature = "std")]
+#[inline]
+pub fn now() -> Option<Instant> {
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    return None;
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    return Some(Instant::now());
+}
 
 #[inline(always)]
 #[allow(unreachable_code)]
 pub fn bittest_u64<const N: u8>(value: u64) -> bool {
-    #[cfg(not(miri))]
+    #[cfg(all(not(miri), target_arch = "x86_64"))]
     unsafe {
-        #[cfg(target_arch = "x86_64")]
         return core::arch::x86_64::_bittest64(&(value as i64), N as i64) != 0;
     }
 
@@ -26,6 +39,50 @@ pub fn bitset_u64<const N: u8>(mut value: u64, bit: bool) -> u64 {
     value
 }
 
+/// With `cache_padded` feature - occupies whole cache line(s), to avoid false sharing
+/// with neighbours. Without - just `T`.
+///
+/// 128 bytes on x86_64/aarch64 (adjacent line prefetcher / big lines), 64 elsewhere.
+#[cfg_attr(all(feature = "cache_padded", any(target_arch = "x86_64", target_arch = "aarch64")), repr(align(128)))]
+#[cfg_attr(all(feature = "cache_padded", not(any(target_arch = "x86_64", target_arch = "aarch64"))), repr(align(64)))]
+#[derive(Debug, Default)]
+pub struct CachePadded<T>(T);
+
+impl<T> CachePadded<T>{
+    #[inline(always)]
+    pub const fn new(value: T) -> Self{
+        Self(value)
+    }
+}
+
+impl<T> core::ops::Deref for CachePadded<T>{
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> core::ops::DerefMut for CachePadded<T>{
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// `Debug` through closure.
+pub struct DebugFn<F>(pub F);
+
+impl<F> fmt::Debug for DebugFn<F>
+    where F: Fn(&mut fmt::Formatter<'_>) -> fmt::Result
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.0)(f)
+    }
+}
+
 /// Epoch or generation or version.
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct Epoch<T, const MAX: u64> (T)
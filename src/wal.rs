@@ -0,0 +1,232 @@
+//! Write-ahead log of pushed values - for replay after restart.
+//!
+//! Attach [WalSink] with `EventQueue::set_wal_sink`. Each value, which get into queue with
+//! `push`/`extend` (and all their variants), appended to sink under queue lock - in push order.
+//! Log is split into segments, aligned to queue chunks: new segment started each time queue
+//! starts new chunk.
+//!
+//! Only pushes logged - `clear`/`truncate_front`/cleanup are not. Values dropped without readers
+//! (see `Settings::DROP_WITHOUT_READERS`), or which does not fit into preallocated chunks - are
+//! not logged too.
+//!
+//! [FileWalSink] - segment per file, in directory. Read it back with [replay].
+//!
+//! ```
+//! # use rc_event_queue::mpmc::{EventQueue, EventReader};
+//! # use std::convert::TryInto;
+//! # use rc_event_queue::wal::{FileWalSink, replay};
+//! # use rc_event_queue::LendingIterator;
+//! let dir = std::env::temp_dir().join(format!("rc_event_queue_wal_doc_{}", std::process::id()));
+//! # let _ = std::fs::remove_dir_all(&dir);
+//! {
+//!     let event = EventQueue::<u32>::new();
+//!     let sink = FileWalSink::new(&dir, |value: &u32, out: &mut Vec<u8>| {
+//!         out.extend_from_slice(&value.to_le_bytes())
+//!     }).unwrap();
+//!     event.set_wal_sink(Some(Box::new(sink)));
+//!     event.extend(0..10);
+//!     event.sync_wal().unwrap();
+//! }
+//!
+//! // After restart
+//! let event = EventQueue::<u32>::new();
+//! let mut reader = EventReader::new(&event);
+//! replay(&dir, |_position, record| {
+//!     event.push(u32::from_le_bytes(record.try_into().unwrap()));
+//! }).unwrap();
+//! assert_eq!(reader.iter().next(), Some(&0));
+//! assert_eq!(event.len(), 10);
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use crate::position::Position;
+use core::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Log segment description, passed to [WalSink::start_segment].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WalSegment{
+    /// Id of queue chunk, segment's values are stored in.
+    pub chunk_id: u64,
+    /// Position of the first value, which will be appended to segment.
+    pub first: Position,
+}
+
+/// Destination of write-ahead log. See [module](self) documentation.
+///
+/// Called under queue lock - should be fast (buffered).
+/// On any error - sink detached from queue, and error can be taken with
+/// `EventQueue::take_wal_error`.
+pub trait WalSink<T>: Send{
+    /// Called on attach, and each time queue starts new chunk.
+    /// All following values belong to this segment.
+    fn start_segment(&mut self, segment: WalSegment) -> io::Result<()>;
+
+    fn append(&mut self, value: &T) -> io::Result<()>;
+
+    /// Make all appended values durable. Called by `EventQueue::sync_wal`.
+    fn sync(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T, W: WalSink<T> + ?Sized> WalSink<T> for Box<W>{
+    #[inline]
+    fn start_segment(&mut self, segment: WalSegment) -> io::Result<()> {
+        (**self).start_segment(segment)
+    }
+
+    #[inline]
+    fn append(&mut self, value: &T) -> io::Result<()> {
+        (**self).append(value)
+    }
+
+    #[inline]
+    fn sync(&mut self) -> io::Result<()> {
+        (**self).sync()
+    }
+}
+
+const SEGMENT_EXTENSION: &str = "wal";
+
+/// Segment per file, in directory. File named by [WalSegment::first] sequence, so segments
+/// from several runs can live in the same directory, as long as positions continue each other.
+/// Existing segment file with the same name - overwritten.
+///
+/// Each record is `u32` little-endian length, followed by bytes from `encode`.
+/// Previous segment synced to disk, when next one starts.
+pub struct FileWalSink<T, F>
+    where F: FnMut(&T, &mut Vec<u8>)
+{
+    dir: PathBuf,
+    encode: F,
+    file: Option<BufWriter<File>>,
+    buffer: Vec<u8>,
+    _phantom: core::marker::PhantomData<fn(&T)>,
+}
+
+impl<T, F> FileWalSink<T, F>
+    where F: FnMut(&T, &mut Vec<u8>)
+{
+    /// Creates `dir`, if not exists. `encode` appends value's bytes to `Vec`.
+    pub fn new(dir: impl AsRef<Path>, encode: F) -> io::Result<Self>{
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self{
+            dir,
+            encode,
+            file: None,
+            buffer: Vec::new(),
+            _phantom: Default::default(),
+        })
+    }
+
+    #[inline]
+    pub fn dir(&self) -> &Path{
+        &self.dir
+    }
+
+    fn sync_file(&mut self) -> io::Result<()>{
+        if let Some(file) = &mut self.file{
+            file.flush()?;
+            file.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, F> WalSink<T> for FileWalSink<T, F>
+    where F: FnMut(&T, &mut Vec<u8>) + Send
+{
+    fn start_segment(&mut self, segment: WalSegment) -> io::Result<()> {
+        self.sync_file()?;
+        self.file = None;
+
+        let path = self.dir.join(format!("{:020}.{}", segment.first.sequence(), SEGMENT_EXTENSION));
+        self.file = Some(BufWriter::new(File::create(path)?));
+        Ok(())
+    }
+
+    fn append(&mut self, value: &T) -> io::Result<()> {
+        let file = match &mut self.file{
+            Some(file) => file,
+            None => return Err(io::Error::other("no WAL segment started")),
+        };
+
+        self.buffer.clear();
+        (self.encode)(value, &mut self.buffer);
+        let len = u32::try_from(self.buffer.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "WAL record too big"))?;
+        file.write_all(&len.to_le_bytes())?;
+        file.write_all(&self.buffer)
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.sync_file()
+    }
+}
+
+impl<T, F> Drop for FileWalSink<T, F>
+    where F: FnMut(&T, &mut Vec<u8>)
+{
+    fn drop(&mut self) {
+        let _ = self.sync_file();
+    }
+}
+
+/// Segments of [FileWalSink] in `dir`, with their first sequences. Ordered by sequence.
+fn segments(dir: &Path) -> io::Result<Vec<(u64, PathBuf)>>{
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(SEGMENT_EXTENSION){
+            continue;
+        }
+        let first = path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u64>().ok());
+        if let Some(first) = first{
+            segments.push((first, path));
+        }
+    }
+    segments.sort_unstable_by_key(|(first, _)| *first);
+    Ok(segments)
+}
+
+/// Read [FileWalSink] log from `dir`. Calls `f` with position and bytes of each record,
+/// in push order.
+///
+/// Incomplete record at the segment end (crash in the middle of write) - ignored.
+pub fn replay<F>(dir: impl AsRef<Path>, mut f: F) -> io::Result<()>
+    where F: FnMut(Position, &[u8])
+{
+    let mut record = Vec::new();
+    for (first, path) in segments(dir.as_ref())? {
+        let mut file = io::BufReader::new(File::open(path)?);
+        let mut seq = first;
+        loop{
+            let mut len = [0u8; 4];
+            if !read_record_part(&mut file, &mut len)? {
+                break;
+            }
+            record.resize(u32::from_le_bytes(len) as usize, 0);
+            if !read_record_part(&mut file, &mut record)? {
+                break;
+            }
+            f(Position::from_sequence(seq), &record);
+            seq += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Returns false on EOF.
+fn read_record_part(file: &mut impl Read, buf: &mut [u8]) -> io::Result<bool>{
+    match file.read_exact(buf){
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(err) => Err(err),
+    }
+}
@@ -1,3 +1,4 @@
+#![allow(unused_imports, clippy::field_reassign_with_default)]
 extern crate compiletest_rs as compiletest;
 use std::env;
 use std::path::PathBuf;
@@ -0,0 +1,13 @@
+extern crate rc_event_queue;
+
+use rc_event_queue::mpmc::EventQueue;
+use std::rc::Rc;
+use std::thread;
+
+fn main() {
+    let event = EventQueue::<Rc<usize>>::new();
+    thread::spawn(move || { //~ ERROR cannot be sent between threads safely
+                            //~| ERROR cannot be shared between threads safely
+        event.push(Rc::new(1));
+    });
+}
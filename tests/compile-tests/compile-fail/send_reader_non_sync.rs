@@ -0,0 +1,13 @@
+extern crate rc_event_queue;
+
+use rc_event_queue::spmc::{EventQueue, EventReader};
+use std::cell::Cell;
+use std::thread;
+
+fn main() {
+    let mut event = EventQueue::<Cell<usize>>::new();
+    let mut reader = EventReader::new(&mut event);
+    thread::spawn(move || { //~ ERROR cannot be shared between threads safely
+        reader.iter();
+    });
+}